@@ -1,16 +1,361 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use eframe::egui;
+use crate::theme::Role;
 
 /// A common interface for all algorithms to implement
 pub trait Algorithm {
     fn initialize(&mut self);
     fn step(&mut self);
     fn render(&mut self, ui: &mut egui::Ui);
-    fn auto_play(&self) -> bool;
-    fn toggle_auto_traverse(&mut self);
-    fn start(&mut self);
-    fn last_step_time(&self) -> Option<Instant> {
-        self.last_step_time()
-    }
-    fn set_last_step_time(&mut self, time: Option<Instant>);
-}
\ No newline at end of file
+    /// True once `step` has nothing left to do.
+    fn is_finished(&self) -> bool;
+    /// Rewinds to the first step while keeping the user's inputs.
+    fn reset(&mut self);
+    /// What the next `step` will do, without doing it. Quiz mode asks the user to
+    /// predict this; `None` means there is nothing to ask about.
+    fn peek(&self) -> Option<Action> {
+        None
+    }
+    /// Node or bar the user clicked during the last `render`, if any.
+    fn take_click(&mut self) -> Option<usize> {
+        None
+    }
+    /// What each color on this screen means, shown above it.
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        Vec::new()
+    }
+    /// The user's inputs, such as sizes, seeds, edited graphs or typed text, so a
+    /// session can reopen this visualizer on the same scenario. `Null` when there is
+    /// nothing to keep.
+    fn save_inputs(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+    /// Restores what `save_inputs` returned and rewinds to the first step.
+    fn load_inputs(&mut self, _inputs: serde_json::Value) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Shown by visualizers whose `reset` goes back to before the user's operations and
+/// queues them again.
+pub fn rewound_message(operations: usize) -> String {
+    match operations {
+        0 => "Back at the start".to_string(),
+        1 => "Back at the start, step to replay 1 operation".to_string(),
+        n => format!("Back at the start, step to replay {} operations", n),
+    }
+}
+
+/// A predictable effect of one step.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    /// The node that will be visited next.
+    Visit(usize),
+    /// Two positions that will be swapped, in either order.
+    Swap(usize, usize),
+}
+
+impl Action {
+    /// How many clicks it takes to answer.
+    pub fn picks_needed(&self) -> usize {
+        match self {
+            Action::Visit(_) => 1,
+            Action::Swap(_, _) => 2,
+        }
+    }
+
+    pub fn matches(&self, picks: &[usize]) -> bool {
+        match *self {
+            Action::Visit(node) => picks == [node],
+            Action::Swap(i, j) => picks == [i, j] || picks == [j, i],
+        }
+    }
+
+    pub fn question(&self) -> &'static str {
+        match self {
+            Action::Visit(_) => "Click the node that will be visited next",
+            Action::Swap(_, _) => "Click the two bars that will be swapped next",
+        }
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Action::Visit(node) => write!(f, "visit node {}", node),
+            Action::Swap(i, j) => write!(f, "swap positions {} and {}", i, j),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Lifecycle {
+    Idle,
+    Running,
+    Paused,
+    Finished,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LifecycleEvent {
+    Started,
+    Paused,
+    Resumed,
+    Finished { steps: usize },
+    Reset,
+}
+
+impl std::fmt::Display for LifecycleEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LifecycleEvent::Started => write!(f, "Started"),
+            LifecycleEvent::Paused => write!(f, "Paused"),
+            LifecycleEvent::Resumed => write!(f, "Resumed"),
+            LifecycleEvent::Finished { steps } => write!(f, "Algorithm finished after {} steps", steps),
+            LifecycleEvent::Reset => write!(f, "Rewound to the first step"),
+        }
+    }
+}
+
+/// Owns the running algorithm and moves it through idle -> running <-> paused -> finished.
+/// Transitions that don't apply to the current state are ignored, so the UI can't desync.
+pub struct Runner {
+    algorithm: Box<dyn Algorithm>,
+    state: Lifecycle,
+    steps: usize,
+    last_step_time: Option<Instant>,
+    pub step_interval: Duration,
+    events: Vec<LifecycleEvent>,
+}
+
+impl Runner {
+    pub fn new(algorithm: Box<dyn Algorithm>) -> Self {
+        Self {
+            algorithm,
+            state: Lifecycle::Idle,
+            steps: 0,
+            last_step_time: None,
+            step_interval: Duration::from_secs(2),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn algorithm(&mut self) -> &mut dyn Algorithm {
+        self.algorithm.as_mut()
+    }
+
+    pub fn state(&self) -> Lifecycle {
+        self.state
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn can_start(&self) -> bool {
+        self.state == Lifecycle::Idle
+    }
+
+    pub fn can_pause(&self) -> bool {
+        self.state == Lifecycle::Running
+    }
+
+    pub fn can_resume(&self) -> bool {
+        self.state == Lifecycle::Paused
+    }
+
+    pub fn can_step(&self) -> bool {
+        matches!(self.state, Lifecycle::Idle | Lifecycle::Paused) && !self.algorithm.is_finished()
+    }
+
+    pub fn can_reset(&self) -> bool {
+        self.state != Lifecycle::Idle || self.steps > 0
+    }
+
+    pub fn start(&mut self) {
+        if self.can_start() {
+            self.state = Lifecycle::Running;
+            self.last_step_time = None;
+            self.events.push(LifecycleEvent::Started);
+        }
+    }
+
+    pub fn pause(&mut self) {
+        if self.can_pause() {
+            self.state = Lifecycle::Paused;
+            self.events.push(LifecycleEvent::Paused);
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.can_resume() {
+            self.state = Lifecycle::Running;
+            self.last_step_time = None;
+            self.events.push(LifecycleEvent::Resumed);
+        }
+    }
+
+    pub fn step(&mut self) {
+        if self.can_step() {
+            self.advance();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.algorithm.reset();
+        self.state = Lifecycle::Idle;
+        self.steps = 0;
+        self.last_step_time = None;
+        self.events.push(LifecycleEvent::Reset);
+    }
+
+    /// Called once per frame: steps a running algorithm when the interval has passed,
+    /// and reopens a finished one whose inputs were edited so it has work again.
+    pub fn tick(&mut self, now: Instant) {
+        if self.state == Lifecycle::Finished && !self.algorithm.is_finished() {
+            self.state = Lifecycle::Idle;
+            self.steps = 0;
+        }
+        if self.state != Lifecycle::Running {
+            return;
+        }
+        if self.algorithm.is_finished() {
+            self.finish();
+            return;
+        }
+        if self.last_step_time.is_none_or(|t| now.duration_since(t) >= self.step_interval) {
+            self.advance();
+            self.last_step_time = Some(now);
+        }
+    }
+
+    pub fn drain_events(&mut self) -> Vec<LifecycleEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn advance(&mut self) {
+        self.algorithm.step();
+        self.steps += 1;
+        if self.algorithm.is_finished() {
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        self.state = Lifecycle::Finished;
+        self.events.push(LifecycleEvent::Finished { steps: self.steps });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Counts down the steps it has left. The count is shared so a test can hand it
+    /// more work, as editing the inputs of a finished visualizer does.
+    struct Countdown {
+        left: Rc<Cell<usize>>,
+        total: usize,
+    }
+
+    impl Algorithm for Countdown {
+        fn initialize(&mut self) {}
+        fn step(&mut self) {
+            self.left.set(self.left.get().saturating_sub(1));
+        }
+        fn render(&mut self, _ui: &mut egui::Ui) {}
+        fn is_finished(&self) -> bool {
+            self.left.get() == 0
+        }
+        fn reset(&mut self) {
+            self.left.set(self.total);
+        }
+    }
+
+    fn runner(steps: usize) -> (Runner, Rc<Cell<usize>>) {
+        let left = Rc::new(Cell::new(steps));
+        (Runner::new(Box::new(Countdown { left: left.clone(), total: steps })), left)
+    }
+
+    #[test]
+    fn stepping_by_hand_runs_to_the_end_and_reset_rewinds() {
+        let (mut runner, left) = runner(3);
+        assert!(runner.can_start() && runner.can_step());
+        assert!(!runner.can_pause() && !runner.can_resume() && !runner.can_reset());
+
+        // Pausing or resuming something that isn't running does nothing.
+        runner.pause();
+        runner.resume();
+        assert_eq!(runner.state(), Lifecycle::Idle);
+
+        runner.step();
+        assert_eq!((runner.state(), runner.steps()), (Lifecycle::Idle, 1));
+        assert!(runner.can_reset());
+        runner.step();
+        runner.step();
+        assert_eq!((runner.state(), runner.steps()), (Lifecycle::Finished, 3));
+        assert!(!runner.can_step() && !runner.can_start());
+        runner.step();
+        runner.start();
+        assert_eq!((runner.state(), runner.steps()), (Lifecycle::Finished, 3));
+        assert_eq!(runner.drain_events(), vec![LifecycleEvent::Finished { steps: 3 }]);
+
+        runner.reset();
+        assert_eq!((runner.state(), runner.steps(), left.get()), (Lifecycle::Idle, 0, 3));
+        assert_eq!(runner.drain_events(), vec![LifecycleEvent::Reset]);
+        assert!(runner.drain_events().is_empty());
+    }
+
+    #[test]
+    fn ticks_step_on_the_interval_and_stop_while_paused() {
+        let (mut runner, _) = runner(3);
+        runner.step_interval = Duration::from_secs(1);
+        let start = Instant::now();
+
+        // Ticking an idle runner does nothing.
+        runner.tick(start);
+        assert_eq!(runner.steps(), 0);
+
+        runner.start();
+        runner.start();
+        assert!(!runner.can_step());
+        runner.tick(start);
+        assert_eq!(runner.steps(), 1);
+        runner.tick(start + Duration::from_millis(500));
+        assert_eq!(runner.steps(), 1);
+        runner.tick(start + Duration::from_secs(1));
+        assert_eq!(runner.steps(), 2);
+
+        runner.pause();
+        runner.tick(start + Duration::from_secs(5));
+        assert_eq!((runner.state(), runner.steps()), (Lifecycle::Paused, 2));
+        runner.resume();
+        runner.tick(start + Duration::from_secs(5));
+        assert_eq!((runner.state(), runner.steps()), (Lifecycle::Finished, 3));
+        assert_eq!(
+            runner.drain_events(),
+            vec![
+                LifecycleEvent::Started,
+                LifecycleEvent::Paused,
+                LifecycleEvent::Resumed,
+                LifecycleEvent::Finished { steps: 3 }
+            ]
+        );
+    }
+
+    #[test]
+    fn tick_finishes_an_empty_run_and_reopens_one_given_more_work() {
+        let (mut runner, left) = runner(0);
+        runner.start();
+        runner.tick(Instant::now());
+        assert_eq!((runner.state(), runner.steps()), (Lifecycle::Finished, 0));
+
+        left.set(2);
+        runner.tick(Instant::now());
+        assert_eq!((runner.state(), runner.steps()), (Lifecycle::Idle, 0));
+        assert!(runner.can_start() && runner.can_step());
+        assert_eq!(runner.drain_events(), vec![LifecycleEvent::Started, LifecycleEvent::Finished { steps: 0 }]);
+    }
+}
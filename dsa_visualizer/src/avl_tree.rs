@@ -0,0 +1,92 @@
+use crate::binary_tree::{bst_delete, bst_insert, Frames, NodeStyle, Tree, TreeKind, TreeVisualizer};
+use crate::theme::{Role, Theme};
+
+/// Height balanced tree: every node keeps |height(left) - height(right)| <= 1.
+pub struct Avl;
+
+pub type AVLVisualizer = TreeVisualizer<Avl>;
+
+impl Avl {
+    /// Walks from `start` up to the root refreshing heights and rotating wherever the
+    /// balance factor leaves [-1, 1].
+    fn rebalance(tree: &mut Tree, start: Option<usize>, frames: &mut Frames) {
+        let mut current = start;
+        while let Some(n) = current {
+            tree.update_height(n);
+            let balance = tree.balance_factor(n);
+            let key = tree.key(n);
+            let mut subtree_root = n;
+
+            if balance.abs() > 1 {
+                // Positive balance means the left side is too tall.
+                let heavy_left = balance > 0;
+                let child = tree.child(n, heavy_left).expect("heavy side has a child");
+                let child_key = tree.key(child);
+                let child_balance = tree.balance_factor(child);
+                let zig_zag = if heavy_left { child_balance < 0 } else { child_balance > 0 };
+                let side = if heavy_left { "Left" } else { "Right" };
+
+                if zig_zag {
+                    let other = if heavy_left { "Right" } else { "Left" };
+                    frames.record(
+                        tree,
+                        &[key, child_key],
+                        format!(
+                            "{} has balance factor {:+}: {}-{} case, rotate {} at {} first",
+                            key, balance, side, other, other.to_lowercase(), child_key
+                        ),
+                    );
+                    tree.rotate(child, heavy_left);
+                    frames.record(tree, &[key, child_key], format!("Rotated {} at {}", other.to_lowercase(), child_key));
+                } else {
+                    frames.record(
+                        tree,
+                        &[key, child_key],
+                        format!("{} has balance factor {:+}: {}-{} case", key, balance, side, side),
+                    );
+                }
+
+                let rotation = if heavy_left { "right" } else { "left" };
+                subtree_root = tree.rotate(n, !heavy_left);
+                frames.record(
+                    tree,
+                    &[key, tree.key(subtree_root)],
+                    format!("Rotated {} at {}", rotation, key),
+                );
+            }
+
+            current = tree.parent(subtree_root);
+        }
+    }
+}
+
+impl TreeKind for Avl {
+    const NAME: &'static str = "AVL Tree";
+
+    fn insert(tree: &mut Tree, key: i32, frames: &mut Frames) {
+        if let Some(new) = bst_insert(tree, key, frames) {
+            let parent = tree.parent(new);
+            Self::rebalance(tree, parent, frames);
+            frames.record(tree, &[key], format!("{} inserted, tree is balanced", key));
+        }
+    }
+
+    fn delete(tree: &mut Tree, key: i32, frames: &mut Frames) {
+        if let Some(parent) = bst_delete(tree, key, frames) {
+            Self::rebalance(tree, parent, frames);
+            frames.record(tree, &[], format!("{} deleted, tree is balanced", key));
+        }
+    }
+
+    fn style(tree: &Tree, i: usize, theme: &Theme) -> NodeStyle {
+        let balance = tree.balance_factor(i);
+        NodeStyle {
+            fill: theme.color(if balance.abs() > 1 { Role::Rejected } else { Role::Unvisited }),
+            label: Some(format!("bf {:+}", balance)),
+        }
+    }
+
+    fn legend() -> Vec<(Role, &'static str)> {
+        vec![(Role::Rejected, "Balance factor beyond ±1")]
+    }
+}
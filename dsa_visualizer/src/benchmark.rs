@@ -0,0 +1,504 @@
+use crate::bubble_sort::{Bubble, Cocktail};
+use crate::counting_sort::Counting;
+use crate::dijkstra;
+use crate::heap_sort;
+use crate::insertion_sort::Insertion;
+use crate::longest_common_sequence;
+use crate::merge_sort;
+use crate::pan_zoom;
+use crate::radix_sort::Radix;
+use crate::selection_sort::Selection;
+use crate::shell_sort::Shell;
+use crate::sort_input::{Distribution, SortInput};
+use crate::sorting::{self, Recorder, SortKind};
+use crate::theme::Theme;
+use crate::tim_sort::Tim;
+use eframe::egui;
+use egui::{Pos2, Rect, Shape, Stroke};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Smallest input measured. Sizes double from here up to the chosen maximum.
+const MIN_SIZE: usize = 16;
+/// Small runs are repeated until they add up to this, so the clock can resolve them.
+const MIN_SAMPLE: Duration = Duration::from_millis(2);
+const MAX_REPEATS: usize = 1000;
+/// Once a single run takes longer than this, larger sizes of that algorithm are skipped.
+const TIME_BUDGET: Duration = Duration::from_millis(400);
+
+/// Every palette has at least six group colors, so switching the marker every six
+/// series keeps each algorithm's color and marker pair apart.
+fn square_marker(i: usize) -> bool {
+    i / 6 % 2 == 1
+}
+
+/// Reference curve drawn behind the measurements.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Growth {
+    Linear,
+    Linearithmic,
+    Quadratic,
+}
+
+impl Growth {
+    pub const ALL: [Growth; 3] = [Growth::Linear, Growth::Linearithmic, Growth::Quadratic];
+
+    fn at(self, n: f64) -> f64 {
+        match self {
+            Growth::Linear => n,
+            Growth::Linearithmic => n * n.log2(),
+            Growth::Quadratic => n * n,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Growth::Linear => "n",
+            Growth::Linearithmic => "n log n",
+            Growth::Quadratic => "n²",
+        }
+    }
+}
+
+/// What the plot's vertical axis shows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Metric {
+    Time,
+    Operations,
+}
+
+impl Metric {
+    fn of(self, sample: &Sample) -> f64 {
+        match self {
+            Metric::Time => sample.seconds,
+            Metric::Operations => sample.operations as f64,
+        }
+    }
+
+    fn format(self, value: f64) -> String {
+        match self {
+            Metric::Time if value < 1e-6 => format!("{:.0} ns", value * 1e9),
+            Metric::Time if value < 1e-3 => format!("{:.0} µs", value * 1e6),
+            Metric::Time if value < 1.0 => format!("{:.0} ms", value * 1e3),
+            Metric::Time => format!("{:.1} s", value),
+            Metric::Operations if value < 1e3 => format!("{:.0}", value),
+            Metric::Operations if value < 1e6 => format!("{:.0}k", value / 1e3),
+            Metric::Operations => format!("{:.0}M", value / 1e6),
+        }
+    }
+}
+
+/// One input size of one algorithm.
+#[derive(Clone, Copy, Debug)]
+pub struct Sample {
+    pub n: usize,
+    /// Wall-clock time of one run.
+    pub seconds: f64,
+    /// Comparisons, moves, relaxations or table cells, depending on the algorithm.
+    pub operations: usize,
+}
+
+/// An algorithm core the benchmark can run on its own, without its visualizer.
+pub struct Subject {
+    pub name: &'static str,
+    pub expected: &'static str,
+    /// Largest size tried regardless of the time budget, for cores that need n² memory.
+    max_size: usize,
+    /// Builds an input of `input.size` from the distribution and seed, and measures it.
+    run: fn(&SortInput) -> Sample,
+}
+
+pub const SUBJECTS: [Subject; 12] = [
+    Subject { name: "Insertion sort", expected: "n²", max_size: usize::MAX, run: sort_kind::<Insertion> },
+    Subject { name: "Selection sort", expected: "n²", max_size: usize::MAX, run: sort_kind::<Selection> },
+    Subject { name: "Bubble sort", expected: "n²", max_size: usize::MAX, run: sort_kind::<Bubble> },
+    Subject { name: "Cocktail shaker sort", expected: "n²", max_size: usize::MAX, run: sort_kind::<Cocktail> },
+    Subject { name: "Shell sort", expected: "about n^(4/3) with Ciura gaps", max_size: usize::MAX, run: sort_kind::<Shell> },
+    Subject { name: "Timsort", expected: "n log n", max_size: usize::MAX, run: sort_kind::<Tim> },
+    Subject { name: "Counting sort", expected: "n + k", max_size: usize::MAX, run: sort_kind::<Counting> },
+    Subject { name: "Radix sort (LSD)", expected: "d (n + b)", max_size: usize::MAX, run: sort_kind::<Radix> },
+    Subject { name: "Merge sort", expected: "n log n", max_size: usize::MAX, run: merge_sort },
+    Subject { name: "Heap sort", expected: "n log n", max_size: usize::MAX, run: heap_sort },
+    Subject { name: "Dijkstra (4n edges)", expected: "(V + E) log V", max_size: usize::MAX, run: dijkstra },
+    Subject { name: "LCS (two length n strings)", expected: "n²", max_size: 4096, run: lcs },
+];
+
+/// Times `core` on fresh copies of `input`, repeating short runs until they can be
+/// measured. The copy is made outside the timed part.
+fn measure<I: Clone>(n: usize, input: I, mut core: impl FnMut(I) -> usize) -> Sample {
+    let mut total = Duration::ZERO;
+    let mut repeats = 0;
+    let mut operations = 0;
+    while total < MIN_SAMPLE && repeats < MAX_REPEATS {
+        let copy = input.clone();
+        let start = Instant::now();
+        operations = std::hint::black_box(core(copy));
+        total += start.elapsed();
+        repeats += 1;
+    }
+    Sample {
+        n,
+        seconds: total.as_secs_f64() / repeats as f64,
+        operations,
+    }
+}
+
+fn sort_kind<T: SortKind>(input: &SortInput) -> Sample {
+    let items = sorting::tag_items(&input.generate());
+    measure(input.size, items, |items| {
+        let mut rec = Recorder::counting(items);
+        T::default().sort(&mut rec);
+        rec.operations()
+    })
+}
+
+fn merge_sort(input: &SortInput) -> Sample {
+    measure(input.size, input.generate(), |mut data| merge_sort::merge_sort(&mut data))
+}
+
+fn heap_sort(input: &SortInput) -> Sample {
+    measure(input.size, input.generate(), |mut data| heap_sort::heap_sort(&mut data))
+}
+
+/// A sparse connected graph: a path through every node plus three random edges out of
+/// each, with weights from 1 to 9.
+fn dijkstra(input: &SortInput) -> Sample {
+    let mut rng = StdRng::seed_from_u64(input.seed);
+    let n = input.size;
+    let graph: HashMap<usize, Vec<(usize, usize)>> = (0..n)
+        .map(|node| {
+            let mut edges: Vec<(usize, usize)> = (0..3).map(|_| (rng.gen_range(0..n), rng.gen_range(1..=9))).collect();
+            if node + 1 < n {
+                edges.push((node + 1, rng.gen_range(1..=9)));
+            }
+            (node, edges)
+        })
+        .collect();
+    measure(n, graph, |graph| dijkstra::shortest_paths(&graph, 0).1)
+}
+
+/// Two random DNA strings of length n.
+fn lcs(input: &SortInput) -> Sample {
+    let mut rng = StdRng::seed_from_u64(input.seed);
+    let mut strand = || -> Vec<u8> { (0..input.size).map(|_| b"ACGT"[rng.gen_range(0..4)]).collect() };
+    let strings = (strand(), strand());
+    measure(input.size, strings, |(a, b)| longest_common_sequence::lcs_length(&a, &b).1)
+}
+
+/// Least squares slope of log(value) against log(n): about 1 for linear growth, a
+/// little over 1 for n log n and 2 for quadratic.
+pub fn slope(samples: &[Sample], metric: Metric) -> Option<f64> {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|s| metric.of(s) > 0.0)
+        .map(|s| ((s.n as f64).ln(), metric.of(s).ln()))
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / count;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / count;
+    let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    Some(covariance / variance)
+}
+
+#[derive(Clone, Default)]
+pub struct Series {
+    pub samples: Vec<Sample>,
+    /// Size that was skipped because the previous one ran over the time budget.
+    pub stopped_at: Option<usize>,
+}
+
+/// What the worker thread reports back.
+enum Progress {
+    /// Subject and size it is measuring now.
+    Measuring(usize, usize),
+    /// A finished measurement, and the size skipped after it if it ran over the budget.
+    Measured { subject: usize, sample: Sample, skipped: Option<usize> },
+}
+
+/// A thread taking the measurements, so a slow one never holds up a frame. It stops
+/// when the queue runs out, when `stop` is set or when nobody is listening any more.
+struct Worker {
+    progress: Receiver<Progress>,
+    stop: Arc<AtomicBool>,
+}
+
+/// Takes the measurements in `queue`, smallest sizes first, dropping the rest of an
+/// algorithm's sizes once one of its runs goes over `TIME_BUDGET`.
+fn measure_queue(mut queue: VecDeque<(usize, usize)>, input: SortInput, progress: Sender<Progress>, stop: Arc<AtomicBool>) {
+    while let Some((subject, n)) = queue.pop_front() {
+        if stop.load(Ordering::Relaxed) || progress.send(Progress::Measuring(subject, n)).is_err() {
+            return;
+        }
+        let sample = (SUBJECTS[subject].run)(&SortInput { size: n, ..input.clone() });
+        let mut skipped = None;
+        if sample.seconds > TIME_BUDGET.as_secs_f64() {
+            skipped = queue.iter().find(|&&(s, _)| s == subject).map(|&(_, n)| n);
+            queue.retain(|&(s, _)| s != subject);
+        }
+        if progress.send(Progress::Measured { subject, sample, skipped }).is_err() {
+            return;
+        }
+    }
+}
+
+/// Runs the algorithm cores on doubling input sizes and plots what they cost.
+pub struct Benchmark {
+    enabled: Vec<bool>,
+    input: SortInput,
+    /// Largest size is 2^max_exponent.
+    max_exponent: u32,
+    metric: Metric,
+    pub results: Vec<Series>,
+    worker: Option<Worker>,
+    /// Subject and size being measured right now.
+    measuring: Option<(usize, usize)>,
+}
+
+impl Benchmark {
+    pub fn new() -> Self {
+        Self {
+            enabled: vec![true; SUBJECTS.len()],
+            input: SortInput::new(MIN_SIZE),
+            max_exponent: 12,
+            metric: Metric::Operations,
+            results: vec![Series::default(); SUBJECTS.len()],
+            worker: None,
+            measuring: None,
+        }
+    }
+
+    pub fn set_max_size(&mut self, size: usize) {
+        self.max_exponent = size.max(MIN_SIZE * 2).ilog2();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.worker.is_some()
+    }
+
+    /// Clears the results and starts a worker on every enabled algorithm at every size.
+    pub fn start(&mut self) {
+        self.stop();
+        self.results = vec![Series::default(); SUBJECTS.len()];
+        let mut queue = VecDeque::new();
+        let mut n = MIN_SIZE;
+        while n <= 1 << self.max_exponent {
+            for (subject, _) in SUBJECTS.iter().enumerate().filter(|&(i, s)| self.enabled[i] && n <= s.max_size) {
+                queue.push_back((subject, n));
+            }
+            n *= 2;
+        }
+
+        let (sender, progress) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let input = self.input.clone();
+        let worker_stop = stop.clone();
+        thread::spawn(move || measure_queue(queue, input, sender, worker_stop));
+        self.worker = Some(Worker { progress, stop });
+    }
+
+    /// Lets the worker finish the measurement it is on and drops the rest.
+    pub fn stop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.stop.store(true, Ordering::Relaxed);
+        }
+        self.measuring = None;
+    }
+
+    /// Takes in what the worker has measured since the last call, without waiting for
+    /// more. Returns true while it is still measuring.
+    pub fn poll(&mut self) -> bool {
+        let Some(worker) = &self.worker else {
+            return false;
+        };
+        loop {
+            match worker.progress.try_recv() {
+                Ok(Progress::Measuring(subject, n)) => self.measuring = Some((subject, n)),
+                Ok(Progress::Measured { subject, sample, skipped }) => {
+                    self.results[subject].samples.push(sample);
+                    if skipped.is_some() {
+                        self.results[subject].stopped_at = skipped;
+                    }
+                }
+                Err(TryRecvError::Empty) => return true,
+                Err(TryRecvError::Disconnected) => {
+                    self.worker = None;
+                    self.measuring = None;
+                    return false;
+                }
+            }
+        }
+    }
+
+    pub fn render(&mut self, ui: &mut egui::Ui) {
+        ui.label("Runs each algorithm without its visualizer on inputs that double in size, and plots the cost on log-log axes. A straight line of slope k means the cost grows like n^k.");
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Input")
+                .selected_text(self.input.distribution.to_string())
+                .show_ui(ui, |ui| {
+                    for distribution in Distribution::ALL {
+                        ui.selectable_value(&mut self.input.distribution, distribution, distribution.to_string());
+                    }
+                });
+            ui.add(egui::DragValue::new(&mut self.input.seed).prefix("seed "));
+            let mut max_size = 1usize << self.max_exponent;
+            egui::ComboBox::from_label("Largest n")
+                .selected_text(max_size.to_string())
+                .show_ui(ui, |ui| {
+                    for exponent in 8..=16 {
+                        ui.selectable_value(&mut max_size, 1 << exponent, (1usize << exponent).to_string());
+                    }
+                });
+            self.set_max_size(max_size);
+            ui.separator();
+            ui.selectable_value(&mut self.metric, Metric::Operations, "Operations");
+            ui.selectable_value(&mut self.metric, Metric::Time, "Wall-clock time");
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            for (i, subject) in SUBJECTS.iter().enumerate() {
+                ui.checkbox(&mut self.enabled[i], subject.name);
+            }
+        });
+
+        self.poll();
+        ui.horizontal(|ui| {
+            if self.is_running() {
+                if ui.button("Stop").clicked() {
+                    self.stop();
+                }
+                if let Some((subject, n)) = self.measuring {
+                    ui.spinner();
+                    ui.label(format!("Measuring {} at n = {}", SUBJECTS[subject].name, n));
+                }
+            } else if ui.button("Run benchmark").clicked() {
+                self.start();
+            }
+        });
+
+        if self.results.iter().all(|series| series.samples.is_empty()) {
+            return;
+        }
+        pan_zoom::show(ui, "benchmark", |ui| self.render_plot(ui));
+        self.render_table(ui);
+    }
+
+    fn render_plot(&self, ui: &mut egui::Ui) {
+        let theme = Theme::current(ui.ctx());
+        let (response, painter) = ui.allocate_painter(egui::vec2(ui.available_width(), 380.0), egui::Sense::hover());
+        let plot = Rect::from_min_max(response.rect.min + egui::vec2(70.0, 10.0), response.rect.max - egui::vec2(90.0, 30.0));
+        let font = egui::FontId::new(12.0, egui::FontFamily::Proportional);
+
+        let measured: Vec<(usize, &Sample)> = self
+            .results
+            .iter()
+            .enumerate()
+            .flat_map(|(i, series)| series.samples.iter().map(move |s| (i, s)))
+            .filter(|(_, s)| self.metric.of(s) > 0.0)
+            .collect();
+        let Some(&(_, first)) = measured.iter().min_by(|a, b| (a.1.n, self.metric.of(a.1)).partial_cmp(&(b.1.n, self.metric.of(b.1))).unwrap()) else {
+            return;
+        };
+        let max_n = 1usize << self.max_exponent;
+        let low = measured.iter().map(|(_, s)| self.metric.of(s)).fold(f64::INFINITY, f64::min).log10().floor();
+        let high = measured.iter().map(|(_, s)| self.metric.of(s)).fold(0.0, f64::max).log10().ceil().max(low + 1.0);
+
+        let span = ((max_n / MIN_SIZE) as f64).log2();
+        let x = |n: f64| plot.left() + ((n / MIN_SIZE as f64).log2() / span) as f32 * plot.width();
+        let y = |value: f64| plot.bottom() - ((value.log10() - low) / (high - low)) as f32 * plot.height();
+
+        painter.rect_stroke(plot, 0.0, Stroke::new(1.0, theme.line()));
+        let mut n = MIN_SIZE;
+        while n <= max_n {
+            let px = x(n as f64);
+            painter.line_segment([Pos2::new(px, plot.bottom()), Pos2::new(px, plot.bottom() + 4.0)], Stroke::new(1.0, theme.line()));
+            painter.text(Pos2::new(px, plot.bottom() + 6.0), egui::Align2::CENTER_TOP, n.to_string(), font.clone(), theme.muted());
+            n *= 2;
+        }
+        for decade in low as i32..=high as i32 {
+            let py = y(10f64.powi(decade));
+            painter.line_segment([Pos2::new(plot.left(), py), Pos2::new(plot.right(), py)], Stroke::new(0.5, theme.line()));
+            painter.text(Pos2::new(plot.left() - 6.0, py), egui::Align2::RIGHT_CENTER, self.metric.format(10f64.powi(decade)), font.clone(), theme.muted());
+        }
+
+        // Reference curves start from the cheapest measurement at the smallest size, so
+        // each measured line can be compared with the slopes around it.
+        let clipped = painter.with_clip_rect(plot);
+        let anchor = self.metric.of(first);
+        for growth in Growth::ALL {
+            let scale = anchor / growth.at(first.n as f64);
+            let points: Vec<Pos2> = (0..=40)
+                .map(|k| first.n as f64 * (max_n as f64 / first.n as f64).powf(k as f64 / 40.0))
+                .map(|n| Pos2::new(x(n), y(scale * growth.at(n))))
+                .collect();
+            let end = *points.iter().rev().find(|p| p.y >= plot.top()).unwrap_or(&points[0]);
+            clipped.extend(Shape::dashed_line(&points, Stroke::new(1.0, theme.muted()), 6.0, 4.0));
+            painter.text(end + egui::vec2(4.0, 0.0), egui::Align2::LEFT_CENTER, growth.label(), font.clone(), theme.muted());
+        }
+
+        for (i, series) in self.results.iter().enumerate() {
+            let color = theme.group(i);
+            let points: Vec<Pos2> = series
+                .samples
+                .iter()
+                .filter(|s| self.metric.of(s) > 0.0)
+                .map(|s| Pos2::new(x(s.n as f64), y(self.metric.of(s))))
+                .collect();
+            clipped.add(Shape::line(points.clone(), Stroke::new(2.0, color)));
+            for point in points {
+                if square_marker(i) {
+                    clipped.rect_filled(Rect::from_center_size(point, egui::vec2(7.0, 7.0)), 0.0, color);
+                } else {
+                    clipped.circle_filled(point, 3.5, color);
+                }
+            }
+        }
+
+        painter.text(
+            Pos2::new(plot.center().x, response.rect.bottom()),
+            egui::Align2::CENTER_BOTTOM,
+            "input size n (log scale)",
+            font,
+            theme.muted(),
+        );
+    }
+
+    fn render_table(&self, ui: &mut egui::Ui) {
+        let theme = Theme::current(ui.ctx());
+        egui::Grid::new("benchmark_results").striped(true).show(ui, |ui| {
+            ui.strong("Algorithm");
+            ui.strong("Expected");
+            ui.strong("Measured slope");
+            ui.strong("Largest n");
+            ui.strong(match self.metric {
+                Metric::Time => "Time at largest n",
+                Metric::Operations => "Operations at largest n",
+            });
+            ui.end_row();
+            for (i, series) in self.results.iter().enumerate() {
+                let Some(last) = series.samples.last() else {
+                    continue;
+                };
+                ui.colored_label(theme.group(i), format!("{} {}", if square_marker(i) { "■" } else { "•" }, SUBJECTS[i].name));
+                ui.label(SUBJECTS[i].expected);
+                ui.label(slope(&series.samples, self.metric).map_or("-".to_string(), |k| format!("n^{:.2}", k)));
+                ui.label(match series.stopped_at {
+                    Some(n) => format!("{} (n = {} would take too long)", last.n, n),
+                    None => last.n.to_string(),
+                });
+                ui.label(self.metric.format(self.metric.of(last)));
+                ui.end_row();
+            }
+        });
+        ui.weak("Operations count comparisons and moves for sorts, heap pops and edge relaxations for Dijkstra and table cells for LCS. Wall-clock time also depends on caches, the allocator and whether this is a debug build.");
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::VecDeque;
-use std::time::{Duration, Instant};
 use eframe::egui;
-use crate::algorithm::Algorithm;
+use crate::algorithm::{Action, Algorithm};
+use crate::graph;
+use crate::pan_zoom;
+use crate::theme::{Role, Theme};
 
 pub struct BFSVisualizer {
     nodes: Vec<(usize, usize, usize)>, // Node ID, Parent ID, Level
@@ -9,8 +11,7 @@ pub struct BFSVisualizer {
     visited: Vec<usize>,
     bfs_queue: VecDeque<usize>,
     tree_created: bool,
-    auto_traverse: bool,
-    last_step_time: Option<Instant>,
+    clicked: Option<usize>,
 }
 
 impl BFSVisualizer {
@@ -21,8 +22,7 @@ impl BFSVisualizer {
             visited: Vec::new(),
             bfs_queue: VecDeque::new(),
             tree_created: false,
-            auto_traverse: false,
-            last_step_time: None,
+            clicked: None,
         }
     }
 
@@ -51,7 +51,6 @@ impl BFSVisualizer {
         self.visited.clear();
         self.bfs_queue.clear();
         self.tree_created = true;
-        self.auto_traverse = false;
     }
 
     fn start_bfs(&mut self) {
@@ -60,8 +59,6 @@ impl BFSVisualizer {
             self.bfs_queue.clear();
             self.current_node = None;
             self.bfs_queue.push_back(1);
-            self.auto_traverse = true;
-            self.last_step_time = None;
         }
     }
 
@@ -76,11 +73,10 @@ impl BFSVisualizer {
             }
         } else {
             self.current_node = None;
-            self.auto_traverse = false;
         }
     }
 
-        fn render_tree(&self, ui: &mut egui::Ui) {
+        fn render_tree(&self, ui: &mut egui::Ui) -> Option<usize> {
             let total_levels = self.nodes.iter().map(|(_, _, level)| level).max().unwrap_or(&0) + 1;
             let node_spacing = 70.0;
             let screen_width = ui.available_width();
@@ -88,6 +84,9 @@ impl BFSVisualizer {
             let height = (total_levels as f32 * 120.0).max(600.0);
             let level_spacing = height / (total_levels as f32 + 1.0);
             let node_radius = 30.0;
+            let theme = Theme::current(ui.ctx());
+            let (response, painter) = ui.allocate_painter(egui::vec2(screen_width, level_spacing * total_levels as f32), egui::Sense::hover());
+            let origin = response.rect.min;
 
             let mut levels: Vec<Vec<(usize, usize)>> = vec![Vec::new(); total_levels];
             for &(node_id, parent_id, level) in &self.nodes {
@@ -103,48 +102,54 @@ impl BFSVisualizer {
                 let x_spacing = width / (level_count as f32 + 1.0);
 
                 for (i, &(node_id, _)) in nodes.iter().enumerate() {
-                    let x = center_offset + x_spacing * (i as f32 + 1.0);
-                    let y = level_spacing * (level as f32 + 1.0);
+                    let x = origin.x + center_offset + x_spacing * (i as f32 + 1.0);
+                    let y = origin.y + level_spacing * (level as f32 + 0.5);
                     positions.push((node_id, x, y));
                 }
             }
 
             for &(node_id, parent_id, _) in &self.nodes {
                 if let Some(&(_, x, y)) = positions.iter().find(|&&(id, _, _)| id == node_id) {
-                    let color = if self.current_node == Some(node_id) {
-                        egui::Color32::GREEN
+                    let color = theme.color(if self.current_node == Some(node_id) {
+                        Role::Current
                     } else if self.visited.contains(&node_id) {
-                        egui::Color32::LIGHT_BLUE
+                        Role::Visited
+                    } else if self.bfs_queue.contains(&node_id) {
+                        Role::Frontier
                     } else {
-                        egui::Color32::RED
-                    };
+                        Role::Unvisited
+                    });
 
-                    ui.painter().circle_filled(egui::pos2(x, y), node_radius, color);
+                    painter.circle_filled(egui::pos2(x, y), node_radius, color);
 
                     if parent_id != 0 {
                         if let Some(&(_, px, py)) = positions.iter().find(|&&(id, _, _)| id == parent_id) {
-                            ui.painter().line_segment(
+                            painter.line_segment(
                                 [egui::pos2(px, py + (node_radius)), egui::pos2(x, y - (node_radius))],
-                                egui::Stroke::new(2.0, egui::Color32::GRAY),
+                                egui::Stroke::new(2.0, theme.line()),
                             );
                         }
                     }
 
-                    ui.painter().text(
+                    painter.text(
                         egui::pos2(x, y - 10.0),
                         egui::Align2::CENTER_BOTTOM,
                         format!("{}", node_id),
                         egui::TextStyle::Body.resolve(ui.style()),
-                        egui::Color32::BLACK,
+                        theme.text_on(color),
                     );
                 }
             }
+
+            let centers = positions.iter().map(|&(id, x, y)| (id, egui::pos2(x, y)));
+            graph::clicked_node(ui, centers, node_radius)
         }
 }
 
 impl Algorithm for BFSVisualizer {
     fn initialize(&mut self) {
         self.create_example_tree();
+        self.start_bfs();
     }
 
     fn step(&mut self) {
@@ -152,25 +157,31 @@ impl Algorithm for BFSVisualizer {
     }
 
     fn render(&mut self, ui: &mut egui::Ui) {
-        self.render_tree(ui);
+        self.clicked = pan_zoom::show(ui, "tree", |ui| self.render_tree(ui));
     }
 
-    fn auto_play(&self) -> bool {
-        self.auto_traverse
+    fn is_finished(&self) -> bool {
+        self.bfs_queue.is_empty()
     }
 
-    fn toggle_auto_traverse(&mut self) {
-        self.auto_traverse = !self.auto_traverse;
+    fn reset(&mut self) {
+        self.start_bfs();
     }
 
-    fn start(&mut self)  {
-        self.start_bfs();
+    fn peek(&self) -> Option<Action> {
+        self.bfs_queue.front().map(|&node| Action::Visit(node))
     }
-    fn last_step_time(&self) -> Option<Instant> {
-        self.last_step_time
+
+    fn take_click(&mut self) -> Option<usize> {
+        self.clicked.take()
     }
 
-    fn set_last_step_time(&mut self, time: Option<Instant>) {
-        self.last_step_time = time;
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![
+            (Role::Unvisited, "Not reached"),
+            (Role::Frontier, "In the queue"),
+            (Role::Current, "Visiting"),
+            (Role::Visited, "Visited"),
+        ]
     }
-}
\ No newline at end of file
+}
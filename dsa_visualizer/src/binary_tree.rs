@@ -0,0 +1,652 @@
+use crate::algorithm::{rewound_message, Algorithm};
+use crate::pan_zoom;
+use crate::session;
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use egui::{Color32, Pos2, Stroke};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// A node in the `Tree` arena. `height` is only maintained by the AVL tree and
+/// `red` only matters to the red-black tree.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Node {
+    pub key: i32,
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+    pub parent: Option<usize>,
+    pub height: i32,
+    pub red: bool,
+}
+
+/// Arena backed binary search tree with parent links, shared by the BST, AVL and
+/// red-black visualizers so that rotations look the same in all three.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Tree {
+    nodes: Vec<Option<Node>>,
+    pub root: Option<usize>,
+}
+
+impl Tree {
+    pub fn node(&self, i: usize) -> &Node {
+        self.nodes[i].as_ref().expect("node was freed")
+    }
+
+    pub fn node_mut(&mut self, i: usize) -> &mut Node {
+        self.nodes[i].as_mut().expect("node was freed")
+    }
+
+    pub fn key(&self, i: usize) -> i32 {
+        self.node(i).key
+    }
+
+    pub fn parent(&self, i: usize) -> Option<usize> {
+        self.node(i).parent
+    }
+
+    /// Child of `i` on the left side when `left` is true, otherwise on the right.
+    pub fn child(&self, i: usize, left: bool) -> Option<usize> {
+        if left {
+            self.node(i).left
+        } else {
+            self.node(i).right
+        }
+    }
+
+    pub fn is_red(&self, i: Option<usize>) -> bool {
+        i.is_some_and(|i| self.node(i).red)
+    }
+
+    pub fn alloc(&mut self, key: i32, parent: Option<usize>) -> usize {
+        self.nodes.push(Some(Node {
+            key,
+            left: None,
+            right: None,
+            parent,
+            height: 1,
+            red: true,
+        }));
+        self.nodes.len() - 1
+    }
+
+    pub fn free(&mut self, i: usize) {
+        self.nodes[i] = None;
+    }
+
+    /// False for a tree read from a session file whose links don't form a tree.
+    fn is_consistent(&self) -> bool {
+        let live = |i: usize| self.nodes.get(i).is_some_and(Option::is_some);
+        if self.root.is_some_and(|r| !live(r) || self.node(r).parent.is_some()) {
+            return false;
+        }
+        let mut reached = 0;
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(i) = stack.pop() {
+            reached += 1;
+            for child in [self.node(i).left, self.node(i).right].into_iter().flatten() {
+                if !live(child) || self.node(child).parent != Some(i) {
+                    return false;
+                }
+                stack.push(child);
+            }
+        }
+        reached == self.nodes.iter().flatten().count()
+    }
+
+    pub fn minimum(&self, mut i: usize) -> usize {
+        while let Some(left) = self.node(i).left {
+            i = left;
+        }
+        i
+    }
+
+    pub fn height(&self, i: Option<usize>) -> i32 {
+        i.map_or(0, |i| self.node(i).height)
+    }
+
+    pub fn update_height(&mut self, i: usize) {
+        let node = self.node(i);
+        let height = 1 + self.height(node.left).max(self.height(node.right));
+        self.node_mut(i).height = height;
+    }
+
+    pub fn balance_factor(&self, i: usize) -> i32 {
+        let node = self.node(i);
+        self.height(node.left) - self.height(node.right)
+    }
+
+    /// Points whatever referenced `old` (its parent, or the root) at `new`.
+    fn replace_child(&mut self, parent: Option<usize>, old: usize, new: Option<usize>) {
+        match parent {
+            None => self.root = new,
+            Some(p) => {
+                if self.node(p).left == Some(old) {
+                    self.node_mut(p).left = new;
+                } else {
+                    self.node_mut(p).right = new;
+                }
+            }
+        }
+    }
+
+    /// Replaces the subtree rooted at `u` with the subtree rooted at `v`.
+    pub fn transplant(&mut self, u: usize, v: Option<usize>) {
+        let parent = self.parent(u);
+        self.replace_child(parent, u, v);
+        if let Some(v) = v {
+            self.node_mut(v).parent = parent;
+        }
+    }
+
+    /// Rotates `x` down towards the `left` side and returns the node that took its place.
+    pub fn rotate(&mut self, x: usize, left: bool) -> usize {
+        let y = self.child(x, !left).expect("rotation needs a child to lift");
+        let inner = self.child(y, left);
+
+        if left {
+            self.node_mut(x).right = inner;
+            self.node_mut(y).left = Some(x);
+        } else {
+            self.node_mut(x).left = inner;
+            self.node_mut(y).right = Some(x);
+        }
+        if let Some(inner) = inner {
+            self.node_mut(inner).parent = Some(x);
+        }
+
+        let parent = self.parent(x);
+        self.node_mut(y).parent = parent;
+        self.replace_child(parent, x, Some(y));
+        self.node_mut(x).parent = Some(y);
+
+        self.update_height(x);
+        self.update_height(y);
+        y
+    }
+
+    /// Node indices in order, each paired with its depth.
+    fn in_order(&self) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+        let mut stack = Vec::new();
+        let mut current = self.root.map(|r| (r, 0));
+        loop {
+            while let Some((i, depth)) = current {
+                stack.push((i, depth));
+                current = self.node(i).left.map(|l| (l, depth + 1));
+            }
+            match stack.pop() {
+                Some((i, depth)) => {
+                    out.push((i, depth));
+                    current = self.node(i).right.map(|r| (r, depth + 1));
+                }
+                None => break,
+            }
+        }
+        out
+    }
+}
+
+/// One snapshot of the tree shown to the user, with the keys involved in the step.
+#[derive(Clone, Default)]
+pub struct Frame {
+    pub tree: Tree,
+    pub highlight: Vec<i32>,
+    pub message: String,
+}
+
+/// Collects the frames an operation produces so they can be replayed one `step()` at a time.
+#[derive(Default)]
+pub struct Frames(VecDeque<Frame>);
+
+impl Frames {
+    pub fn record(&mut self, tree: &Tree, highlight: &[i32], message: impl Into<String>) {
+        self.0.push_back(Frame {
+            tree: tree.clone(),
+            highlight: highlight.to_vec(),
+            message: message.into(),
+        });
+    }
+}
+
+/// Walks down from the root like an ordinary BST insert and links in a new leaf.
+/// Returns `None` when the key is already present.
+pub fn bst_insert(tree: &mut Tree, key: i32, frames: &mut Frames) -> Option<usize> {
+    let mut parent = None;
+    let mut current = tree.root;
+    while let Some(i) = current {
+        let node_key = tree.key(i);
+        if key == node_key {
+            frames.record(tree, &[key], format!("{} is already in the tree", key));
+            return None;
+        }
+        let go_left = key < node_key;
+        frames.record(
+            tree,
+            &[node_key],
+            format!(
+                "{} {} {}, go {}",
+                key,
+                if go_left { "<" } else { ">" },
+                node_key,
+                if go_left { "left" } else { "right" }
+            ),
+        );
+        parent = Some(i);
+        current = tree.child(i, go_left);
+    }
+
+    let new = tree.alloc(key, parent);
+    match parent {
+        None => tree.root = Some(new),
+        Some(p) => {
+            if key < tree.key(p) {
+                tree.node_mut(p).left = Some(new);
+            } else {
+                tree.node_mut(p).right = Some(new);
+            }
+        }
+    }
+    frames.record(tree, &[key], format!("Inserted {}", key));
+    Some(new)
+}
+
+/// Plain BST delete: a node with two children takes its in-order successor's key and
+/// the successor is unlinked instead. Returns the parent of the unlinked node (where
+/// rebalancing should start), or `None` when the key is missing.
+pub fn bst_delete(tree: &mut Tree, key: i32, frames: &mut Frames) -> Option<Option<usize>> {
+    let target = search(tree, key, frames)?;
+
+    let mut removed = target;
+    let node = tree.node(target);
+    if let (Some(_), Some(right)) = (node.left, node.right) {
+        removed = tree.minimum(right);
+        let successor = tree.key(removed);
+        frames.record(
+            tree,
+            &[key, successor],
+            format!("{} has two children, replace it with its in-order successor {}", key, successor),
+        );
+        tree.node_mut(target).key = successor;
+    }
+
+    let node = tree.node(removed);
+    let child = node.left.or(node.right);
+    let parent = node.parent;
+    tree.transplant(removed, child);
+    tree.free(removed);
+    frames.record(tree, &[], format!("Removed {}", key));
+    Some(parent)
+}
+
+/// Descends from the root recording every comparison. Shared by all tree kinds.
+pub fn search(tree: &Tree, key: i32, frames: &mut Frames) -> Option<usize> {
+    let mut current = tree.root;
+    while let Some(i) = current {
+        let node_key = tree.key(i);
+        if key == node_key {
+            frames.record(tree, &[key], format!("Found {}", key));
+            return Some(i);
+        }
+        let go_left = key < node_key;
+        frames.record(
+            tree,
+            &[node_key],
+            format!("{} {} {}, go {}", key, if go_left { "<" } else { ">" }, node_key, if go_left { "left" } else { "right" }),
+        );
+        current = tree.child(i, go_left);
+    }
+    frames.record(tree, &[], format!("{} is not in the tree", key));
+    None
+}
+
+/// How a node is painted: fill color and an optional annotation under it. The key is
+/// drawn in black or white, whichever reads better on the fill.
+pub struct NodeStyle {
+    pub fill: Color32,
+    pub label: Option<String>,
+}
+
+/// The insert/delete rules that distinguish one kind of search tree from another.
+pub trait TreeKind {
+    const NAME: &'static str;
+    fn insert(tree: &mut Tree, key: i32, frames: &mut Frames);
+    fn delete(tree: &mut Tree, key: i32, frames: &mut Frames);
+    fn style(tree: &Tree, i: usize, theme: &Theme) -> NodeStyle;
+    /// What the fills picked by `style` mean.
+    fn legend() -> Vec<(Role, &'static str)> {
+        Vec::new()
+    }
+}
+
+/// A change the user asked for, kept so `reset` can play it again.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum Operation {
+    Insert(i32),
+    Delete(i32),
+    Search(i32),
+}
+
+/// What a session keeps: the tree the operations started from and the operations, so
+/// a reopened session can replay them the same way `reset` does.
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    start: Tree,
+    operations: Vec<Operation>,
+}
+
+pub struct TreeVisualizer<T: TreeKind> {
+    tree: Tree,
+    /// The tree before any of `operations`: the seed tree, a cleared one or a restored one.
+    start: Tree,
+    operations: Vec<Operation>,
+    frames: VecDeque<Frame>,
+    shown: Frame,
+    key_input: String,
+    kind: PhantomData<T>,
+}
+
+impl<T: TreeKind> TreeVisualizer<T> {
+    pub fn new() -> Self {
+        Self {
+            tree: Tree::default(),
+            start: Tree::default(),
+            operations: Vec::new(),
+            frames: VecDeque::new(),
+            shown: Frame::default(),
+            key_input: String::new(),
+            kind: PhantomData,
+        }
+    }
+
+    /// Runs an operation against the working tree and queues the frames it produced.
+    fn apply(&mut self, operation: Operation) {
+        let mut frames = Frames::default();
+        match operation {
+            Operation::Insert(key) => T::insert(&mut self.tree, key, &mut frames),
+            Operation::Delete(key) => T::delete(&mut self.tree, key, &mut frames),
+            Operation::Search(key) => {
+                search(&self.tree, key, &mut frames);
+            }
+        }
+        self.frames.extend(frames.0);
+    }
+
+    fn queue(&mut self, operation: Operation) {
+        self.apply(operation);
+        self.operations.push(operation);
+    }
+
+    /// Makes `tree` the starting point with no operations after it.
+    fn start_from(&mut self, tree: Tree, message: String) {
+        self.tree = tree;
+        self.start = self.tree.clone();
+        self.operations.clear();
+        self.frames.clear();
+        self.shown = Frame {
+            tree: self.tree.clone(),
+            highlight: Vec::new(),
+            message,
+        };
+    }
+
+    fn parsed_key(&self) -> Option<i32> {
+        self.key_input.trim().parse().ok()
+    }
+
+    fn render_tree(&self, ui: &mut egui::Ui) {
+        let tree = &self.shown.tree;
+        let order = tree.in_order();
+        let depth = order.iter().map(|&(_, d)| d).max().unwrap_or(0);
+        let node_radius = 20.0;
+        let level_spacing = 70.0;
+        let theme = Theme::current(ui.ctx());
+
+        let size = egui::vec2(ui.available_width(), (depth as f32 + 1.0) * level_spacing + 40.0);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let rect = response.rect;
+
+        let x_spacing = rect.width() / (order.len() as f32 + 1.0);
+        let positions: Vec<(usize, Pos2)> = order
+            .iter()
+            .enumerate()
+            .map(|(rank, &(i, d))| {
+                (
+                    i,
+                    Pos2::new(
+                        rect.left() + x_spacing * (rank as f32 + 1.0),
+                        rect.top() + 30.0 + d as f32 * level_spacing,
+                    ),
+                )
+            })
+            .collect();
+        let position_of = |i: usize| positions.iter().find(|&&(n, _)| n == i).map(|&(_, p)| p);
+
+        for &(i, pos) in &positions {
+            if let Some(parent) = tree.parent(i).and_then(position_of) {
+                painter.line_segment([parent, pos], Stroke::new(2.0, theme.line()));
+            }
+        }
+
+        for &(i, pos) in &positions {
+            let style = T::style(tree, i, &theme);
+            painter.circle_filled(pos, node_radius, style.fill);
+            if self.shown.highlight.contains(&tree.key(i)) {
+                painter.circle_stroke(pos, node_radius + 3.0, Stroke::new(3.0, theme.color(Role::Current)));
+            }
+            painter.text(
+                pos,
+                egui::Align2::CENTER_CENTER,
+                tree.key(i).to_string(),
+                egui::FontId::new(16.0, egui::FontFamily::Proportional),
+                theme.text_on(style.fill),
+            );
+            if let Some(label) = style.label {
+                painter.text(
+                    pos + egui::vec2(0.0, node_radius + 8.0),
+                    egui::Align2::CENTER_CENTER,
+                    label,
+                    egui::FontId::new(12.0, egui::FontFamily::Proportional),
+                    theme.muted(),
+                );
+            }
+        }
+    }
+}
+
+impl<T: TreeKind> Algorithm for TreeVisualizer<T> {
+    fn initialize(&mut self) {
+        let mut tree = Tree::default();
+        let mut frames = Frames::default();
+        for key in [50, 30, 70, 20, 40, 60, 80] {
+            T::insert(&mut tree, key, &mut frames);
+        }
+        self.start_from(tree, format!("{} ready, enter a key to insert, delete or search", T::NAME));
+    }
+
+    fn step(&mut self) {
+        if let Some(frame) = self.frames.pop_front() {
+            self.shown = frame;
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Key: ");
+            ui.add(egui::TextEdit::singleline(&mut self.key_input).desired_width(80.0));
+
+            if let Some(key) = self.parsed_key() {
+                if ui.button("Insert").clicked() {
+                    self.queue(Operation::Insert(key));
+                }
+                if ui.button("Delete").clicked() {
+                    self.queue(Operation::Delete(key));
+                }
+                if ui.button("Search").clicked() {
+                    self.queue(Operation::Search(key));
+                }
+            }
+            if ui.button("Insert Random").clicked() {
+                let key = rand::thread_rng().gen_range(1..100);
+                self.queue(Operation::Insert(key));
+            }
+            if ui.button("Clear").clicked() {
+                self.start_from(Tree::default(), String::new());
+            }
+        });
+
+        ui.label(&self.shown.message);
+        ui.label(format!("Pending steps: {}", self.frames.len()));
+        pan_zoom::show(ui, "tree", |ui| self.render_tree(ui));
+    }
+
+    fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Operations edit the tree as they are queued, so rewinding goes back to the
+    /// starting tree and queues every operation again.
+    fn reset(&mut self) {
+        self.tree = self.start.clone();
+        self.frames.clear();
+        for operation in self.operations.clone() {
+            self.apply(operation);
+        }
+        self.shown = Frame {
+            tree: self.start.clone(),
+            highlight: Vec::new(),
+            message: rewound_message(self.operations.len()),
+        };
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            start: self.start.clone(),
+            operations: self.operations.clone(),
+        })
+    }
+
+    /// Replays the operations without animating them and shows where they ended.
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        if !inputs.start.is_consistent() {
+            return Err("the saved tree's links are broken".to_string());
+        }
+        self.start = inputs.start;
+        self.operations = inputs.operations;
+        self.reset();
+        self.frames.clear();
+        self.shown = Frame {
+            tree: self.tree.clone(),
+            highlight: Vec::new(),
+            message: format!("Restored the {}", T::NAME),
+        };
+        Ok(())
+    }
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        let mut entries = vec![(Role::Current, "Looked at (ring)")];
+        entries.extend(T::legend());
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avl_tree::Avl;
+    use crate::red_black_tree::RedBlack;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::BTreeSet;
+
+    fn keys(tree: &Tree) -> Vec<i32> {
+        tree.in_order().into_iter().map(|(i, _)| tree.key(i)).collect()
+    }
+
+    /// Random inserts and deletes, checking after each one that the links hold, the keys
+    /// stay sorted and match a set given the same operations, and `invariant` holds.
+    fn churn<T: TreeKind>(invariant: impl Fn(&Tree)) {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut tree = Tree::default();
+        let mut expected = BTreeSet::new();
+        for _ in 0..500 {
+            let key = rng.gen_range(0..60);
+            if rng.gen_bool(0.6) {
+                T::insert(&mut tree, key, &mut Frames::default());
+                expected.insert(key);
+            } else {
+                T::delete(&mut tree, key, &mut Frames::default());
+                expected.remove(&key);
+            }
+            assert!(tree.is_consistent());
+            assert_eq!(keys(&tree), expected.iter().copied().collect::<Vec<_>>());
+            invariant(&tree);
+        }
+    }
+
+    /// Height of the subtree at `i`, checking each node's stored height and balance on the way.
+    fn checked_avl_height(tree: &Tree, i: Option<usize>) -> i32 {
+        let Some(i) = i else {
+            return 0;
+        };
+        let left = checked_avl_height(tree, tree.child(i, true));
+        let right = checked_avl_height(tree, tree.child(i, false));
+        assert!((left - right).abs() <= 1, "node {} is out of balance", tree.key(i));
+        assert_eq!(tree.node(i).height, 1 + left.max(right));
+        1 + left.max(right)
+    }
+
+    /// Black nodes on every path down from `i`, checking no red node has a red child.
+    fn black_height(tree: &Tree, i: Option<usize>) -> usize {
+        let Some(i) = i else {
+            return 1;
+        };
+        let (left, right) = (tree.child(i, true), tree.child(i, false));
+        if tree.is_red(Some(i)) {
+            assert!(!tree.is_red(left) && !tree.is_red(right), "red node {} has a red child", tree.key(i));
+        }
+        let height = black_height(tree, left);
+        assert_eq!(height, black_height(tree, right), "paths below {} differ in black nodes", tree.key(i));
+        height + usize::from(!tree.is_red(Some(i)))
+    }
+
+    #[test]
+    fn avl_stays_balanced() {
+        churn::<Avl>(|tree| {
+            checked_avl_height(tree, tree.root);
+        });
+    }
+
+    #[test]
+    fn red_black_keeps_its_colours() {
+        churn::<RedBlack>(|tree| {
+            assert!(!tree.is_red(tree.root));
+            black_height(tree, tree.root);
+        });
+    }
+
+    #[test]
+    fn reset_and_reload_replay_the_operations() {
+        let mut visualizer = TreeVisualizer::<Avl>::new();
+        visualizer.initialize();
+        for operation in [Operation::Insert(10), Operation::Delete(70), Operation::Search(30), Operation::Insert(65)] {
+            visualizer.queue(operation);
+        }
+        let finished = keys(&visualizer.tree);
+        assert_eq!(finished, vec![10, 20, 30, 40, 50, 60, 65, 80]);
+
+        visualizer.reset();
+        assert_eq!(keys(&visualizer.shown.tree), vec![20, 30, 40, 50, 60, 70, 80]);
+        while !visualizer.is_finished() {
+            visualizer.step();
+        }
+        assert_eq!(keys(&visualizer.shown.tree), finished);
+
+        let mut reloaded = TreeVisualizer::<Avl>::new();
+        reloaded.load_inputs(visualizer.save_inputs()).unwrap();
+        assert_eq!(keys(&reloaded.shown.tree), finished);
+        assert_eq!(reloaded.save_inputs(), visualizer.save_inputs());
+    }
+}
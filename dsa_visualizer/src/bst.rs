@@ -0,0 +1,26 @@
+use crate::binary_tree::{bst_delete, bst_insert, Frames, NodeStyle, Tree, TreeKind, TreeVisualizer};
+use crate::theme::{Role, Theme};
+
+/// Unbalanced binary search tree, the baseline the AVL and red-black trees improve on.
+pub struct Bst;
+
+pub type BSTVisualizer = TreeVisualizer<Bst>;
+
+impl TreeKind for Bst {
+    const NAME: &'static str = "Binary Search Tree";
+
+    fn insert(tree: &mut Tree, key: i32, frames: &mut Frames) {
+        bst_insert(tree, key, frames);
+    }
+
+    fn delete(tree: &mut Tree, key: i32, frames: &mut Frames) {
+        bst_delete(tree, key, frames);
+    }
+
+    fn style(_tree: &Tree, _i: usize, theme: &Theme) -> NodeStyle {
+        NodeStyle {
+            fill: theme.color(Role::Unvisited),
+            label: None,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::sorting::{Recorder, SortKind, SortVisualizer};
+use serde::{Deserialize, Serialize};
+
+/// Swaps neighbors that are out of order until a full pass makes no swap.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Bubble;
+
+/// Bubble sort that alternates direction, so small values near the end ("turtles")
+/// move left as fast as large values move right.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Cocktail;
+
+pub type BubbleSortVisualizer = SortVisualizer<Bubble>;
+pub type CocktailSortVisualizer = SortVisualizer<Cocktail>;
+
+/// Compares a[i] with a[i + 1] and swaps them if they are out of order.
+fn bubble(rec: &mut Recorder, i: usize) -> bool {
+    if rec.less(i + 1, i) {
+        rec.swap(i, i + 1);
+        true
+    } else {
+        false
+    }
+}
+
+impl SortKind for Bubble {
+    const NAME: &'static str = "Bubble sort";
+    const STABLE: bool = true;
+
+    fn sort(&self, rec: &mut Recorder) {
+        let mut end = rec.len();
+        while end > 1 {
+            let mut swapped = false;
+            for i in 0..end - 1 {
+                swapped |= bubble(rec, i);
+            }
+            end -= 1;
+            if !swapped {
+                rec.mark(0..end, "No swaps in this pass, the array is sorted".to_string());
+                break;
+            }
+            rec.mark(end..rec.len(), format!("Pass done, a[{}..] holds the largest values", end));
+        }
+    }
+}
+
+impl SortKind for Cocktail {
+    const NAME: &'static str = "Cocktail shaker sort";
+    const STABLE: bool = true;
+
+    fn sort(&self, rec: &mut Recorder) {
+        let (mut start, mut end) = (0, rec.len());
+        while end - start > 1 {
+            let mut swapped = false;
+            for i in start..end - 1 {
+                swapped |= bubble(rec, i);
+            }
+            end -= 1;
+            if !swapped {
+                break;
+            }
+            rec.mark(end..rec.len(), "Forward pass done, largest value bubbled right".to_string());
+
+            swapped = false;
+            for i in (start..end - 1).rev() {
+                swapped |= bubble(rec, i);
+            }
+            start += 1;
+            if !swapped {
+                break;
+            }
+            rec.mark(0..start, "Backward pass done, smallest value bubbled left".to_string());
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::algorithm::Algorithm;
+use crate::{
+    avl_tree, bfs, bst, bubble_sort, counting_sort, dfs, dijkstra, euclidean, gradient_descent, grid_pathfinding,
+    hash_table, heap_sort, insertion_sort, longest_common_sequence, max_flow, merge_sort, prim, radix_sort, red_black_tree,
+    selection_sort, shell_sort, strongly_connected_components, suffix_array, tim_sort, topological_sort, trie,
+};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Category {
+    Sorting,
+    Graphs,
+    DataStructures,
+    Strings,
+    DynamicProgramming,
+    Math,
+    MachineLearning,
+}
+
+impl Category {
+    pub const ALL: [Category; 7] = [
+        Category::Sorting,
+        Category::Graphs,
+        Category::DataStructures,
+        Category::Strings,
+        Category::DynamicProgramming,
+        Category::Math,
+        Category::MachineLearning,
+    ];
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Category::Sorting => "Sorting",
+            Category::Graphs => "Graphs",
+            Category::DataStructures => "Data Structures",
+            Category::Strings => "Strings",
+            Category::DynamicProgramming => "Dynamic Programming",
+            Category::Math => "Math",
+            Category::MachineLearning => "Machine Learning",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Everything the menu needs to list an algorithm and open its visualizer.
+pub struct AlgorithmEntry {
+    pub name: &'static str,
+    pub category: Category,
+    pub complexity: &'static str,
+    pub description: &'static str,
+    pub factory: fn() -> Box<dyn Algorithm>,
+}
+
+impl AlgorithmEntry {
+    /// Builds the visualizer and runs its `initialize`.
+    pub fn create(&self) -> Box<dyn Algorithm> {
+        let mut algorithm = (self.factory)();
+        algorithm.initialize();
+        algorithm
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.name.to_lowercase().contains(&query)
+            || self.description.to_lowercase().contains(&query)
+            || self.category.to_string().to_lowercase().contains(&query)
+    }
+}
+
+#[derive(Default)]
+pub struct Registry {
+    entries: Vec<AlgorithmEntry>,
+}
+
+impl Registry {
+    pub fn register(&mut self, entry: AlgorithmEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn get(&self, index: usize) -> Option<&AlgorithmEntry> {
+        self.entries.get(index)
+    }
+
+    pub fn entries(&self) -> &[AlgorithmEntry] {
+        &self.entries
+    }
+
+    /// Index of the entry named exactly `name`, as saved in a session.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|e| e.name == name)
+    }
+
+    /// Looks an entry up by name, ignoring case. Falls back to the only name that
+    /// contains `name`, so "dijkstra" finds "Dijkstra's Algorithm".
+    pub fn find(&self, name: &str) -> Option<&AlgorithmEntry> {
+        let name = name.trim().to_lowercase();
+        if let Some(entry) = self.entries.iter().find(|e| e.name.to_lowercase() == name) {
+            return Some(entry);
+        }
+        let mut matches = self.entries.iter().filter(|e| e.name.to_lowercase().contains(&name));
+        match (matches.next(), matches.next()) {
+            (Some(entry), None) => Some(entry),
+            _ => None,
+        }
+    }
+
+    /// Entries matching `query`, grouped by category in menu order. Each entry keeps
+    /// its registry index so the caller can open it later.
+    pub fn grouped(&self, query: &str) -> Vec<(Category, Vec<(usize, &AlgorithmEntry)>)> {
+        Category::ALL
+            .iter()
+            .map(|&category| {
+                let entries = self
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| entry.category == category && entry.matches(query))
+                    .collect::<Vec<_>>();
+                (category, entries)
+            })
+            .filter(|(_, entries)| !entries.is_empty())
+            .collect()
+    }
+}
+
+/// Every visualizer in the app. Adding an algorithm means adding one entry here.
+pub fn default_registry() -> Registry {
+    let mut registry = Registry::default();
+
+    registry.register(AlgorithmEntry {
+        name: "Merge Sort",
+        category: Category::Sorting,
+        complexity: "O(n log n)",
+        description: "Splits the array in halves and merges sorted runs back together.",
+        factory: || Box::new(merge_sort::MergeSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Heap Sort",
+        category: Category::Sorting,
+        complexity: "O(n log n)",
+        description: "Builds a max heap, then repeatedly moves the root to the end of the array.",
+        factory: || Box::new(heap_sort::HeapSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Insertion Sort",
+        category: Category::Sorting,
+        complexity: "O(n^2)",
+        description: "Sinks each value left into a growing sorted prefix. Stable and fast on nearly sorted input.",
+        factory: || Box::new(insertion_sort::InsertionSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Selection Sort",
+        category: Category::Sorting,
+        complexity: "O(n^2)",
+        description: "Swaps the minimum of the unsorted part to its front. Few writes, but not stable.",
+        factory: || Box::new(selection_sort::SelectionSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Bubble Sort",
+        category: Category::Sorting,
+        complexity: "O(n^2)",
+        description: "Swaps adjacent values until a pass makes no swap.",
+        factory: || Box::new(bubble_sort::BubbleSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Cocktail Shaker Sort",
+        category: Category::Sorting,
+        complexity: "O(n^2)",
+        description: "Bubble sort in alternating directions so small values at the end move quickly.",
+        factory: || Box::new(bubble_sort::CocktailSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Shell Sort",
+        category: Category::Sorting,
+        complexity: "O(n^(4/3)) to O(n^2) by gaps",
+        description: "Insertion sort over shrinking gaps, with Shell, Knuth, Hibbard or Ciura sequences.",
+        factory: || Box::new(shell_sort::ShellSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Counting Sort",
+        category: Category::Sorting,
+        complexity: "O(n + k)",
+        description: "Counts keys, prefix sums the counts and places values into an output buffer.",
+        factory: || Box::new(counting_sort::CountingSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Radix Sort (LSD)",
+        category: Category::Sorting,
+        complexity: "O(d (n + b))",
+        description: "Distributes values into ten buckets one digit at a time, least significant first.",
+        factory: || Box::new(radix_sort::RadixSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Timsort",
+        category: Category::Sorting,
+        complexity: "O(n log n)",
+        description: "Detects natural runs, extends them with binary insertion and merges with galloping.",
+        factory: || Box::new(tim_sort::TimSortVisualizer::new()),
+    });
+
+    registry.register(AlgorithmEntry {
+        name: "Breadth First Search (BFS)",
+        category: Category::Graphs,
+        complexity: "O(V + E)",
+        description: "Visits a tree level by level using a queue.",
+        factory: || Box::new(bfs::BFSVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Depth First Search (DFS)",
+        category: Category::Graphs,
+        complexity: "O(V + E)",
+        description: "Follows each branch as deep as possible using a stack.",
+        factory: || Box::new(dfs::DFSVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Dijkstra's Algorithm",
+        category: Category::Graphs,
+        complexity: "O((V + E) log V)",
+        description: "Shortest paths from a source over non-negative edge weights with a priority queue.",
+        factory: || Box::new(dijkstra::DijkstraVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Grid Pathfinding (BFS / Dijkstra / A*)",
+        category: Category::Graphs,
+        complexity: "O(N log N) for N cells",
+        description: "Paint walls and terrain on a maze and compare how many cells each search expands.",
+        factory: || Box::new(grid_pathfinding::GridPathfindingVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Topological Sort",
+        category: Category::Graphs,
+        complexity: "O(V + E)",
+        description: "Orders a DAG with Kahn's in-degree queue or DFS finish times, and reports cycles.",
+        factory: || Box::new(topological_sort::TopologicalSortVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Strongly Connected Components",
+        category: Category::Graphs,
+        complexity: "O(V + E)",
+        description: "Tarjan's low-link search or Kosaraju's two passes over a directed graph.",
+        factory: || Box::new(strongly_connected_components::SCCVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Prim's Minimum Spanning Tree",
+        category: Category::Graphs,
+        complexity: "O(E log V)",
+        description: "Grows a spanning tree from one vertex by always taking the cheapest frontier edge.",
+        factory: || Box::new(prim::PrimVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Max Flow (Edmonds-Karp)",
+        category: Category::Graphs,
+        complexity: "O(V E^2)",
+        description: "Augments along BFS paths in the residual graph and finishes with the min cut.",
+        factory: || Box::new(max_flow::MaxFlowVisualizer::new()),
+    });
+
+    registry.register(AlgorithmEntry {
+        name: "Binary Search Tree",
+        category: Category::DataStructures,
+        complexity: "O(h) per operation",
+        description: "Insert, delete and search without any rebalancing.",
+        factory: || Box::new(bst::BSTVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "AVL Tree",
+        category: Category::DataStructures,
+        complexity: "O(log n) per operation",
+        description: "Height balanced tree with single and double rotations driven by balance factors.",
+        factory: || Box::new(avl_tree::AVLVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Red-Black Tree",
+        category: Category::DataStructures,
+        complexity: "O(log n) per operation",
+        description: "Balanced by node colors, with recoloring and rotation cases on insert and delete.",
+        factory: || Box::new(red_black_tree::RedBlackVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Hash Table",
+        category: Category::DataStructures,
+        complexity: "O(1) expected per operation",
+        description: "Chaining, linear and quadratic probing or double hashing, with tombstones and rehash on grow.",
+        factory: || Box::new(hash_table::HashTableVisualizer::new()),
+    });
+
+    registry.register(AlgorithmEntry {
+        name: "Trie",
+        category: Category::Strings,
+        complexity: "O(m) per word of length m",
+        description: "Prefix tree with insert, search and alphabetical autocomplete.",
+        factory: || Box::new(trie::TrieVisualizer::new()),
+    });
+    registry.register(AlgorithmEntry {
+        name: "Suffix Array",
+        category: Category::Strings,
+        complexity: "O(n log^2 n) build, O(m log n) query",
+        description: "Prefix doubling, Kasai's LCP array and substring search by binary search over sorted suffixes.",
+        factory: || Box::new(suffix_array::SuffixArrayVisualizer::new()),
+    });
+
+    registry.register(AlgorithmEntry {
+        name: "Longest Common Sequence",
+        category: Category::DynamicProgramming,
+        complexity: "O(m n)",
+        description: "Fills the DP table for two strings and walks back to recover the subsequence.",
+        factory: || Box::new(longest_common_sequence::LCSVisualizer::new()),
+    });
+
+    registry.register(AlgorithmEntry {
+        name: "Euclidean Algorithm",
+        category: Category::Math,
+        complexity: "O(log min(a, b))",
+        description: "Greatest common divisor by repeated remainders.",
+        factory: || Box::new(euclidean::EuclideanVisualizer::new()),
+    });
+
+    registry.register(AlgorithmEntry {
+        name: "Gradient Descent",
+        category: Category::MachineLearning,
+        complexity: "O(k) for k iterations",
+        description: "Minimises (x - 3)^2 by stepping against the gradient.",
+        factory: || Box::new(gradient_descent::GradientDescentVisualizer::new()),
+    });
+
+    registry
+}
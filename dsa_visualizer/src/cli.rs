@@ -0,0 +1,136 @@
+use crate::benchmark::{self, Benchmark, Metric, SUBJECTS};
+use crate::catalog;
+use crate::export::{self, ExportFormat, ExportSettings};
+use std::path::Path;
+use std::time::Duration;
+
+const USAGE: &str = "Usage:
+  dsa_visualizer                      open the visualizer window
+  dsa_visualizer list                 list the algorithms that can be exported
+  dsa_visualizer export <algorithm> <output> [options]
+  dsa_visualizer bench [--max-size <n>]
+                                      measure how the sorts, Dijkstra and LCS grow with
+                                      the input size (default up to n = 4096)
+
+Export options:
+  --format gif|apng|png   png writes numbered frames into <output> as a directory
+                          (default: from the extension of <output>)
+  --width <px>            frame width (default 960)
+  --height <px>           frame height (default 640)
+  --delay <ms>            time each frame is shown (default 500)
+  --max-frames <n>        stop after this many frames, at least 1 (default 300)
+  --theme dark|light      background and widget colors (default dark)
+  --palette classic|okabe-ito|tol
+                          colors for visited, current, compared ... (default okabe-ito)";
+
+/// Runs a headless subcommand and returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let result = match args.first().map(String::as_str) {
+        Some("list") => {
+            list();
+            Ok(())
+        }
+        Some("export") => export(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(format!("unknown command \"{}\"", other)),
+        None => Err("no command given".to_string()),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            1
+        }
+    }
+}
+
+fn list() {
+    let registry = catalog::default_registry();
+    for entry in registry.entries() {
+        println!("{:<45} {}", entry.name, entry.category);
+    }
+}
+
+fn export(args: &[String]) -> Result<(), String> {
+    let [name, output, options @ ..] = args else {
+        return Err("export needs an algorithm name and an output path".to_string());
+    };
+
+    let registry = catalog::default_registry();
+    let entry = registry
+        .find(name)
+        .ok_or_else(|| format!("no single algorithm matches \"{}\", see `dsa_visualizer list`", name))?;
+
+    let mut settings = ExportSettings::new();
+    settings.path = output.clone();
+    settings.format = ExportFormat::from_path(Path::new(output));
+
+    let mut options = options.iter();
+    while let Some(flag) = options.next() {
+        let value = options.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        let number = || value.parse::<u32>().map_err(|_| format!("{} expects a number, got \"{}\"", flag, value));
+        match flag.as_str() {
+            "--format" => settings.format = value.parse()?,
+            "--width" => settings.width = number()?,
+            "--height" => settings.height = number()?,
+            "--delay" => settings.frame_delay_ms = number()?,
+            "--max-frames" => {
+                settings.max_frames = match number()? {
+                    0 => return Err("--max-frames must be at least 1".to_string()),
+                    frames => frames as usize,
+                }
+            }
+            "--theme" => {
+                settings.theme.dark = match value.as_str() {
+                    "dark" => true,
+                    "light" => false,
+                    other => return Err(format!("unknown theme \"{}\", expected dark or light", other)),
+                }
+            }
+            "--palette" => settings.theme.palette = value.parse()?,
+            other => return Err(format!("unknown option \"{}\"", other)),
+        }
+    }
+
+    let mut algorithm = entry.create();
+    let frames = export::export(algorithm.as_mut(), &settings).map_err(|e| e.to_string())?;
+    println!("Wrote {} frames of {} to {}", frames, entry.name, settings.path);
+    Ok(())
+}
+
+fn bench(args: &[String]) -> Result<(), String> {
+    let mut benchmark = Benchmark::new();
+    match args {
+        [] => {}
+        [flag, value] if flag == "--max-size" => {
+            let size = value.parse::<usize>().map_err(|_| format!("--max-size expects a number, got \"{}\"", value))?;
+            benchmark.set_max_size(size);
+        }
+        [other, ..] => return Err(format!("unknown option \"{}\"", other)),
+    }
+
+    benchmark.start();
+    while benchmark.poll() {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    println!("{:<28} {:<32} {:>10} {:>10} {:>10}", "algorithm", "expected", "ops slope", "time slope", "largest n");
+    for (subject, series) in SUBJECTS.iter().zip(&benchmark.results) {
+        let slope = |metric| benchmark::slope(&series.samples, metric).map_or("-".to_string(), |k| format!("n^{:.2}", k));
+        let largest = series.samples.last().map_or(0, |s| s.n);
+        println!(
+            "{:<28} {:<32} {:>10} {:>10} {:>10}",
+            subject.name,
+            subject.expected,
+            slope(Metric::Operations),
+            slope(Metric::Time),
+            largest
+        );
+    }
+    Ok(())
+}
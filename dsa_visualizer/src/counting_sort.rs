@@ -0,0 +1,50 @@
+use crate::sorting::{Recorder, SortKind, SortVisualizer};
+use serde::{Deserialize, Serialize};
+
+/// Counts each key, turns the counts into end positions with a prefix sum, then
+/// places items from the back so equal keys keep their order.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Counting;
+
+pub type CountingSortVisualizer = SortVisualizer<Counting>;
+
+impl SortKind for Counting {
+    const NAME: &'static str = "Counting sort";
+    const STABLE: bool = true;
+
+    fn sort(&self, rec: &mut Recorder) {
+        let n = rec.len();
+        let min = (0..n).map(|i| rec.key(i)).min().unwrap_or(0);
+        let max = (0..n).map(|i| rec.key(i)).max().unwrap_or(0);
+        let mut counts = vec![0usize; (max - min + 1) as usize];
+
+        for i in 0..n {
+            let key = rec.key(i);
+            counts[(key - min) as usize] += 1;
+            rec.mark([i], format!("count[{}] = {}", key, counts[(key - min) as usize]));
+        }
+
+        for k in 1..counts.len() {
+            counts[k] += counts[k - 1];
+        }
+        rec.mark(
+            [],
+            format!("Prefix sums: count[k] is now one past the last slot for key k, {} keys in {}..={}", n, min, max),
+        );
+
+        for i in (0..n).rev() {
+            let item = rec.items[i];
+            let count = &mut counts[(item.key - min) as usize];
+            *count -= 1;
+            let slot = *count;
+            rec.put_in_buffer(slot, item);
+            rec.note(format!("a[{}] = {} goes to output slot {}", i, item.key, slot));
+        }
+
+        for i in 0..n {
+            let item = rec.buffered(i);
+            rec.write(i, item);
+        }
+        rec.clear_buffer();
+    }
+}
@@ -1,6 +1,8 @@
 use std::collections::VecDeque;
-use std::time::Instant;
-use crate::algorithm::Algorithm;
+use crate::algorithm::{Action, Algorithm};
+use crate::graph;
+use crate::pan_zoom;
+use crate::theme::{Role, Theme};
 use eframe::egui;
 
 pub struct DFSVisualizer {
@@ -9,8 +11,7 @@ pub struct DFSVisualizer {
     visited: Vec<usize>,
     dfs_stack: VecDeque<usize>,
     tree_created: bool,
-    auto_traverse: bool,
-    last_step_time: Option<Instant>,
+    clicked: Option<usize>,
 }
 
 impl DFSVisualizer {
@@ -22,8 +23,7 @@ impl DFSVisualizer {
             visited: Vec::new(),
             dfs_stack: VecDeque::new(),
             tree_created: false,
-            auto_traverse: false,
-            last_step_time: None,
+            clicked: None,
         }
     }
 
@@ -33,8 +33,6 @@ impl DFSVisualizer {
             self.dfs_stack.clear();
             self.current_node = None;
             self.dfs_stack.push_back(1);
-            self.auto_traverse = true;
-            self.last_step_time = None;
         }
     }
 
@@ -63,7 +61,6 @@ impl DFSVisualizer {
         self.visited.clear();
         self.dfs_stack.clear();
         self.tree_created = true;
-        self.auto_traverse = false;
     }
     fn dfs_step(&mut self) {
         if let Some(current) = self.dfs_stack.pop_back() {
@@ -86,14 +83,9 @@ impl DFSVisualizer {
                 self.dfs_stack.extend(children);
             }
         }
-
-        if self.dfs_stack.is_empty() {
-            self.current_node = None;
-            self.auto_traverse = false;
-        }
     }
 
-    fn render_tree(&self, ui: &mut egui::Ui) {
+    fn render_tree(&self, ui: &mut egui::Ui) -> Option<usize> {
         let total_levels = self.nodes.iter().map(|(_, _, level)| level).max().unwrap_or(&0) + 1;
         let node_spacing = 70.0;
         let screen_width = ui.available_width();
@@ -101,6 +93,9 @@ impl DFSVisualizer {
         let height = (total_levels as f32 * 120.0).max(600.0);
         let level_spacing = height / (total_levels as f32 + 1.0);
         let node_radius = 30.0;
+        let theme = Theme::current(ui.ctx());
+        let (response, painter) = ui.allocate_painter(egui::vec2(screen_width, level_spacing * total_levels as f32), egui::Sense::hover());
+        let origin = response.rect.min;
 
         let mut levels: Vec<Vec<(usize, usize)>> = vec![Vec::new(); total_levels];
         for &(node_id, parent_id, level) in &self.nodes {
@@ -116,73 +111,86 @@ impl DFSVisualizer {
             let x_spacing = width / (level_count as f32 + 1.0);
 
             for (i, &(node_id, _)) in nodes.iter().enumerate() {
-                let x = center_offset + x_spacing * (i as f32 + 1.0);
-                let y = level_spacing * (level as f32 + 1.0);
+                let x = origin.x + center_offset + x_spacing * (i as f32 + 1.0);
+                let y = origin.y + level_spacing * (level as f32 + 0.5);
                 positions.push((node_id, x, y));
             }
         }
 
         for &(node_id, parent_id, _) in &self.nodes {
             if let Some(&(_, x, y)) = positions.iter().find(|&&(id, _, _)| id == node_id) {
-                let color = if self.current_node == Some(node_id) {
-                    egui::Color32::GREEN
+                let color = theme.color(if self.current_node == Some(node_id) {
+                    Role::Current
                 } else if self.visited.contains(&node_id) {
-                    egui::Color32::LIGHT_BLUE
+                    Role::Visited
+                } else if self.dfs_stack.contains(&node_id) {
+                    Role::Frontier
                 } else {
-                    egui::Color32::RED
-                };
+                    Role::Unvisited
+                });
 
-                ui.painter().circle_filled(egui::pos2(x, y), node_radius, color);
+                painter.circle_filled(egui::pos2(x, y), node_radius, color);
 
                 if parent_id != 0 {
                     if let Some(&(_, px, py)) = positions.iter().find(|&&(id, _, _)| id == parent_id) {
-                        ui.painter().line_segment(
+                        painter.line_segment(
                             [egui::pos2(px, py + (node_radius)), egui::pos2(x, y - (node_radius))],
-                            egui::Stroke::new(2.0, egui::Color32::GRAY),
+                            egui::Stroke::new(2.0, theme.line()),
                         );
                     }
                 }
 
                 // Render the node ID as text
-                ui.painter().text(
+                painter.text(
                     egui::pos2(x, y - 10.0),
                     egui::Align2::CENTER_BOTTOM,
                     format!("{}", node_id),
                     egui::TextStyle::Body.resolve(ui.style()),
-                    egui::Color32::BLACK,
+                    theme.text_on(color),
                 );
             }
         }
+
+        let centers = positions.iter().map(|&(id, x, y)| (id, egui::pos2(x, y)));
+        graph::clicked_node(ui, centers, node_radius)
     }
 
 }
 
 impl Algorithm for DFSVisualizer {
-    fn initialize(&mut self) { self.create_example_tree() }
+    fn initialize(&mut self) {
+        self.create_example_tree();
+        self.start_dfs();
+    }
 
     fn step(&mut self) { self.dfs_step() }
 
     fn render(&mut self, ui: &mut eframe::egui::Ui) {
-        self.render_tree(ui)    ;
+        self.clicked = pan_zoom::show(ui, "tree", |ui| self.render_tree(ui));
     }
 
-    fn auto_play(&self) -> bool {
-        self.auto_traverse
+    fn is_finished(&self) -> bool {
+        self.dfs_stack.is_empty()
     }
 
-    fn toggle_auto_traverse(&mut self) {
-        self.auto_traverse = !self.auto_traverse;
+    fn reset(&mut self) {
+        self.start_dfs();
     }
 
-    fn start(&mut self) {
-        self.start_dfs();
+    fn peek(&self) -> Option<Action> {
+        self.dfs_stack.back().map(|&node| Action::Visit(node))
     }
 
-    fn last_step_time(&self) -> Option<std::time::Instant> {
-        self.last_step_time
+    fn take_click(&mut self) -> Option<usize> {
+        self.clicked.take()
     }
 
-    fn set_last_step_time(&mut self, time: Option<std::time::Instant>) {
-        self.last_step_time = time;
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![
+            (Role::Unvisited, "Not reached"),
+            (Role::Frontier, "On the stack"),
+            (Role::Current, "Visiting"),
+            (Role::Visited, "Visited"),
+        ]
     }
-}
\ No newline at end of file
+}
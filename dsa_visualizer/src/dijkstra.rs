@@ -1,42 +1,47 @@
-use crate::algorithm::Algorithm;
+use crate::algorithm::{Action, Algorithm};
+use crate::graph::{self, Graph};
+use crate::pan_zoom;
+use crate::theme::{Role, Theme};
 use eframe::egui;
-use egui::{Color32, Pos2, Shape, Stroke};
+use egui::{Pos2, Shape, Stroke};
 use std::collections::BinaryHeap;
-use std::collections::HashMap;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+
+type Queue = BinaryHeap<std::cmp::Reverse<(usize, usize)>>;
 
 pub struct DijkstraVisualizer {
-    graph: HashMap<usize, Vec<(usize, usize)>>,
+    graph: Graph,
     distances: HashMap<usize, usize>,
     previous: HashMap<usize, Option<usize>>,
     visited: Vec<usize>,
     current: Option<usize>,
     source: usize,
     target: Option<usize>,
-    is_running: bool,
-    last_step_time: Option<Instant>,
-    auto_play: bool,
-    heap: BinaryHeap<std::cmp::Reverse<(usize, usize)>>,
+    finished: bool,
+    clicked: Option<usize>,
+    heap: Queue,
+    /// What the last step did, shown under the graph.
+    message: String,
 }
 
 impl DijkstraVisualizer {
     pub fn new() -> Self {
         Self {
-            graph: HashMap::new(),
+            graph: Graph::new(),
             distances: HashMap::new(),
             previous: HashMap::new(),
             visited: Vec::new(),
             current: None,
             source: 0,
             target: None,
-            is_running: false,
-            last_step_time: None,
-            auto_play: false,
+            finished: false,
+            clicked: None,
             heap: BinaryHeap::new(),
+            message: String::new(),
         }
     }
 
-    pub fn initialize_graph(&mut self, graph: HashMap<usize, Vec<(usize, usize)>>, source: usize) {
+    pub fn initialize_graph(&mut self, graph: Graph, source: usize) {
         self.graph = graph;
         self.source = source;
         self.target = None;
@@ -45,6 +50,12 @@ impl DijkstraVisualizer {
         self.visited.clear();
         self.current = None;
         self.heap.clear();
+        self.finished = false;
+        self.message = if self.graph.contains_key(&source) {
+            format!("Starting from node {}", source)
+        } else {
+            format!("Source node {} is not in the graph", source)
+        };
 
         for &node in self.graph.keys() {
             self.distances.insert(node, usize::MAX);
@@ -55,86 +66,16 @@ impl DijkstraVisualizer {
             self.heap.push(std::cmp::Reverse((0, source)));
         }
     }
-}
-
-impl Algorithm for DijkstraVisualizer {
-    fn initialize(&mut self) {
-        // test graph
-        let mut example_graph = HashMap::new();
-        example_graph.insert(0, vec![(1, 1), (3, 4)]);
-        example_graph.insert(1, vec![(4, 1)]);
-        example_graph.insert(2, vec![(5, 6), (0, 5), (3, 1)]);
-        example_graph.insert(3, vec![(4, 2), (3, 3)]);
-        example_graph.insert(4, vec![(5, 2), (2, 1)]);
-        example_graph.insert(5, vec![(3, 2)]);
-
-        self.initialize_graph(example_graph, 0);
-    }
-    fn step(&mut self) {
-        if !self.is_running {
-            println!("Algorithm finished");
-            self.auto_play = false;
-            return;
-        }
-
-        if let Some(std::cmp::Reverse((cost, node))) = self.heap.pop() {
-            if self.visited.contains(&node) {
-                return;
-            }
-
-            println!("Processing node: {:?} with cost: {}", node, cost);
-
-            self.visited.push(node);
-            self.current = Some(node);
-
-            if let Some(target) = self.target {
-                if node == target {
-                    self.is_running = false;
-                    println!(
-                        "Target node reached: {:?}. Algorithm terminated.",
-                        target
-                    );
-                    return;
-                }
-            }
-
-            if let Some(neighbors) = self.graph.get(&node) {
-                for &(neighbor, weight) in neighbors {
-                    let new_cost = cost + weight;
-
-                    println!(
-                        "Inspecting edge: {} -> {} with weight: {}. New cost would be: {}",
-                        node, neighbor, weight, new_cost
-                    );
-
-                    if new_cost < *self.distances.get(&neighbor).unwrap_or(&usize::MAX) {
-                        self.distances.insert(neighbor, new_cost);
-                        self.previous.insert(neighbor, Some(node));
-
-                        self.heap.push(std::cmp::Reverse((new_cost, neighbor)));
-
-                        println!(
-                            "Updated distance for node {:?} to {}. Added to heap.",
-                            neighbor, new_cost
-                        );
-                    }
-                }
-            }
-
-            println!("Finished processing node: {:?}", node);
-        } else {
-            // If the heap is empty, the algorithm is done
-            self.is_running = false;
-            println!("Algorithm completed. No further steps are required.");
-        }
-    }
 
-    fn render(&mut self, ui: &mut egui::Ui) {
-        let painter = ui.painter();
+    /// Draws the nodes on a circle and returns the one clicked, if any.
+    fn render_graph(&self, ui: &mut egui::Ui) -> Option<usize> {
         let node_radius = 30.0;
-        let center_x = ui.min_rect().center().x;
-        let center_y = ui.min_rect().center().y;
         let graph_radius = 150.0;
+        let theme = Theme::current(ui.ctx());
+        let size = egui::vec2(ui.available_width(), 2.0 * (graph_radius + node_radius) + 20.0);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let center_x = response.rect.center().x;
+        let center_y = response.rect.center().y;
         let nodes_count = self.graph.len();
 
         let positions: Vec<(usize, Pos2)> = self
@@ -160,7 +101,7 @@ impl Algorithm for DijkstraVisualizer {
 
                         painter.line_segment(
                             [from_pos.1, to_pos.1],
-                            Stroke::new(2.0, Color32::LIGHT_BLUE), // default edge color
+                            Stroke::new(2.0, theme.line()),
                         );
 
                         let midpoint = Pos2::new(
@@ -173,7 +114,7 @@ impl Algorithm for DijkstraVisualizer {
                             egui::Align2::CENTER_CENTER,
                             weight.to_string(),
                             egui::FontId::new(21.0, egui::FontFamily::Proportional),
-                            Color32::DARK_GRAY,
+                            theme.muted(),
                         );
                     }
                 }
@@ -181,13 +122,16 @@ impl Algorithm for DijkstraVisualizer {
         }
 
         for (node, pos) in positions.iter() {
-            let color = if Some(node) == self.current.as_ref() {
-                Color32::BLUE
-            } else if self.visited.contains(&node) {
-                Color32::GREEN
+            let queued = self.heap.iter().any(|std::cmp::Reverse((_, n))| n == node);
+            let color = theme.color(if Some(node) == self.current.as_ref() {
+                Role::Current
+            } else if self.visited.contains(node) {
+                Role::Visited
+            } else if queued {
+                Role::Frontier
             } else {
-                Color32::GRAY
-            };
+                Role::Unvisited
+            });
 
             painter.add(Shape::circle_filled(*pos, node_radius, color));
 
@@ -196,44 +140,152 @@ impl Algorithm for DijkstraVisualizer {
                 egui::Align2::CENTER_CENTER,
                 node.to_string(),
                 egui::FontId::new(16.0, egui::FontFamily::Proportional),
-                Color32::BLACK,
+                theme.text_on(color),
             );
         }
 
-        ui.separator();
-        if self.is_running {
-            ui.label(format!("Currently Visiting: {:?}", self.current));
-            ui.label(format!("Visited Nodes: {:?}", self.visited));
-        }
+        graph::clicked_node(ui, positions, node_radius)
     }
+}
 
-    fn auto_play(&self) -> bool {
-        self.auto_play
+/// Lowers the distance of every neighbor of `node` that is cheaper to reach through it
+/// and queues it again.
+fn relax(
+    graph: &Graph,
+    node: usize,
+    cost: usize,
+    distances: &mut HashMap<usize, usize>,
+    previous: &mut HashMap<usize, Option<usize>>,
+    heap: &mut Queue,
+) {
+    for &(neighbor, weight) in graph.get(&node).into_iter().flatten() {
+        let new_cost = cost + weight;
+        if new_cost < *distances.get(&neighbor).unwrap_or(&usize::MAX) {
+            distances.insert(neighbor, new_cost);
+            previous.insert(neighbor, Some(node));
+            heap.push(std::cmp::Reverse((new_cost, neighbor)));
+        }
     }
+}
 
-    fn toggle_auto_traverse(&mut self) {
-        self.auto_play = !self.auto_play;
+/// Distances from `source` to every reachable node, computed in one go without the
+/// visualizer. Also returns the operations it took: heap pops plus edges inspected.
+pub fn shortest_paths(graph: &Graph, source: usize) -> (HashMap<usize, usize>, usize) {
+    let mut distances = HashMap::from([(source, 0)]);
+    let mut previous = HashMap::new();
+    let mut settled = HashSet::new();
+    let mut heap = Queue::from([std::cmp::Reverse((0, source))]);
+    let mut operations = 0;
+
+    while let Some(std::cmp::Reverse((cost, node))) = heap.pop() {
+        operations += 1;
+        if !settled.insert(node) {
+            continue;
+        }
+        operations += graph.get(&node).map_or(0, Vec::len);
+        relax(graph, node, cost, &mut distances, &mut previous, &mut heap);
     }
+    (distances, operations)
+}
+
+impl Algorithm for DijkstraVisualizer {
+    fn initialize(&mut self) {
+        // test graph
+        let mut example_graph = HashMap::new();
+        example_graph.insert(0, vec![(1, 1), (3, 4)]);
+        example_graph.insert(1, vec![(4, 1)]);
+        example_graph.insert(2, vec![(5, 6), (0, 5), (3, 1)]);
+        example_graph.insert(3, vec![(4, 2), (3, 3)]);
+        example_graph.insert(4, vec![(5, 2), (2, 1)]);
+        example_graph.insert(5, vec![(3, 2)]);
 
-    fn start(&mut self) {
-        if !self.graph.contains_key(&self.source) {
-            println!("Source node {:?} is not in the graph.", self.source);
+        self.initialize_graph(example_graph, 0);
+    }
+    fn step(&mut self) {
+        if self.finished {
             return;
         }
 
-        self.is_running = true;
-        self.auto_play = true;
+        // Entries for nodes that were already settled are stale copies, skip past them.
+        while let Some(&std::cmp::Reverse((_, node))) = self.heap.peek() {
+            if !self.visited.contains(&node) {
+                break;
+            }
+            self.heap.pop();
+        }
+
+        if let Some(std::cmp::Reverse((cost, node))) = self.heap.pop() {
+            self.visited.push(node);
+            self.current = Some(node);
 
-        if self.heap.is_empty() {
-            self.heap.push(std::cmp::Reverse((0, self.source)));
+            if let Some(target) = self.target {
+                if node == target {
+                    self.finished = true;
+                    self.message = format!("Reached target node {} at distance {}", target, cost);
+                    return;
+                }
+            }
+
+            let before = self.heap.len();
+            relax(&self.graph, node, cost, &mut self.distances, &mut self.previous, &mut self.heap);
+            self.message = match self.heap.len() - before {
+                0 => format!("Settled node {} at distance {}, no neighbor got closer", node, cost),
+                1 => format!("Settled node {} at distance {}, 1 neighbor got closer", node, cost),
+                improved => format!("Settled node {} at distance {}, {} neighbors got closer", node, cost, improved),
+            };
+        } else {
+            // If the heap is empty, the algorithm is done
+            self.finished = true;
+            self.message = "Every reachable node is settled".to_string();
         }
     }
 
-    fn last_step_time(&self) -> Option<Instant> {
-        self.last_step_time
+    fn render(&mut self, ui: &mut egui::Ui) {
+        self.clicked = pan_zoom::show(ui, "graph", |ui| self.render_graph(ui));
+
+        ui.separator();
+        ui.label(&self.message);
+        if self.current.is_some() {
+            ui.label(format!("Currently Visiting: {:?}", self.current));
+            ui.label(format!("Visited Nodes: {:?}", self.visited));
+        }
+    }
+
+    /// Done once the target is reached or every entry left on the heap is a stale
+    /// copy of a node that has already been visited.
+    fn is_finished(&self) -> bool {
+        self.finished
+            || self
+                .heap
+                .iter()
+                .all(|std::cmp::Reverse((_, node))| self.visited.contains(node))
+    }
+
+    fn reset(&mut self) {
+        self.initialize_graph(self.graph.clone(), self.source);
     }
 
-    fn set_last_step_time(&mut self, time: Option<Instant>) {
-        self.last_step_time = time;
+    fn peek(&self) -> Option<Action> {
+        if self.finished {
+            return None;
+        }
+        self.heap
+            .iter()
+            .filter(|std::cmp::Reverse((_, node))| !self.visited.contains(node))
+            .max()
+            .map(|&std::cmp::Reverse((_, node))| Action::Visit(node))
+    }
+
+    fn take_click(&mut self) -> Option<usize> {
+        self.clicked.take()
     }
-}
\ No newline at end of file
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![
+            (Role::Unvisited, "Unreached"),
+            (Role::Frontier, "On the heap"),
+            (Role::Current, "Visiting"),
+            (Role::Visited, "Settled"),
+        ]
+    }
+}
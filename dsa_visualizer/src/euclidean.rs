@@ -1,6 +1,15 @@
 use crate::algorithm::Algorithm;
+use crate::pan_zoom;
+use crate::session;
+use crate::theme::{Role, Theme};
 use eframe::egui;
-use std::time::Instant;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    number1: i32,
+    number2: i32,
+}
 
 pub struct EuclideanVisualizer {
     number1: i32,
@@ -9,8 +18,6 @@ pub struct EuclideanVisualizer {
     current_b: i32,
     steps: Vec<(i32, i32)>,
     current_step: usize,
-    auto_traverse: bool,
-    last_step: Option<Instant>,
     result: Option<i32>,
 }
 
@@ -23,8 +30,6 @@ impl EuclideanVisualizer {
             current_b: 18,
             steps: Vec::new(),
             current_step: 0,
-            auto_traverse: false,
-            last_step: None,
             result: None,
         }
     }
@@ -46,6 +51,36 @@ impl EuclideanVisualizer {
         self.current_a = self.number1;
         self.current_b = self.number2;
     }
+
+    fn render_bars(&self, ui: &mut egui::Ui) {
+        let theme = Theme::current(ui.ctx());
+        let available_width = ui.available_width();
+        let available_height = 200.0;
+        let (response, painter) = ui.allocate_painter(
+            egui::vec2(available_width, available_height),
+            egui::Sense::hover(),
+        );
+
+        let rect = response.rect;
+        let center = rect.center();
+
+        let max_size = (self.number1.max(self.number2) as f32).min(100.0);
+        let scale = (rect.height() / 2.0) / max_size;
+
+        let height1 = self.current_a as f32 * scale;
+        let rect1 = egui::Rect::from_min_size(
+            egui::pos2(center.x - 60.0, center.y - height1),
+            egui::vec2(40.0, height1),
+        );
+        painter.rect_filled(rect1, 0.0, theme.color(Role::Frontier));
+
+        let height2 = self.current_b as f32 * scale;
+        let rect2 = egui::Rect::from_min_size(
+            egui::pos2(center.x + 20.0, center.y - height2),
+            egui::vec2(40.0, height2),
+        );
+        painter.rect_filled(rect2, 0.0, theme.color(Role::Current));
+    }
 }
 
 impl Algorithm for EuclideanVisualizer {
@@ -85,52 +120,35 @@ impl Algorithm for EuclideanVisualizer {
             ui.label(format!("GCD({}, {}) = {}", self.number1, self.number2, result));
         }
 
-        let available_width = ui.available_width();
-        let available_height = 200.0;
-        let (response, painter) = ui.allocate_painter(
-            egui::vec2(available_width, available_height),
-            egui::Sense::hover(),
-        );
-
-        let rect = response.rect;
-        let center = rect.center();
-
-        let max_size = (self.number1.max(self.number2) as f32).min(100.0);
-        let scale = (rect.height() / 2.0) / max_size;
-
-        let height1 = self.current_a as f32 * scale;
-        let rect1 = egui::Rect::from_min_size(
-            egui::pos2(center.x - 60.0, center.y - height1),
-            egui::vec2(40.0, height1),
-        );
-        painter.rect_filled(rect1, 0.0, egui::Color32::BLUE);
-
-        let height2 = self.current_b as f32 * scale;
-        let rect2 = egui::Rect::from_min_size(
-            egui::pos2(center.x + 20.0, center.y - height2),
-            egui::vec2(40.0, height2),
-        );
-        painter.rect_filled(rect2, 0.0, egui::Color32::GREEN);
+        pan_zoom::show(ui, "bars", |ui| self.render_bars(ui));
     }
 
-    fn auto_play(&self) -> bool {
-        self.auto_traverse
+    fn is_finished(&self) -> bool {
+        self.current_step >= self.steps.len()
     }
 
-    fn toggle_auto_traverse(&mut self) {
-        self.auto_traverse = !self.auto_traverse;
+    fn reset(&mut self) {
+        self.current_step = 0;
+        self.current_a = self.number1;
+        self.current_b = self.number2;
     }
 
-    fn start(&mut self) {
-        self.current_step = 0;
-        self.calculate_steps();
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![(Role::Frontier, "a"), (Role::Current, "b")]
     }
 
-    fn last_step_time(&self) -> Option<Instant> {
-        self.last_step
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            number1: self.number1,
+            number2: self.number2,
+        })
     }
 
-    fn set_last_step_time(&mut self, time: Option<Instant>) {
-        self.last_step = time;
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        self.number1 = inputs.number1;
+        self.number2 = inputs.number2;
+        self.calculate_steps();
+        Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,352 @@
+use crate::algorithm::Algorithm;
+use crate::offscreen::OffscreenRenderer;
+use crate::theme::Theme;
+use eframe::egui;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    Gif,
+    Apng,
+    /// One numbered PNG per step in a directory.
+    PngFrames,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Gif, ExportFormat::Apng, ExportFormat::PngFrames];
+
+    /// Guesses the format from a path: `.gif`, `.png`/`.apng`, anything else is a directory.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("gif") => ExportFormat::Gif,
+            Some("png") | Some("apng") => ExportFormat::Apng,
+            _ => ExportFormat::PngFrames,
+        }
+    }
+
+    fn extension(self) -> Option<&'static str> {
+        match self {
+            ExportFormat::Gif => Some("gif"),
+            ExportFormat::Apng => Some("png"),
+            ExportFormat::PngFrames => None,
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ExportFormat::Gif => "Animated GIF",
+            ExportFormat::Apng => "Animated PNG",
+            ExportFormat::PngFrames => "PNG frames",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "gif" => Ok(ExportFormat::Gif),
+            "apng" => Ok(ExportFormat::Apng),
+            "png" | "frames" => Ok(ExportFormat::PngFrames),
+            other => Err(format!("unknown format \"{}\", expected gif, apng or png", other)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExportSettings {
+    pub format: ExportFormat,
+    pub width: u32,
+    pub height: u32,
+    pub frame_delay_ms: u32,
+    /// Frames after this many are dropped, so an endless or very long run still ends.
+    pub max_frames: usize,
+    pub path: String,
+    /// Colors to render with, the app's current theme when exporting from the UI.
+    pub theme: Theme,
+}
+
+impl ExportSettings {
+    pub const MAX_SIDE: u32 = 4096;
+
+    pub fn new() -> Self {
+        Self {
+            format: ExportFormat::Gif,
+            width: 960,
+            height: 640,
+            frame_delay_ms: 500,
+            max_frames: 300,
+            path: String::from("export.gif"),
+            theme: Theme::default(),
+        }
+    }
+
+    /// Keeps the path's extension in line with the chosen format.
+    fn sync_path(&mut self) {
+        let path = PathBuf::from(&self.path);
+        let path = match self.format.extension() {
+            Some(extension) => path.with_extension(extension),
+            None => path.with_extension(""),
+        };
+        self.path = path.to_string_lossy().into_owned();
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    Png(png::EncodingError),
+    Gif(gif::EncodingError),
+    InvalidSize(u32, u32),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExportError::Io(e) => write!(f, "could not write export: {}", e),
+            ExportError::Png(e) => write!(f, "PNG encoding failed: {}", e),
+            ExportError::Gif(e) => write!(f, "GIF encoding failed: {}", e),
+            ExportError::InvalidSize(w, h) => write!(
+                f,
+                "{}x{} is not a valid size, each side must be between 1 and {}",
+                w,
+                h,
+                ExportSettings::MAX_SIDE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<png::EncodingError> for ExportError {
+    fn from(e: png::EncodingError) -> Self {
+        ExportError::Png(e)
+    }
+}
+
+impl From<gif::EncodingError> for ExportError {
+    fn from(e: gif::EncodingError) -> Self {
+        ExportError::Gif(e)
+    }
+}
+
+/// Where encoded frames go.
+enum FrameSink {
+    Gif(gif::Encoder<BufWriter<File>>),
+    Apng(png::Writer<BufWriter<File>>),
+    PngFrames { directory: PathBuf, next: usize },
+}
+
+impl FrameSink {
+    fn create(settings: &ExportSettings, frames: usize) -> Result<Self, ExportError> {
+        let path = PathBuf::from(&settings.path);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        Ok(match settings.format {
+            ExportFormat::Gif => {
+                let file = BufWriter::new(File::create(&path)?);
+                let mut encoder = gif::Encoder::new(file, settings.width as u16, settings.height as u16, &[])?;
+                encoder.set_repeat(gif::Repeat::Infinite)?;
+                FrameSink::Gif(encoder)
+            }
+            ExportFormat::Apng => {
+                let file = BufWriter::new(File::create(&path)?);
+                let mut encoder = png::Encoder::new(file, settings.width, settings.height);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_animated(frames as u32, 0)?;
+                encoder.set_frame_delay(settings.frame_delay_ms.min(u16::MAX as u32) as u16, 1000)?;
+                FrameSink::Apng(encoder.write_header()?)
+            }
+            ExportFormat::PngFrames => {
+                fs::create_dir_all(&path)?;
+                FrameSink::PngFrames { directory: path, next: 0 }
+            }
+        })
+    }
+
+    fn write(&mut self, mut rgba: Vec<u8>, settings: &ExportSettings) -> Result<(), ExportError> {
+        match self {
+            FrameSink::Gif(encoder) => {
+                let mut frame = gif::Frame::from_rgba_speed(settings.width as u16, settings.height as u16, &mut rgba, 10);
+                // GIF delays are in hundredths of a second.
+                frame.delay = (settings.frame_delay_ms / 10).clamp(1, u16::MAX as u32) as u16;
+                encoder.write_frame(&frame)?;
+            }
+            FrameSink::Apng(writer) => writer.write_image_data(&rgba)?,
+            FrameSink::PngFrames { directory, next } => {
+                let file = BufWriter::new(File::create(directory.join(format!("frame_{:04}.png", next)))?);
+                let mut encoder = png::Encoder::new(file, settings.width, settings.height);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.write_header()?.write_image_data(&rgba)?;
+                *next += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), ExportError> {
+        if let FrameSink::Apng(writer) = self {
+            writer.finish()?;
+        }
+        Ok(())
+    }
+}
+
+/// Rewinds `algorithm` and renders its starting state plus one frame per step until it
+/// finishes or `max_frames` is reached. Returns how many frames were written; the
+/// algorithm is left on its last exported step, so pass a copy of one the user is
+/// watching.
+pub fn export(algorithm: &mut dyn Algorithm, settings: &ExportSettings) -> Result<usize, ExportError> {
+    let side = 1..=ExportSettings::MAX_SIDE;
+    if !side.contains(&settings.width) || !side.contains(&settings.height) {
+        return Err(ExportError::InvalidSize(settings.width, settings.height));
+    }
+
+    // Dry run to count frames, the APNG header needs the total up front.
+    algorithm.reset();
+    let mut frames = 1;
+    while frames < settings.max_frames.max(1) && !algorithm.is_finished() {
+        algorithm.step();
+        frames += 1;
+    }
+    algorithm.reset();
+
+    let mut renderer = OffscreenRenderer::new(settings.width, settings.height);
+    settings.theme.install(renderer.context());
+    let mut sink = FrameSink::create(settings, frames)?;
+    for frame in 0..frames {
+        if frame > 0 {
+            algorithm.step();
+        }
+        let rgba = renderer.render(|ui| algorithm.render(ui));
+        sink.write(rgba, settings)?;
+    }
+    sink.finish()?;
+    Ok(frames)
+}
+
+/// An export running on its own thread, so the window keeps drawing while the frames
+/// are rendered and encoded.
+pub struct Job {
+    result: Receiver<Result<usize, String>>,
+    /// Where the frames go, kept since the settings may be edited while it runs.
+    pub path: String,
+}
+
+impl Job {
+    /// Builds a fresh visualizer with `factory`, gives it `inputs` and exports it, all on
+    /// the worker thread, so the one the user is watching is never touched.
+    pub fn start(factory: fn() -> Box<dyn Algorithm>, inputs: serde_json::Value, settings: ExportSettings) -> Self {
+        let (sender, result) = mpsc::channel();
+        let path = settings.path.clone();
+        thread::spawn(move || {
+            let mut copy = factory();
+            copy.initialize();
+            let exported = copy
+                .load_inputs(inputs)
+                .and_then(|()| export(copy.as_mut(), &settings).map_err(|e| e.to_string()));
+            let _ = sender.send(exported);
+        });
+        Self { result, path }
+    }
+
+    /// How many frames were written once the thread is done, `None` while it still runs.
+    pub fn poll(&self) -> Option<Result<usize, String>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("the export thread stopped unexpectedly".to_string())),
+        }
+    }
+}
+
+/// Settings row shown under the playback controls. Returns true when "Export" was clicked;
+/// the button is disabled while an earlier export is `busy`.
+pub fn export_panel(ui: &mut egui::Ui, settings: &mut ExportSettings, busy: bool) -> bool {
+    let mut clicked = false;
+    egui::CollapsingHeader::new("Export animation").show(ui, |ui| {
+        ui.horizontal(|ui| {
+            let before = settings.format;
+            egui::ComboBox::from_id_salt("export_format")
+                .selected_text(settings.format.to_string())
+                .show_ui(ui, |ui| {
+                    for format in ExportFormat::ALL {
+                        ui.selectable_value(&mut settings.format, format, format.to_string());
+                    }
+                });
+            if settings.format != before {
+                settings.sync_path();
+            }
+
+            ui.label("Size:");
+            ui.add(egui::DragValue::new(&mut settings.width).range(1..=ExportSettings::MAX_SIDE));
+            ui.label("x");
+            ui.add(egui::DragValue::new(&mut settings.height).range(1..=ExportSettings::MAX_SIDE));
+            ui.label("Frame delay (ms):");
+            ui.add(egui::DragValue::new(&mut settings.frame_delay_ms).range(10..=10_000));
+            ui.label("Max frames:");
+            ui.add(egui::DragValue::new(&mut settings.max_frames).range(1..=10_000));
+        });
+        ui.horizontal(|ui| {
+            ui.label(if settings.format == ExportFormat::PngFrames { "Directory:" } else { "File:" });
+            ui.text_edit_singleline(&mut settings.path);
+            clicked = ui.add_enabled(!busy, egui::Button::new("Export")).clicked();
+        });
+    });
+    clicked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog;
+    use std::time::Duration;
+
+    #[test]
+    fn a_job_exports_a_copy_on_its_own_thread() {
+        let registry = catalog::default_registry();
+        let entry = registry.find("Bubble Sort").unwrap();
+        let directory = std::env::temp_dir().join(format!("dsa-export-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let settings = ExportSettings {
+            format: ExportFormat::PngFrames,
+            width: 64,
+            height: 48,
+            max_frames: 3,
+            path: directory.to_string_lossy().into_owned(),
+            ..ExportSettings::new()
+        };
+
+        let job = Job::start(entry.factory, entry.create().save_inputs(), settings);
+        let result = loop {
+            if let Some(result) = job.poll() {
+                break result;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(result, Ok(3));
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 3);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -1,7 +1,8 @@
 use crate::algorithm::Algorithm;
+use crate::pan_zoom;
+use crate::theme::{Role, Theme};
 use eframe::egui;
-use egui::{Color32, Pos2, Rect, Stroke};
-use std::time::Instant;
+use egui::{Pos2, Rect, Stroke};
 
 pub struct GradientDescentVisualizer {
     weights: f64,
@@ -9,8 +10,6 @@ pub struct GradientDescentVisualizer {
     iteration: usize,
     max_iterations: usize,
     tolerance: f64,
-    is_running: bool,
-    last_step_time: Option<Instant>,
     points: Vec<Pos2>,
 }
 
@@ -22,8 +21,6 @@ impl GradientDescentVisualizer {
             iteration: 0,
             max_iterations: 100,
             tolerance: 0.0001,
-            is_running: false,
-            last_step_time: None,
             points: Vec::new(),
         }
     }
@@ -47,35 +44,10 @@ impl GradientDescentVisualizer {
         gradient.abs() < self.tolerance || self.iteration >= self.max_iterations
     }
 
-}
-
-impl Algorithm for GradientDescentVisualizer {
-    fn initialize(&mut self) {
-        self.weights = 10.0;
-        self.iteration = 0;
-        self.is_running = false;
-        self.points.clear();
-        self.last_step_time = None;
-    }
-
-    fn step(&mut self) {
-        if !self.is_converged() {
-            self.update_weights();
-        } else {
-            self.is_running = false;
-        }
-    }
-
-    fn render(&mut self, ui: &mut egui::Ui) {
-        ui.label(format!("Weights: {:.4}", self.weights));
-        ui.label(format!("Iteration: {}", self.iteration));
-        ui.label(format!("Gradient: {:.4}", self.calculate_gradient()));
-
-        if self.is_converged() {
-            ui.label("Converged!");
-        }
-
-        let available_rect = ui.available_rect_before_wrap();
+    fn render_plot(&self, ui: &mut egui::Ui) {
+        let theme = Theme::current(ui.ctx());
+        let (response, painter) = ui.allocate_painter(ui.available_size_before_wrap(), egui::Sense::hover());
+        let available_rect = response.rect;
         let width = available_rect.width();
         let height = available_rect.height();
         let plot_left = available_rect.left();
@@ -101,14 +73,13 @@ impl Algorithm for GradientDescentVisualizer {
             plot_bottom - (normalized_value * height) // Map to screen-space y
         };
 
-        let painter = ui.painter();
         painter.rect_stroke(
             Rect::from_min_max(
                 Pos2::new(plot_left, plot_top),
                 Pos2::new(plot_right, plot_bottom),
             ),
             0.0, // No corner rounding
-            Stroke::new(2.0, Color32::BLACK),
+            Stroke::new(2.0, theme.line()),
         );
 
 
@@ -123,9 +94,9 @@ impl Algorithm for GradientDescentVisualizer {
             let this_pixel_point = Pos2::new(x, y);
 
             let color = if i == self.points.len() - 1 && self.is_converged() {
-                Color32::GREEN
+                theme.color(Role::Solution)
             } else {
-                Color32::RED
+                theme.color(Role::Frontier)
             };
 
             painter.circle_filled(this_pixel_point, 4.0, color);
@@ -133,31 +104,49 @@ impl Algorithm for GradientDescentVisualizer {
             if let Some(last_pixel_point) = last_pixel_point {
                 painter.line_segment(
                     [last_pixel_point, this_pixel_point],
-                    Stroke::new(1.0, Color32::BLACK),
+                    Stroke::new(1.0, theme.line()),
                 );
             }
 
             last_pixel_point = Some(this_pixel_point);
         }
     }
+}
 
-    fn auto_play(&self) -> bool {
-        self.is_running
+impl Algorithm for GradientDescentVisualizer {
+    fn initialize(&mut self) {
+        self.weights = 10.0;
+        self.iteration = 0;
+        self.points.clear();
     }
 
-    fn toggle_auto_traverse(&mut self) {
-        self.is_running = !self.is_running;
+    fn step(&mut self) {
+        if !self.is_converged() {
+            self.update_weights();
+        }
     }
 
-    fn start(&mut self) {
-        self.is_running = true;
+    fn render(&mut self, ui: &mut egui::Ui) {
+        ui.label(format!("Weights: {:.4}", self.weights));
+        ui.label(format!("Iteration: {}", self.iteration));
+        ui.label(format!("Gradient: {:.4}", self.calculate_gradient()));
+
+        if self.is_converged() {
+            ui.label("Converged!");
+        }
+
+        pan_zoom::show(ui, "plot", |ui| self.render_plot(ui));
     }
 
-    fn last_step_time(&self) -> Option<Instant> {
-        self.last_step_time
+    fn is_finished(&self) -> bool {
+        self.is_converged()
     }
 
-    fn set_last_step_time(&mut self, time: Option<Instant>) {
-        self.last_step_time = time;
+    fn reset(&mut self) {
+        self.initialize();
     }
-}
\ No newline at end of file
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![(Role::Frontier, "Iterate"), (Role::Solution, "Converged")]
+    }
+}
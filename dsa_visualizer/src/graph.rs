@@ -0,0 +1,239 @@
+use crate::pan_zoom;
+use crate::theme::{self, Theme};
+use eframe::egui;
+use egui::{Color32, Pos2, Stroke};
+use std::collections::HashMap;
+
+/// Directed weighted adjacency list, the same shape `DijkstraVisualizer` uses:
+/// node -> [(neighbor, weight)].
+pub type Graph = HashMap<usize, Vec<(usize, usize)>>;
+
+/// Every node mentioned in the graph, as a source or a target, in ascending order.
+pub fn nodes(graph: &Graph) -> Vec<usize> {
+    let mut nodes: Vec<usize> = graph
+        .iter()
+        .flat_map(|(&node, edges)| std::iter::once(node).chain(edges.iter().map(|&(n, _)| n)))
+        .collect();
+    nodes.sort_unstable();
+    nodes.dedup();
+    nodes
+}
+
+pub fn add_edge(graph: &mut Graph, from: usize, to: usize, weight: usize) {
+    graph.entry(to).or_default();
+    let edges = graph.entry(from).or_default();
+    match edges.iter_mut().find(|(n, _)| *n == to) {
+        Some(edge) => edge.1 = weight,
+        None => edges.push((to, weight)),
+    }
+}
+
+pub fn remove_edge(graph: &mut Graph, from: usize, to: usize) {
+    if let Some(edges) = graph.get_mut(&from) {
+        edges.retain(|&(n, _)| n != to);
+    }
+}
+
+/// Edges reversed, used by Kosaraju's second pass.
+pub fn transpose(graph: &Graph) -> Graph {
+    let mut reversed = Graph::new();
+    for node in nodes(graph) {
+        reversed.entry(node).or_default();
+    }
+    for (&from, edges) in graph {
+        for &(to, weight) in edges {
+            reversed.entry(to).or_default().push((from, weight));
+        }
+    }
+    reversed
+}
+
+/// Row of inputs to add or remove an edge, in both directions when the input is
+/// undirected. Returns true when the graph changed.
+pub fn edge_editor(ui: &mut egui::Ui, graph: &mut Graph, input: &mut EdgeInput) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Edge from");
+        ui.add(egui::TextEdit::singleline(&mut input.from).desired_width(40.0));
+        ui.label("to");
+        ui.add(egui::TextEdit::singleline(&mut input.to).desired_width(40.0));
+        if input.weighted {
+            ui.label("weight");
+            ui.add(egui::TextEdit::singleline(&mut input.weight).desired_width(40.0));
+        }
+
+        let from = input.from.trim().parse::<usize>();
+        let to = input.to.trim().parse::<usize>();
+        let weight = if input.weighted { input.weight.trim().parse::<usize>() } else { Ok(1) };
+        if let (Ok(from), Ok(to), Ok(weight)) = (from, to, weight) {
+            if ui.button("Add Edge").clicked() {
+                add_edge(graph, from, to, weight);
+                if input.undirected {
+                    add_edge(graph, to, from, weight);
+                }
+                changed = true;
+            }
+            if ui.button("Remove Edge").clicked() {
+                remove_edge(graph, from, to);
+                if input.undirected {
+                    remove_edge(graph, to, from);
+                }
+                changed = true;
+            }
+        }
+    });
+    changed
+}
+
+/// Text typed into the `edge_editor` row.
+pub struct EdgeInput {
+    pub from: String,
+    pub to: String,
+    pub weight: String,
+    pub weighted: bool,
+    pub undirected: bool,
+}
+
+impl EdgeInput {
+    pub fn new(weighted: bool, undirected: bool) -> Self {
+        Self {
+            from: String::new(),
+            to: String::new(),
+            weight: String::from("1"),
+            weighted,
+            undirected,
+        }
+    }
+}
+
+/// Nodes placed evenly on a circle.
+pub fn circle_layout(nodes: &[usize], center: Pos2, radius: f32) -> HashMap<usize, Pos2> {
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(i, &node)| {
+            let angle = std::f32::consts::PI * 2.0 * (i as f32 / nodes.len() as f32) - std::f32::consts::FRAC_PI_2;
+            (node, Pos2::new(center.x + angle.cos() * radius, center.y + angle.sin() * radius))
+        })
+        .collect()
+}
+
+/// The node whose circle contains this frame's primary click, if any. The click is
+/// mapped into the layer's coordinates so it still lands on a panned or zoomed canvas.
+pub fn clicked_node(ui: &egui::Ui, positions: impl IntoIterator<Item = (usize, Pos2)>, node_radius: f32) -> Option<usize> {
+    let click = ui.input(|i| if i.pointer.primary_clicked() { i.pointer.interact_pos() } else { None })?;
+    let click = ui.ctx().layer_transform_from_global(ui.layer_id()).map_or(click, |to_layer| to_layer * click);
+    positions
+        .into_iter()
+        .find(|&(_, pos)| pos.distance(click) <= node_radius)
+        .map(|(node, _)| node)
+}
+
+/// Line from the rim of one node to the rim of another with an arrow head at `to`.
+/// Bidirectional edges are bent apart so both stay visible.
+pub fn draw_arrow(painter: &egui::Painter, from: Pos2, to: Pos2, node_radius: f32, stroke: Stroke) -> Pos2 {
+    let direction = (to - from).normalized();
+    let normal = egui::vec2(-direction.y, direction.x) * 6.0;
+    let start = from + direction * node_radius + normal;
+    let end = to - direction * node_radius + normal;
+    painter.line_segment([start, end], stroke);
+
+    let head = 10.0;
+    let back = end - direction * head;
+    let side = egui::vec2(-direction.y, direction.x) * head * 0.5;
+    painter.line_segment([end, back + side], stroke);
+    painter.line_segment([end, back - side], stroke);
+
+    Pos2::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0) + normal
+}
+
+/// How `draw_graph` paints a graph. Every callback has a neutral default, so callers
+/// only override what their algorithm highlights.
+pub struct GraphStyle<'a> {
+    /// Undirected graphs store each edge in both directions and draw it once without arrows.
+    pub directed: bool,
+    pub node_color: Box<dyn Fn(usize) -> Color32 + 'a>,
+    pub node_label: Box<dyn Fn(usize) -> Option<String> + 'a>,
+    pub edge_color: Box<dyn Fn(usize, usize) -> Color32 + 'a>,
+    pub edge_label: Box<dyn Fn(usize, usize, usize) -> Option<String> + 'a>,
+}
+
+impl Default for GraphStyle<'_> {
+    fn default() -> Self {
+        Self {
+            directed: true,
+            node_color: Box::new(|_| theme::NEUTRAL),
+            node_label: Box::new(|_| None),
+            edge_color: Box::new(|_, _| theme::NEUTRAL),
+            edge_label: Box::new(|_, _, _| None),
+        }
+    }
+}
+
+/// Draws a graph with its nodes on a circle, on a canvas that can be panned and zoomed.
+pub fn draw_graph(ui: &mut egui::Ui, graph: &Graph, style: GraphStyle) {
+    pan_zoom::show(ui, "graph", |ui| paint_graph(ui, graph, style));
+}
+
+fn paint_graph(ui: &mut egui::Ui, graph: &Graph, style: GraphStyle) {
+    let theme = Theme::current(ui.ctx());
+    let node_radius = 24.0;
+    let size = egui::vec2(ui.available_width(), 380.0);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let rect = response.rect;
+
+    let nodes = nodes(graph);
+    let positions = circle_layout(&nodes, rect.center(), (rect.height() / 2.0 - node_radius - 20.0).max(40.0));
+
+    for (&from, edges) in graph {
+        for &(to, weight) in edges {
+            let stroke = Stroke::new(2.0, (style.edge_color)(from, to));
+            if from == to {
+                painter.circle_stroke(positions[&from] - egui::vec2(0.0, node_radius + 8.0), 10.0, stroke);
+                continue;
+            }
+            let midpoint = if style.directed {
+                draw_arrow(&painter, positions[&from], positions[&to], node_radius, stroke)
+            } else if from < to {
+                painter.line_segment([positions[&from], positions[&to]], stroke);
+                Pos2::new(
+                    (positions[&from].x + positions[&to].x) / 2.0,
+                    (positions[&from].y + positions[&to].y) / 2.0,
+                )
+            } else {
+                continue;
+            };
+            if let Some(label) = (style.edge_label)(from, to, weight) {
+                painter.text(
+                    midpoint,
+                    egui::Align2::CENTER_CENTER,
+                    label,
+                    egui::FontId::new(16.0, egui::FontFamily::Proportional),
+                    theme.muted(),
+                );
+            }
+        }
+    }
+
+    for &node in &nodes {
+        let pos = positions[&node];
+        let fill = (style.node_color)(node);
+        painter.circle_filled(pos, node_radius, fill);
+        painter.text(
+            pos,
+            egui::Align2::CENTER_CENTER,
+            node.to_string(),
+            egui::FontId::new(16.0, egui::FontFamily::Proportional),
+            theme.text_on(fill),
+        );
+        if let Some(label) = (style.node_label)(node) {
+            painter.text(
+                pos + egui::vec2(0.0, node_radius + 10.0),
+                egui::Align2::CENTER_CENTER,
+                label,
+                egui::FontId::new(12.0, egui::FontFamily::Proportional),
+                theme.muted(),
+            );
+        }
+    }
+}
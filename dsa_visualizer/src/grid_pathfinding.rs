@@ -0,0 +1,695 @@
+use crate::algorithm::Algorithm;
+use crate::session;
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use egui::{Color32, Pos2, Rect, Stroke};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Cost of a straight move on open ground. Diagonal moves cost `DIAGONAL`,
+/// roughly 10 * sqrt(2), which keeps every cost an integer.
+const STRAIGHT: u32 = 10;
+const DIAGONAL: u32 = 14;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Cell {
+    Wall,
+    /// Open ground with a movement weight, 1 for plain floor.
+    Open(u32),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Brush {
+    Wall,
+    Erase,
+    Forest,
+    Swamp,
+    Start,
+    Goal,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+enum Heuristic {
+    Manhattan,
+    Euclidean,
+    Octile,
+}
+
+impl Heuristic {
+    /// Whether the estimate never exceeds the real cost, so A* still finds a shortest path.
+    /// Manhattan counts a diagonal as two straight moves, 20 against the real 14.
+    fn admissible(self, diagonal: bool) -> bool {
+        !(diagonal && self == Heuristic::Manhattan)
+    }
+
+    fn estimate(self, from: (usize, usize), to: (usize, usize)) -> u32 {
+        let dx = from.0.abs_diff(to.0) as u32;
+        let dy = from.1.abs_diff(to.1) as u32;
+        match self {
+            Heuristic::Manhattan => STRAIGHT * (dx + dy),
+            // Scaled so a diagonal counts DIAGONAL rather than 10 * sqrt(2), which would
+            // overestimate every diagonal step.
+            Heuristic::Euclidean => {
+                (DIAGONAL as f32 / std::f32::consts::SQRT_2 * ((dx * dx + dy * dy) as f32).sqrt()) as u32
+            }
+            Heuristic::Octile => STRAIGHT * dx.max(dy) + (DIAGONAL - STRAIGHT) * dx.min(dy),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SearchKind {
+    Bfs,
+    Dijkstra,
+    AStar,
+}
+
+impl SearchKind {
+    fn name(self) -> &'static str {
+        match self {
+            SearchKind::Bfs => "BFS",
+            SearchKind::Dijkstra => "Dijkstra",
+            SearchKind::AStar => "A*",
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    start: usize,
+    goal: usize,
+    diagonal: bool,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::Open(1); width * height],
+            start: width + 1,
+            goal: width * (height - 1) - 2,
+            diagonal: false,
+        }
+    }
+
+    fn coords(&self, i: usize) -> (usize, usize) {
+        (i % self.width, i / self.width)
+    }
+
+    /// Passable neighbors with the cost of stepping onto them.
+    fn neighbors(&self, i: usize) -> Vec<(usize, u32)> {
+        let (x, y) = self.coords(i);
+        let mut out = Vec::new();
+        for dy in -1i32..=1 {
+            for dx in -1i32..=1 {
+                let diagonal = dx != 0 && dy != 0;
+                if (dx == 0 && dy == 0) || (diagonal && !self.diagonal) {
+                    continue;
+                }
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                if nx < 0 || ny < 0 || nx >= self.width as i32 || ny >= self.height as i32 {
+                    continue;
+                }
+                let n = ny as usize * self.width + nx as usize;
+                // Diagonals may not squeeze between two walls.
+                if diagonal
+                    && (self.cells[y * self.width + nx as usize] == Cell::Wall
+                        || self.cells[ny as usize * self.width + x] == Cell::Wall)
+                {
+                    continue;
+                }
+                if let Cell::Open(weight) = self.cells[n] {
+                    out.push((n, weight * if diagonal { DIAGONAL } else { STRAIGHT }));
+                }
+            }
+        }
+        out
+    }
+
+    fn fill_walls(&mut self) {
+        self.cells.iter_mut().for_each(|c| *c = Cell::Wall);
+    }
+
+    fn carve(&mut self, x: usize, y: usize) {
+        self.cells[y * self.width + x] = Cell::Open(1);
+    }
+
+    /// Maze cells sit on odd coordinates, two apart, with walls in between.
+    fn maze_neighbors(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+        if x >= 3 {
+            out.push((x - 2, y));
+        }
+        if y >= 3 {
+            out.push((x, y - 2));
+        }
+        if x + 2 < self.width - 1 {
+            out.push((x + 2, y));
+        }
+        if y + 2 < self.height - 1 {
+            out.push((x, y + 2));
+        }
+        out
+    }
+
+    fn recursive_backtracker(&mut self) {
+        let mut rng = rand::thread_rng();
+        self.fill_walls();
+        let mut visited = vec![false; self.cells.len()];
+        let mut stack = vec![(1, 1)];
+        visited[self.width + 1] = true;
+        self.carve(1, 1);
+
+        while let Some(&(x, y)) = stack.last() {
+            let options: Vec<(usize, usize)> = self
+                .maze_neighbors(x, y)
+                .into_iter()
+                .filter(|&(nx, ny)| !visited[ny * self.width + nx])
+                .collect();
+            match options.choose(&mut rng) {
+                Some(&(nx, ny)) => {
+                    visited[ny * self.width + nx] = true;
+                    self.carve((x + nx) / 2, (y + ny) / 2);
+                    self.carve(nx, ny);
+                    stack.push((nx, ny));
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        self.place_endpoints();
+    }
+
+    fn prim(&mut self) {
+        let mut rng = rand::thread_rng();
+        self.fill_walls();
+        let mut in_maze = vec![false; self.cells.len()];
+        in_maze[self.width + 1] = true;
+        self.carve(1, 1);
+        let mut frontier = self.maze_neighbors(1, 1);
+
+        while !frontier.is_empty() {
+            let (x, y) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+            if in_maze[y * self.width + x] {
+                continue;
+            }
+            let connected: Vec<(usize, usize)> = self
+                .maze_neighbors(x, y)
+                .into_iter()
+                .filter(|&(nx, ny)| in_maze[ny * self.width + nx])
+                .collect();
+            if let Some(&(nx, ny)) = connected.choose(&mut rng) {
+                self.carve((x + nx) / 2, (y + ny) / 2);
+            }
+            self.carve(x, y);
+            in_maze[y * self.width + x] = true;
+            frontier.extend(
+                self.maze_neighbors(x, y)
+                    .into_iter()
+                    .filter(|&(nx, ny)| !in_maze[ny * self.width + nx]),
+            );
+        }
+        self.place_endpoints();
+    }
+
+    /// Knocks out random interior walls so the maze has loops and more than one route.
+    fn braid(&mut self, fraction: f32) {
+        let mut rng = rand::thread_rng();
+        for y in 1..self.height - 1 {
+            for x in 1..self.width - 1 {
+                let i = y * self.width + x;
+                if self.cells[i] == Cell::Wall && rng.gen::<f32>() < fraction {
+                    self.cells[i] = Cell::Open(1);
+                }
+            }
+        }
+    }
+
+    fn place_endpoints(&mut self) {
+        self.start = self.width + 1;
+        self.goal = (self.height - 2) * self.width + self.width - 2;
+        self.cells[self.start] = Cell::Open(1);
+        self.cells[self.goal] = Cell::Open(1);
+    }
+}
+
+/// What a session keeps: the painted grid and which searches race on it.
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    grid: Grid,
+    enabled: [bool; 3],
+    heuristic: Heuristic,
+    expansions_per_step: usize,
+}
+
+/// One search running over the shared grid. The open set is a heap keyed on
+/// (priority, insertion order), so BFS with a constant priority behaves as a FIFO queue.
+struct GridSearch {
+    kind: SearchKind,
+    open: BinaryHeap<Reverse<(u32, usize, usize)>>,
+    pushes: usize,
+    cost: Vec<u32>,
+    came_from: Vec<Option<usize>>,
+    closed: Vec<bool>,
+    expanded: usize,
+    current: Option<usize>,
+    path: Option<Vec<usize>>,
+    finished: bool,
+}
+
+impl GridSearch {
+    fn new(kind: SearchKind, grid: &Grid) -> Self {
+        let mut search = Self {
+            kind,
+            open: BinaryHeap::new(),
+            pushes: 0,
+            cost: vec![u32::MAX; grid.cells.len()],
+            came_from: vec![None; grid.cells.len()],
+            closed: vec![false; grid.cells.len()],
+            expanded: 0,
+            current: None,
+            path: None,
+            finished: false,
+        };
+        search.cost[grid.start] = 0;
+        search.push(0, grid.start);
+        search
+    }
+
+    fn push(&mut self, priority: u32, cell: usize) {
+        self.open.push(Reverse((priority, self.pushes, cell)));
+        self.pushes += 1;
+    }
+
+    fn in_open(&self, cell: usize) -> bool {
+        !self.closed[cell] && self.cost[cell] != u32::MAX
+    }
+
+    /// Expands one node: pops the best open cell, closes it and relaxes its neighbors.
+    fn step(&mut self, grid: &Grid, heuristic: Heuristic) {
+        if self.finished {
+            return;
+        }
+        let cell = loop {
+            match self.open.pop() {
+                Some(Reverse((_, _, cell))) if self.closed[cell] => continue,
+                Some(Reverse((_, _, cell))) => break cell,
+                None => {
+                    self.finished = true;
+                    self.current = None;
+                    return;
+                }
+            }
+        };
+
+        self.closed[cell] = true;
+        self.expanded += 1;
+        self.current = Some(cell);
+
+        if cell == grid.goal {
+            let mut path = vec![cell];
+            let mut node = cell;
+            while let Some(previous) = self.came_from[node] {
+                path.push(previous);
+                node = previous;
+            }
+            path.reverse();
+            self.path = Some(path);
+            self.finished = true;
+            return;
+        }
+
+        for (neighbor, step_cost) in grid.neighbors(cell) {
+            if self.closed[neighbor] {
+                continue;
+            }
+            let new_cost = self.cost[cell] + step_cost;
+            match self.kind {
+                SearchKind::Bfs => {
+                    // BFS ignores weights: the first discovery wins.
+                    if self.cost[neighbor] == u32::MAX {
+                        self.cost[neighbor] = new_cost;
+                        self.came_from[neighbor] = Some(cell);
+                        self.push(0, neighbor);
+                    }
+                }
+                SearchKind::Dijkstra | SearchKind::AStar => {
+                    if new_cost < self.cost[neighbor] {
+                        self.cost[neighbor] = new_cost;
+                        self.came_from[neighbor] = Some(cell);
+                        let estimate = if self.kind == SearchKind::AStar {
+                            heuristic.estimate(grid.coords(neighbor), grid.coords(grid.goal))
+                        } else {
+                            0
+                        };
+                        self.push(new_cost + estimate, neighbor);
+                    }
+                }
+            }
+        }
+    }
+
+    fn path_cost(&self, grid: &Grid) -> Option<u32> {
+        self.path.as_ref().map(|_| self.cost[grid.goal])
+    }
+}
+
+pub struct GridPathfindingVisualizer {
+    grid: Grid,
+    searches: Vec<GridSearch>,
+    enabled: [bool; 3],
+    heuristic: Heuristic,
+    brush: Brush,
+    expansions_per_step: usize,
+}
+
+impl GridPathfindingVisualizer {
+    pub fn new() -> Self {
+        Self {
+            grid: Grid::new(31, 21),
+            searches: Vec::new(),
+            enabled: [true, true, true],
+            heuristic: Heuristic::Manhattan,
+            brush: Brush::Wall,
+            expansions_per_step: 1,
+        }
+    }
+
+    fn reset_searches(&mut self) {
+        let kinds = [SearchKind::Bfs, SearchKind::Dijkstra, SearchKind::AStar];
+        self.searches = kinds
+            .iter()
+            .zip(self.enabled)
+            .filter(|&(_, enabled)| enabled)
+            .map(|(&kind, _)| GridSearch::new(kind, &self.grid))
+            .collect();
+    }
+
+    fn paint(&mut self, cell: usize) {
+        match self.brush {
+            Brush::Wall if cell != self.grid.start && cell != self.grid.goal => self.grid.cells[cell] = Cell::Wall,
+            Brush::Erase => self.grid.cells[cell] = Cell::Open(1),
+            Brush::Forest => self.grid.cells[cell] = Cell::Open(3),
+            Brush::Swamp => self.grid.cells[cell] = Cell::Open(5),
+            Brush::Start if self.grid.cells[cell] != Cell::Wall => self.grid.start = cell,
+            Brush::Goal if self.grid.cells[cell] != Cell::Wall => self.grid.goal = cell,
+            _ => return,
+        }
+        self.reset_searches();
+    }
+
+    fn render_controls(&mut self, ui: &mut egui::Ui) {
+        let mut reset = false;
+        ui.horizontal(|ui| {
+            ui.label("Brush:");
+            ui.radio_value(&mut self.brush, Brush::Wall, "Wall");
+            ui.radio_value(&mut self.brush, Brush::Erase, "Floor (1)");
+            ui.radio_value(&mut self.brush, Brush::Forest, "Forest (3)");
+            ui.radio_value(&mut self.brush, Brush::Swamp, "Swamp (5)");
+            ui.radio_value(&mut self.brush, Brush::Start, "Start");
+            ui.radio_value(&mut self.brush, Brush::Goal, "Goal");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Compare:");
+            reset |= ui.checkbox(&mut self.enabled[0], "BFS").changed();
+            reset |= ui.checkbox(&mut self.enabled[1], "Dijkstra").changed();
+            reset |= ui.checkbox(&mut self.enabled[2], "A*").changed();
+            ui.separator();
+            ui.label("Heuristic:");
+            for heuristic in [Heuristic::Manhattan, Heuristic::Euclidean, Heuristic::Octile] {
+                reset |= ui.radio_value(&mut self.heuristic, heuristic, format!("{:?}", heuristic)).changed();
+            }
+            ui.separator();
+            reset |= ui.checkbox(&mut self.grid.diagonal, "Diagonal moves").changed();
+            ui.separator();
+            ui.add(egui::Slider::new(&mut self.expansions_per_step, 1..=50).text("expansions per step"));
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Recursive Backtracker Maze").clicked() {
+                self.grid.recursive_backtracker();
+                reset = true;
+            }
+            if ui.button("Prim's Maze").clicked() {
+                self.grid.prim();
+                reset = true;
+            }
+            if ui.button("Add Loops").clicked() {
+                self.grid.braid(0.1);
+                reset = true;
+            }
+            if ui.button("Clear Grid").clicked() {
+                let (width, height) = (self.grid.width, self.grid.height);
+                let diagonal = self.grid.diagonal;
+                self.grid = Grid::new(width, height);
+                self.grid.diagonal = diagonal;
+                reset = true;
+            }
+        });
+        if reset {
+            self.reset_searches();
+        }
+    }
+
+    /// Draws one search's view of the grid and returns the cell under a click or drag.
+    fn render_search(&self, ui: &mut egui::Ui, search: &GridSearch, size: egui::Vec2) -> Option<usize> {
+        let grid = &self.grid;
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::click_and_drag());
+        let rect = response.rect;
+        let theme = Theme::current(ui.ctx());
+        let cell_size = (rect.width() / grid.width as f32).min(rect.height() / grid.height as f32);
+        let on_path = |i: usize| search.path.as_ref().is_some_and(|p| p.contains(&i));
+
+        for i in 0..grid.cells.len() {
+            let (x, y) = grid.coords(i);
+            let min = Pos2::new(rect.left() + x as f32 * cell_size, rect.top() + y as f32 * cell_size);
+            let cell_rect = Rect::from_min_size(min, egui::vec2(cell_size, cell_size));
+
+            let color = if i == grid.start {
+                theme.color(Role::Swapped)
+            } else if i == grid.goal {
+                theme.color(Role::Rejected)
+            } else if search.current == Some(i) {
+                theme.color(Role::Current)
+            } else if on_path(i) {
+                theme.color(Role::Solution)
+            } else {
+                match grid.cells[i] {
+                    Cell::Wall => Color32::from_gray(50),
+                    Cell::Open(weight) => {
+                        let base = match weight {
+                            1 => Color32::WHITE,
+                            3 => Color32::from_rgb(170, 210, 150),
+                            _ => Color32::from_rgb(150, 130, 100),
+                        };
+                        if search.closed[i] {
+                            base.lerp_to_gamma(theme.color(Role::Visited), 0.45)
+                        } else if search.in_open(i) {
+                            base.lerp_to_gamma(theme.color(Role::Frontier), 0.55)
+                        } else {
+                            base
+                        }
+                    }
+                }
+            };
+            painter.rect_filled(cell_rect, 0.0, color);
+        }
+        painter.rect_stroke(
+            Rect::from_min_size(rect.min, egui::vec2(cell_size * grid.width as f32, cell_size * grid.height as f32)),
+            0.0,
+            Stroke::new(1.0, theme.line()),
+        );
+
+        if response.clicked() || response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let x = ((pos.x - rect.left()) / cell_size) as usize;
+                let y = ((pos.y - rect.top()) / cell_size) as usize;
+                if x < grid.width && y < grid.height {
+                    return Some(y * grid.width + x);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Algorithm for GridPathfindingVisualizer {
+    fn initialize(&mut self) {
+        self.grid.recursive_backtracker();
+        self.grid.braid(0.08);
+        self.reset_searches();
+    }
+
+    fn step(&mut self) {
+        for search in &mut self.searches {
+            for _ in 0..self.expansions_per_step {
+                search.step(&self.grid, self.heuristic);
+            }
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        self.render_controls(ui);
+        ui.label("Paint on any grid with the selected brush.");
+
+        let columns = self.searches.len().max(1) as f32;
+        let width = (ui.available_width() - 12.0 * (columns - 1.0)) / columns;
+        let size = egui::vec2(width, width * self.grid.height as f32 / self.grid.width as f32);
+
+        let mut painted = None;
+        ui.horizontal_top(|ui| {
+            for search in &self.searches {
+                ui.vertical(|ui| {
+                    let name = if search.kind == SearchKind::AStar {
+                        if self.heuristic.admissible(self.grid.diagonal) {
+                            format!("A* ({:?})", self.heuristic)
+                        } else {
+                            format!("A* ({:?}, overestimates, path may not be shortest)", self.heuristic)
+                        }
+                    } else {
+                        search.kind.name().to_string()
+                    };
+                    ui.strong(name);
+                    ui.label(format!("Nodes expanded: {}", search.expanded));
+                    let result = match (search.finished, search.path_cost(&self.grid)) {
+                        (_, Some(cost)) => format!(
+                            "Path: {} cells, cost {:.1}",
+                            search.path.as_ref().map_or(0, |p| p.len()),
+                            cost as f32 / STRAIGHT as f32
+                        ),
+                        (true, None) => "No path".to_string(),
+                        (false, None) => "Searching...".to_string(),
+                    };
+                    ui.label(result);
+                    if let Some(cell) = self.render_search(ui, search, size) {
+                        painted = Some(cell);
+                    }
+                });
+            }
+        });
+        if let Some(cell) = painted {
+            self.paint(cell);
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.searches.iter().all(|s| s.finished)
+    }
+
+    fn reset(&mut self) {
+        self.reset_searches();
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            grid: self.grid.clone(),
+            enabled: self.enabled,
+            heuristic: self.heuristic,
+            expansions_per_step: self.expansions_per_step,
+        })
+    }
+
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        let grid = &inputs.grid;
+        if grid.width < 3 || grid.height < 3 {
+            return Err(format!("a {}x{} grid is too small, each side must be at least 3", grid.width, grid.height));
+        }
+        let cells = grid.width.checked_mul(grid.height);
+        if cells != Some(grid.cells.len()) || grid.start >= grid.cells.len() || grid.goal >= grid.cells.len() {
+            return Err("the grid's size doesn't match its cells".to_string());
+        }
+        if grid.cells.iter().any(|&cell| !matches!(cell, Cell::Wall | Cell::Open(1 | 3 | 5))) {
+            return Err("the grid has a weight other than floor (1), forest (3) or swamp (5)".to_string());
+        }
+        self.grid = inputs.grid;
+        self.enabled = inputs.enabled;
+        self.heuristic = inputs.heuristic;
+        self.expansions_per_step = inputs.expansions_per_step.clamp(1, 50);
+        self.reset_searches();
+        Ok(())
+    }
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![
+            (Role::Swapped, "Start"),
+            (Role::Rejected, "Goal"),
+            (Role::Frontier, "Open set (tint)"),
+            (Role::Visited, "Closed set (tint)"),
+            (Role::Current, "Expanding"),
+            (Role::Solution, "Path"),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Saved inputs of a fresh grid with `edit` applied to its JSON.
+    fn edited(edit: impl FnOnce(&mut serde_json::Value)) -> serde_json::Value {
+        let mut inputs = GridPathfindingVisualizer::new().save_inputs();
+        edit(&mut inputs["grid"]);
+        inputs
+    }
+
+    /// Cheapest cost from `from` to `to` on open floor, by Dijkstra over the grid itself.
+    fn floor_cost(grid: &Grid, from: usize, to: usize) -> u32 {
+        let grid = Grid { start: from, goal: to, ..grid.clone() };
+        let mut search = GridSearch::new(SearchKind::Dijkstra, &grid);
+        while !search.finished {
+            search.step(&grid, Heuristic::Octile);
+        }
+        search.path_cost(&grid).unwrap()
+    }
+
+    #[test]
+    fn heuristics_never_overestimate_unless_marked() {
+        for diagonal in [false, true] {
+            let grid = Grid { diagonal, ..Grid::new(9, 9) };
+            for to in 0..grid.cells.len() {
+                let cost = floor_cost(&grid, 0, to);
+                for heuristic in [Heuristic::Manhattan, Heuristic::Euclidean, Heuristic::Octile] {
+                    let estimate = heuristic.estimate(grid.coords(0), grid.coords(to));
+                    assert!(
+                        !heuristic.admissible(diagonal) || estimate <= cost,
+                        "{:?} guesses {} for a cost of {} with diagonal {}",
+                        heuristic,
+                        estimate,
+                        cost,
+                        diagonal
+                    );
+                }
+            }
+        }
+        assert!(!Heuristic::Manhattan.admissible(true));
+    }
+
+    #[test]
+    fn crafted_grids_are_refused() {
+        let load = |inputs| GridPathfindingVisualizer::new().load_inputs(inputs);
+        assert!(load(edited(|_| {})).is_ok());
+        assert!(load(edited(|grid| {
+            grid["width"] = 2.into();
+            grid["height"] = 1.into();
+            grid["cells"] = serde_json::json!([{ "Open": 1 }, { "Open": 1 }]);
+            grid["start"] = 0.into();
+            grid["goal"] = 1.into();
+        }))
+        .is_err());
+        assert!(load(edited(|grid| {
+            grid["width"] = (usize::MAX / 2).into();
+            grid["height"] = 4.into();
+        }))
+        .is_err());
+        assert!(load(edited(|grid| grid["cells"][40] = serde_json::json!({ "Open": u32::MAX }))).is_err());
+    }
+}
@@ -0,0 +1,771 @@
+use crate::algorithm::{rewound_message, Algorithm};
+use crate::pan_zoom;
+use crate::session;
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use egui::{Color32, Stroke};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+
+const INITIAL_CAPACITY: usize = 8;
+const SEED_KEYS: [i32; 7] = [15, 23, 7, 31, 42, 4, 12];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Strategy {
+    /// Every bucket holds a list of the keys that hash to it.
+    Chaining,
+    /// Collisions try the next slot, then the one after.
+    LinearProbing,
+    /// Collisions jump by triangular numbers 1, 3, 6, 10, ... which reach every slot of
+    /// a power-of-two table.
+    QuadraticProbing,
+    /// Collisions jump by a second, odd hash of the key.
+    DoubleHashing,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::Chaining,
+        Strategy::LinearProbing,
+        Strategy::QuadraticProbing,
+        Strategy::DoubleHashing,
+    ];
+
+    fn open_addressing(self) -> bool {
+        self != Strategy::Chaining
+    }
+
+    /// Highest load factor the slider allows. Open addressing needs free slots to stop
+    /// probing at, chains can grow as long as they like.
+    fn load_limit(self) -> f32 {
+        if self.open_addressing() {
+            0.9
+        } else {
+            3.0
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Strategy::Chaining => "Separate chaining",
+            Strategy::LinearProbing => "Linear probing",
+            Strategy::QuadraticProbing => "Quadratic probing",
+            Strategy::DoubleHashing => "Double hashing",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+enum Slot {
+    Empty,
+    Occupied(i32),
+    /// A deleted key. Lookups probe past it, inserts may reuse it.
+    Tombstone,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+enum Buckets {
+    Chains(Vec<Vec<i32>>),
+    Slots(Vec<Slot>),
+}
+
+/// Running probe counts, shown with every frame.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+struct Stats {
+    operations: usize,
+    probes: usize,
+    last_probes: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Table {
+    strategy: Strategy,
+    buckets: Buckets,
+    len: usize,
+    tombstones: usize,
+    stats: Stats,
+}
+
+impl Table {
+    fn new(strategy: Strategy, capacity: usize) -> Self {
+        let buckets = if strategy.open_addressing() {
+            Buckets::Slots(vec![Slot::Empty; capacity])
+        } else {
+            Buckets::Chains(vec![Vec::new(); capacity])
+        };
+        Self {
+            strategy,
+            buckets,
+            len: 0,
+            tombstones: 0,
+            stats: Stats::default(),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        match &self.buckets {
+            Buckets::Chains(chains) => chains.len(),
+            Buckets::Slots(slots) => slots.len(),
+        }
+    }
+
+    /// False for a table read from a session file that breaks the invariants the
+    /// probing code relies on.
+    fn is_consistent(&self) -> bool {
+        let tombstones = match &self.buckets {
+            Buckets::Chains(_) => 0,
+            Buckets::Slots(slots) => slots.iter().filter(|&&slot| slot == Slot::Tombstone).count(),
+        };
+        self.capacity().is_power_of_two()
+            && self.strategy.open_addressing() == matches!(self.buckets, Buckets::Slots(_))
+            && self.len == self.keys().len()
+            && self.tombstones == tombstones
+    }
+
+    fn load_factor(&self) -> f32 {
+        self.len as f32 / self.capacity() as f32
+    }
+
+    /// Stored keys in bucket order.
+    fn keys(&self) -> Vec<i32> {
+        match &self.buckets {
+            Buckets::Chains(chains) => chains.iter().flatten().copied().collect(),
+            Buckets::Slots(slots) => slots
+                .iter()
+                .filter_map(|slot| match slot {
+                    Slot::Occupied(key) => Some(*key),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    fn home(&self, key: i32) -> usize {
+        key.rem_euclid(self.capacity() as i32) as usize
+    }
+
+    /// Double hashing step. It is odd, so it shares no factor with the power-of-two
+    /// capacity and the probe sequence visits every slot.
+    fn step_size(&self, key: i32) -> usize {
+        let m = self.capacity() as i32;
+        (1 + 2 * key.div_euclid(m).rem_euclid((m / 2).max(1))) as usize
+    }
+
+    /// Slot tried on the `i`-th probe for `key`.
+    fn probe(&self, key: i32, i: usize) -> usize {
+        let offset = match self.strategy {
+            Strategy::Chaining | Strategy::LinearProbing => i,
+            Strategy::QuadraticProbing => i * (i + 1) / 2,
+            Strategy::DoubleHashing => i * self.step_size(key),
+        };
+        (self.home(key) + offset) % self.capacity()
+    }
+
+    fn probe_formula(&self, key: i32, i: usize) -> String {
+        let (h, m) = (self.home(key), self.capacity());
+        match self.strategy {
+            Strategy::Chaining => format!("h({}) = {} mod {} = {}", key, key, m, h),
+            Strategy::LinearProbing => format!("({} + {}) mod {} = {}", h, i, m, self.probe(key, i)),
+            Strategy::QuadraticProbing => format!("({} + {}*{}/2) mod {} = {}", h, i, i + 1, m, self.probe(key, i)),
+            Strategy::DoubleHashing => {
+                format!("({} + {}*{}) mod {} = {}", h, i, self.step_size(key), m, self.probe(key, i))
+            }
+        }
+    }
+
+    fn hash_description(&self) -> String {
+        let m = self.capacity();
+        match self.strategy {
+            Strategy::Chaining | Strategy::LinearProbing | Strategy::QuadraticProbing => format!("h(k) = k mod {}", m),
+            Strategy::DoubleHashing => format!("h(k) = k mod {}, h2(k) = 1 + 2*((k / {}) mod {})", m, m, m / 2),
+        }
+    }
+
+    /// Capacity the table must be rebuilt at before it can take one more key, if any.
+    /// Tombstones count against open addressing since probes can't stop on them; when
+    /// they are what pushes the load over, a same-size rebuild clears them.
+    fn rebuild_target(&self, max_load: f32) -> Option<usize> {
+        let m = self.capacity() as f32;
+        if (self.len + 1) as f32 / m > max_load {
+            Some(self.capacity() * 2)
+        } else if (self.len + self.tombstones + 1) as f32 / m > max_load {
+            Some(self.capacity())
+        } else {
+            None
+        }
+    }
+
+    /// Inserts a key known to be missing into a table without tombstones, recording
+    /// nothing. Returns the bucket it went to.
+    fn place(&mut self, key: i32) -> usize {
+        let bucket = match &self.buckets {
+            Buckets::Chains(_) => self.home(key),
+            Buckets::Slots(slots) => (0..slots.len())
+                .map(|i| self.probe(key, i))
+                .find(|&slot| slots[slot] == Slot::Empty)
+                .expect("a table under its load limit has an empty slot"),
+        };
+        match &mut self.buckets {
+            Buckets::Chains(chains) => chains[bucket].push(key),
+            Buckets::Slots(slots) => slots[bucket] = Slot::Occupied(key),
+        }
+        self.len += 1;
+        bucket
+    }
+
+    /// Moves every key into a fresh table of `capacity` buckets, one frame per key.
+    /// Probe totals carry over.
+    fn rebuild(&mut self, strategy: Strategy, capacity: usize, trace: &mut Trace) {
+        let keys = self.keys();
+        let mut table = Table::new(strategy, capacity);
+        table.stats = self.stats;
+        trace.probed.clear();
+        for key in keys {
+            let bucket = table.place(key);
+            trace.record(&table, Some((bucket, None)), format!("Rehash {}: {} puts it in bucket {}", key, table.hash_description(), bucket));
+        }
+        *self = table;
+    }
+}
+
+/// One snapshot shown to the user: the table, the buckets probed so far in the current
+/// operation and the one being looked at (with its position in the chain, if chaining).
+#[derive(Clone)]
+struct Frame {
+    table: Table,
+    probed: Vec<usize>,
+    current: Option<(usize, Option<usize>)>,
+    message: String,
+}
+
+/// Records the frames of one operation and counts its probes: slots inspected under
+/// open addressing, keys compared under chaining.
+struct Trace<'a> {
+    frames: &'a mut VecDeque<Frame>,
+    probed: Vec<usize>,
+    probes: usize,
+}
+
+impl<'a> Trace<'a> {
+    fn new(frames: &'a mut VecDeque<Frame>) -> Self {
+        Self {
+            frames,
+            probed: Vec::new(),
+            probes: 0,
+        }
+    }
+
+    fn record(&mut self, table: &Table, current: Option<(usize, Option<usize>)>, message: impl Into<String>) {
+        self.frames.push_back(Frame {
+            table: table.clone(),
+            probed: self.probed.clone(),
+            current,
+            message: message.into(),
+        });
+    }
+
+    fn probe(&mut self, table: &Table, bucket: usize, position: Option<usize>, message: impl Into<String>) {
+        self.probes += 1;
+        self.record(table, Some((bucket, position)), message);
+        if !self.probed.contains(&bucket) {
+            self.probed.push(bucket);
+        }
+    }
+
+    /// Adds this operation's probes to the table's totals and records the outcome.
+    fn finish(mut self, table: &mut Table, message: String) {
+        table.stats.operations += 1;
+        table.stats.probes += self.probes;
+        table.stats.last_probes = self.probes;
+        let message = format!("{} ({} probe{})", message, self.probes, if self.probes == 1 { "" } else { "s" });
+        self.record(table, None, message);
+    }
+}
+
+enum Search {
+    /// Bucket and, when chaining, position in the chain.
+    Found(usize, usize),
+    /// Where the key would go: the first tombstone passed, else the empty slot that
+    /// ended the search. `None` when open addressing found no room at all.
+    Missing(Option<usize>),
+}
+
+fn search(table: &Table, key: i32, trace: &mut Trace) -> Search {
+    match &table.buckets {
+        Buckets::Chains(chains) => {
+            let bucket = table.home(key);
+            trace.record(table, Some((bucket, None)), format!("{}, look in bucket {}", table.probe_formula(key, 0), bucket));
+            for (position, &other) in chains[bucket].iter().enumerate() {
+                if other == key {
+                    trace.probe(table, bucket, Some(position), format!("Found {} at position {} of the chain", key, position));
+                    return Search::Found(bucket, position);
+                }
+                trace.probe(table, bucket, Some(position), format!("{} != {}, follow the chain", other, key));
+            }
+            Search::Missing(Some(bucket))
+        }
+        Buckets::Slots(slots) => {
+            let mut reusable = None;
+            for i in 0..slots.len() {
+                let slot = table.probe(key, i);
+                let formula = table.probe_formula(key, i);
+                match slots[slot] {
+                    Slot::Empty => {
+                        trace.probe(table, slot, None, format!("Probe {}: {}, empty so {} is not in the table", i, formula, key));
+                        return Search::Missing(reusable.or(Some(slot)));
+                    }
+                    Slot::Tombstone => {
+                        trace.probe(table, slot, None, format!("Probe {}: {}, tombstone, keep probing", i, formula));
+                        reusable.get_or_insert(slot);
+                    }
+                    Slot::Occupied(other) if other == key => {
+                        trace.probe(table, slot, None, format!("Probe {}: {}, found {}", i, formula, key));
+                        return Search::Found(slot, 0);
+                    }
+                    Slot::Occupied(other) => {
+                        trace.probe(table, slot, None, format!("Probe {}: {}, collision with {}", i, formula, other));
+                    }
+                }
+            }
+            Search::Missing(reusable)
+        }
+    }
+}
+
+/// A change the user asked for, kept so `reset` can play it again. Inserts and
+/// switches keep the load limit they ran with, since it decides when the table grows.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum Operation {
+    Insert(i32, f32),
+    Delete(i32),
+    Lookup(i32),
+    Switch(Strategy, f32),
+}
+
+/// What a session keeps: the table the operations started from, tombstones included,
+/// and the operations, so a reopened session can replay them the same way `reset` does.
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    start: Table,
+    operations: Vec<Operation>,
+    max_load: f32,
+}
+
+pub struct HashTableVisualizer {
+    table: Table,
+    /// The table before any of `operations`: the seeded one, a cleared one or a restored one.
+    start: Table,
+    operations: Vec<Operation>,
+    max_load: f32,
+    frames: VecDeque<Frame>,
+    shown: Option<Frame>,
+    key_input: String,
+}
+
+impl HashTableVisualizer {
+    pub fn new() -> Self {
+        Self {
+            table: Table::new(Strategy::Chaining, INITIAL_CAPACITY),
+            start: Table::new(Strategy::Chaining, INITIAL_CAPACITY),
+            operations: Vec::new(),
+            max_load: 0.75,
+            frames: VecDeque::new(),
+            shown: None,
+            key_input: String::new(),
+        }
+    }
+
+    fn parsed_key(&self) -> Option<i32> {
+        self.key_input.trim().parse().ok()
+    }
+
+    fn insert(&mut self, key: i32, max_load: f32) {
+        let mut trace = Trace::new(&mut self.frames);
+        let mut found = search(&self.table, key, &mut trace);
+        if let Search::Found(..) = found {
+            trace.finish(&mut self.table, format!("{} is already in the table", key));
+            return;
+        }
+
+        if let Some(capacity) = self.table.rebuild_target(max_load) {
+            let table = &self.table;
+            let reason = if capacity > table.capacity() {
+                format!(
+                    "Load factor would reach {}/{} = {:.2} > {:.2}, grow to {} buckets and rehash",
+                    table.len + 1,
+                    table.capacity(),
+                    (table.len + 1) as f32 / table.capacity() as f32,
+                    max_load,
+                    capacity
+                )
+            } else {
+                format!("{} tombstones would push the load over {:.2}, rehash to clear them", table.tombstones, max_load)
+            };
+            trace.record(table, None, reason);
+            self.table.rebuild(self.table.strategy, capacity, &mut trace);
+            // Every key has moved, so search again for where this one goes.
+            found = search(&self.table, key, &mut trace);
+        }
+
+        let Search::Missing(Some(target)) = found else {
+            trace.finish(&mut self.table, format!("No free slot for {}", key));
+            return;
+        };
+        let message = match &mut self.table.buckets {
+            Buckets::Chains(chains) => {
+                chains[target].push(key);
+                format!("Appended {} to the chain in bucket {}", key, target)
+            }
+            Buckets::Slots(slots) => {
+                let reused = slots[target] == Slot::Tombstone;
+                slots[target] = Slot::Occupied(key);
+                if reused {
+                    self.table.tombstones -= 1;
+                    format!("Stored {} in slot {}, reusing a tombstone", key, target)
+                } else {
+                    format!("Stored {} in slot {}", key, target)
+                }
+            }
+        };
+        self.table.len += 1;
+        trace.finish(&mut self.table, message);
+    }
+
+    fn delete(&mut self, key: i32) {
+        let mut trace = Trace::new(&mut self.frames);
+        let Search::Found(bucket, position) = search(&self.table, key, &mut trace) else {
+            trace.finish(&mut self.table, format!("{} is not in the table, nothing to delete", key));
+            return;
+        };
+        let message = match &mut self.table.buckets {
+            Buckets::Chains(chains) => {
+                chains[bucket].remove(position);
+                format!("Unlinked {} from bucket {}", key, bucket)
+            }
+            Buckets::Slots(slots) => {
+                slots[bucket] = Slot::Tombstone;
+                self.table.tombstones += 1;
+                format!("Replaced {} with a tombstone so probes for keys past slot {} still find them", key, bucket)
+            }
+        };
+        self.table.len -= 1;
+        trace.finish(&mut self.table, message);
+    }
+
+    fn lookup(&mut self, key: i32) {
+        let mut trace = Trace::new(&mut self.frames);
+        let message = match search(&self.table, key, &mut trace) {
+            Search::Found(bucket, _) => format!("{} is in bucket {}", key, bucket),
+            Search::Missing(_) => format!("{} is not in the table", key),
+        };
+        trace.finish(&mut self.table, message);
+    }
+
+    /// Moves the current keys into an empty table using `strategy` in a single frame.
+    /// Chains can hold more keys than there are buckets, so the table grows until the
+    /// keys fit under `max_load`.
+    fn switch_strategy(&mut self, strategy: Strategy, max_load: f32) {
+        let mut capacity = self.table.capacity();
+        while self.table.len as f32 / capacity as f32 > max_load {
+            capacity *= 2;
+        }
+        self.table.rebuild(strategy, capacity, &mut Trace::new(&mut VecDeque::new()));
+        Trace::new(&mut self.frames).record(&self.table, None, format!("Switched to {}", strategy));
+    }
+
+    fn apply(&mut self, operation: Operation) {
+        match operation {
+            Operation::Insert(key, max_load) => self.insert(key, max_load),
+            Operation::Delete(key) => self.delete(key),
+            Operation::Lookup(key) => self.lookup(key),
+            Operation::Switch(strategy, max_load) => self.switch_strategy(strategy, max_load),
+        }
+    }
+
+    fn queue(&mut self, operation: Operation) {
+        self.apply(operation);
+        self.operations.push(operation);
+    }
+
+    /// Makes the current table the starting point with no operations after it.
+    fn start_here(&mut self, message: String) {
+        self.start = self.table.clone();
+        self.operations.clear();
+        self.frames.clear();
+        self.show_table(message);
+    }
+
+    fn show_table(&mut self, message: String) {
+        self.shown = Some(Frame {
+            table: self.table.clone(),
+            probed: Vec::new(),
+            current: None,
+            message,
+        });
+    }
+
+    fn render_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Key: ");
+            ui.add(egui::TextEdit::singleline(&mut self.key_input).desired_width(80.0));
+
+            if let Some(key) = self.parsed_key() {
+                if ui.button("Insert").clicked() {
+                    self.queue(Operation::Insert(key, self.max_load));
+                }
+                if ui.button("Delete").clicked() {
+                    self.queue(Operation::Delete(key));
+                }
+                if ui.button("Lookup").clicked() {
+                    self.queue(Operation::Lookup(key));
+                }
+            }
+            if ui.button("Insert Random").clicked() {
+                let key = rand::thread_rng().gen_range(1..100);
+                self.queue(Operation::Insert(key, self.max_load));
+            }
+            if ui.button("Clear").clicked() {
+                self.table = Table::new(self.table.strategy, INITIAL_CAPACITY);
+                self.start_here("Cleared the table".to_string());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut strategy = self.table.strategy;
+            egui::ComboBox::from_id_salt("hash_table_strategy")
+                .selected_text(strategy.to_string())
+                .show_ui(ui, |ui| {
+                    for option in Strategy::ALL {
+                        ui.selectable_value(&mut strategy, option, option.to_string());
+                    }
+                });
+            if strategy != self.table.strategy {
+                // Frames still queued show the old layout, skip to the switch.
+                self.frames.clear();
+                self.max_load = self.max_load.min(strategy.load_limit());
+                self.queue(Operation::Switch(strategy, self.max_load));
+                self.step();
+            }
+            ui.add(egui::Slider::new(&mut self.max_load, 0.25..=self.table.strategy.load_limit()).text("max load factor"));
+        });
+    }
+
+    fn render_stats(ui: &mut egui::Ui, table: &Table, max_load: f32) {
+        ui.label(format!(
+            "{} keys in {} buckets, load factor {:.2} (rehash above {:.2})",
+            table.len,
+            table.capacity(),
+            table.load_factor(),
+            max_load
+        ));
+        if table.strategy.open_addressing() {
+            ui.label(format!("Tombstones: {}", table.tombstones));
+        }
+        ui.monospace(table.hash_description());
+        let stats = table.stats;
+        let average = if stats.operations == 0 { 0.0 } else { stats.probes as f32 / stats.operations as f32 };
+        ui.label(format!(
+            "Last operation: {} probes, average {:.2} probes over {} operations",
+            stats.last_probes, average, stats.operations
+        ));
+    }
+
+    fn render_table(ui: &mut egui::Ui, frame: &Frame) {
+        let table = &frame.table;
+        let cell = egui::vec2(44.0, 30.0);
+        let gap = 8.0;
+        let label_height = 16.0;
+        let columns = ((ui.available_width() / (cell.x + gap)) as usize).max(1);
+        let chain_length = |bucket: usize| match &table.buckets {
+            Buckets::Chains(chains) => chains[bucket].len(),
+            Buckets::Slots(_) => 0,
+        };
+
+        let rows: Vec<(usize, usize)> = (0..table.capacity())
+            .step_by(columns)
+            .map(|start| (start, (start + columns).min(table.capacity())))
+            .collect();
+        let row_height = |&(start, end): &(usize, usize)| {
+            let longest = (start..end).map(chain_length).max().unwrap_or(0);
+            label_height + cell.y + longest as f32 * (cell.y + 6.0) + gap * 2.0
+        };
+        let height: f32 = rows.iter().map(row_height).sum();
+        let (response, painter) = ui.allocate_painter(egui::vec2(ui.available_width(), height), egui::Sense::hover());
+        let theme = Theme::current(ui.ctx());
+
+        let font = egui::FontId::new(14.0, egui::FontFamily::Proportional);
+        let small = egui::FontId::new(11.0, egui::FontFamily::Proportional);
+        let mut top = response.rect.top();
+        for row in &rows {
+            for bucket in row.0..row.1 {
+                let x = response.rect.left() + (bucket - row.0) as f32 * (cell.x + gap);
+                let rect = egui::Rect::from_min_size(egui::pos2(x, top + label_height), cell);
+                let is_current = frame.current.is_some_and(|(b, _)| b == bucket);
+                painter.text(
+                    egui::pos2(rect.center().x, top),
+                    egui::Align2::CENTER_TOP,
+                    bucket.to_string(),
+                    small.clone(),
+                    if is_current { theme.color(Role::Current) } else { theme.muted() },
+                );
+
+                let (fill, text) = match &table.buckets {
+                    Buckets::Chains(chains) => (Color32::TRANSPARENT, format!("{}", chains[bucket].len())),
+                    Buckets::Slots(slots) => match slots[bucket] {
+                        Slot::Empty => (Color32::TRANSPARENT, String::new()),
+                        Slot::Occupied(key) => (theme.color(Role::Frontier), key.to_string()),
+                        Slot::Tombstone => (theme.color(Role::Rejected), "DEL".to_string()),
+                    },
+                };
+                painter.rect_filled(rect, 3.0, fill);
+                let stroke = if frame.current == Some((bucket, None)) {
+                    Stroke::new(3.0, theme.color(Role::Current))
+                } else if frame.probed.contains(&bucket) {
+                    Stroke::new(2.0, theme.color(Role::Compared))
+                } else {
+                    Stroke::new(1.0, theme.line())
+                };
+                painter.rect_stroke(rect, 3.0, stroke);
+                painter.text(rect.center(), egui::Align2::CENTER_CENTER, text, font.clone(), theme.text_on(fill));
+
+                if let Buckets::Chains(chains) = &table.buckets {
+                    let mut above = rect;
+                    for (position, key) in chains[bucket].iter().enumerate() {
+                        let node = above.translate(egui::vec2(0.0, cell.y + 6.0));
+                        let fill = theme.color(Role::Frontier);
+                        painter.line_segment([above.center_bottom(), node.center_top()], Stroke::new(1.5, theme.line()));
+                        painter.rect_filled(node, 3.0, fill);
+                        if frame.current == Some((bucket, Some(position))) {
+                            painter.rect_stroke(node, 3.0, Stroke::new(3.0, theme.color(Role::Current)));
+                        }
+                        painter.text(node.center(), egui::Align2::CENTER_CENTER, key.to_string(), font.clone(), theme.text_on(fill));
+                        above = node;
+                    }
+                }
+            }
+            top += row_height(row);
+        }
+    }
+}
+
+impl Algorithm for HashTableVisualizer {
+    fn initialize(&mut self) {
+        self.table = Table::new(self.table.strategy, INITIAL_CAPACITY);
+        for key in SEED_KEYS {
+            self.insert(key, self.max_load);
+        }
+        self.table.stats = Stats::default();
+        self.start_here(format!("{} ready, enter a key to insert, delete or look up", self.table.strategy));
+    }
+
+    fn step(&mut self) {
+        if let Some(frame) = self.frames.pop_front() {
+            self.shown = Some(frame);
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        self.render_controls(ui);
+        let Some(frame) = &self.shown else {
+            return;
+        };
+        ui.label(&frame.message);
+        ui.label(format!("Pending steps: {}", self.frames.len()));
+        Self::render_stats(ui, &frame.table, self.max_load);
+        ui.add_space(8.0);
+        pan_zoom::show(ui, "table", |ui| Self::render_table(ui, frame));
+    }
+
+    fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Operations edit the table as they are queued, so like the trees this goes back
+    /// to the starting table and queues every operation again.
+    fn reset(&mut self) {
+        self.table = self.start.clone();
+        self.frames.clear();
+        for operation in self.operations.clone() {
+            self.apply(operation);
+        }
+        self.shown = Some(Frame {
+            table: self.start.clone(),
+            probed: Vec::new(),
+            current: None,
+            message: rewound_message(self.operations.len()),
+        });
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            start: self.start.clone(),
+            operations: self.operations.clone(),
+            max_load: self.max_load,
+        })
+    }
+
+    /// Replays the operations without animating them and shows where they ended.
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        if !inputs.start.is_consistent() {
+            return Err("the saved hash table is inconsistent".to_string());
+        }
+        let valid = |max_load: f32, strategy: Strategy| (0.25..=strategy.load_limit()).contains(&max_load);
+        let mut strategy = inputs.start.strategy;
+        for operation in &inputs.operations {
+            let fits = match *operation {
+                Operation::Insert(_, max_load) => valid(max_load, strategy),
+                Operation::Switch(to, max_load) => {
+                    strategy = to;
+                    valid(max_load, to)
+                }
+                Operation::Delete(_) | Operation::Lookup(_) => true,
+            };
+            if !fits {
+                return Err("the saved hash table operations use an impossible load factor".to_string());
+            }
+        }
+        self.start = inputs.start;
+        self.operations = inputs.operations;
+        self.reset();
+        self.frames.clear();
+        self.max_load = inputs.max_load.clamp(0.25, self.table.strategy.load_limit());
+        self.show_table(format!("Restored a {} table", self.table.strategy));
+        Ok(())
+    }
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        let mut entries = vec![(Role::Frontier, "Key"), (Role::Compared, "Probed"), (Role::Current, "Current")];
+        if matches!(self.table.buckets, Buckets::Slots(_)) {
+            entries.push((Role::Rejected, "Tombstone"));
+        }
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switching_a_full_chained_table_to_probing_grows_it() {
+        let mut visualizer = HashTableVisualizer::new();
+        for key in 0..24 {
+            visualizer.insert(key, 3.0);
+        }
+        assert_eq!(visualizer.table.capacity(), INITIAL_CAPACITY);
+
+        visualizer.switch_strategy(Strategy::LinearProbing, Strategy::LinearProbing.load_limit());
+        let table = &visualizer.table;
+        assert!(table.is_consistent());
+        assert!(table.load_factor() <= Strategy::LinearProbing.load_limit());
+        let mut keys = table.keys();
+        keys.sort();
+        assert_eq!(keys, (0..24).collect::<Vec<_>>());
+    }
+}
@@ -1,17 +1,20 @@
-use crate::algorithm::Algorithm;
+use crate::algorithm::{Action, Algorithm};
+use crate::pan_zoom;
+use crate::session;
+use crate::sort_input::{self, SortInput};
+use crate::theme::{Role, Theme};
 use eframe::egui;
-use rand::Rng;
-use std::time::Instant;
 
 pub struct HeapSortVisualizer {
+    input: SortInput,
+    original: Vec<i32>,
     data: Vec<i32>,
     heap_size: usize,
     current_index: usize,
     comparing_indices: (usize, usize),
     swapping_indices: (usize, usize),
-    is_auto_traversing: bool,
-    last_step: Option<Instant>,
     stage: HeapSortStage,
+    clicked: Option<usize>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -24,47 +27,92 @@ enum HeapSortStage {
 impl HeapSortVisualizer {
     pub fn new() -> Self {
         Self {
+            input: SortInput::new(20),
+            original: Vec::new(),
             data: Vec::new(),
             heap_size: 0,
             current_index: 0,
             comparing_indices: (0, 0),
             swapping_indices: (0, 0),
-            is_auto_traversing: false,
-            last_step: None,
             stage: HeapSortStage::BuildHeap,
+            clicked: None,
         }
     }
 
+    /// Sifts `data[i]` down with the same core the benchmark runs and highlights where
+    /// it stopped.
     fn heapify(&mut self, i: usize) {
-        let left = 2 * i + 1;
-        let right = 2 * i + 2;
-        let mut largest = i;
-
-        if left < self.heap_size && self.data[left] > self.data[largest] {
-            largest = left;
+        let sift = sift_down(&mut self.data, self.heap_size, i);
+        self.comparing_indices = sift.compared;
+        if let Some(swapped) = sift.swapped {
+            self.swapping_indices = swapped;
         }
+    }
+}
 
-        if right < self.heap_size && self.data[right] > self.data[largest] {
-            largest = right;
-        }
+/// What one sift-down did.
+struct Sift {
+    /// Comparisons and swaps.
+    operations: usize,
+    /// The node it stopped at and the largest of it and its children, the same index
+    /// once the heap property holds.
+    compared: (usize, usize),
+    /// The last pair swapped, if any.
+    swapped: Option<(usize, usize)>,
+}
 
-        self.comparing_indices = (i, largest);
+/// Index of the largest of node `i` and its children within the first `heap_size` values.
+fn largest_child(data: &[i32], heap_size: usize, i: usize) -> usize {
+    let left = 2 * i + 1;
+    let right = 2 * i + 2;
+    let mut largest = i;
+
+    if left < heap_size && data[left] > data[largest] {
+        largest = left;
+    }
+
+    if right < heap_size && data[right] > data[largest] {
+        largest = right;
+    }
+    largest
+}
 
-        if largest != i {
-            self.swapping_indices = (i, largest);
-            self.data.swap(i, largest);
-            self.heapify(largest);
+/// Sifts `data[i]` down until it is larger than its children. Shared by the visualizer,
+/// one call per step, and by `heap_sort`.
+fn sift_down(data: &mut [i32], heap_size: usize, mut i: usize) -> Sift {
+    let mut operations = 0;
+    let mut swapped = None;
+    loop {
+        operations += (2 * i + 1 < heap_size) as usize + (2 * i + 2 < heap_size) as usize;
+        let largest = largest_child(data, heap_size, i);
+        if largest == i {
+            return Sift { operations, compared: (i, largest), swapped };
         }
+        data.swap(i, largest);
+        operations += 1;
+        swapped = Some((i, largest));
+        i = largest;
+    }
+}
+
+/// Sorts `data` in one go, without the visualizer. Returns the operations it took.
+pub fn heap_sort(data: &mut [i32]) -> usize {
+    let n = data.len();
+    let mut operations = 0;
+    for i in (0..n / 2).rev() {
+        operations += sift_down(data, n, i).operations;
+    }
+    for end in (1..n).rev() {
+        data.swap(0, end);
+        operations += 1 + sift_down(data, end, 0).operations;
     }
+    operations
 }
 
 impl Algorithm for HeapSortVisualizer {
     fn initialize(&mut self) {
-        let mut rng = rand::thread_rng();
-        self.data = (0..20).map(|_| rng.gen_range(1..100)).collect();
-        self.heap_size = self.data.len();
-        self.current_index = self.heap_size / 2 - 1;
-        self.stage = HeapSortStage::BuildHeap;
+        self.original = self.input.generate();
+        self.reset();
     }
 
     fn step(&mut self) {
@@ -87,7 +135,8 @@ impl Algorithm for HeapSortVisualizer {
                     self.heap_size -= 1;
                     self.heapify(0);
                     self.current_index -= 1;
-                } else {
+                }
+                if self.current_index == 0 {
                     self.stage = HeapSortStage::Done;
                 }
             }
@@ -98,55 +147,72 @@ impl Algorithm for HeapSortVisualizer {
     fn render(&mut self, ui: &mut egui::Ui) {
         ui.label("Heap Sort Visualization");
         ui.label(format!("Current Stage: {:?}", self.stage));
-
-        let canvas_size = egui::Vec2::new(800.0, 400.0);
-        let (response, painter) = ui.allocate_painter(canvas_size, egui::Sense::hover());
-        let rect = response.rect;
-
-        let bar_width = rect.width() / self.data.len() as f32;
-        let scale_factor = rect.height() / 100.0;
-
-        for (i, &value) in self.data.iter().enumerate() {
-            let x = rect.left() + (i as f32 * bar_width);
-            let height = value as f32 * scale_factor;
-            let y = rect.bottom() - height;
-
-            let color = if i == self.comparing_indices.0 || i == self.comparing_indices.1 {
-                egui::Color32::YELLOW
-            } else if i == self.swapping_indices.0 || i == self.swapping_indices.1 {
-                egui::Color32::RED
-            } else {
-                egui::Color32::BLUE
-            };
-
-            painter.rect_filled(
-                egui::Rect::from_min_max(
-                    egui::pos2(x, y),
-                    egui::pos2(x + bar_width - 2.0, rect.bottom()),
-                ),
-                0.0,
-                color,
-            );
+        if self.input.controls(ui) {
+            self.initialize();
         }
+
+        let theme = Theme::current(ui.ctx());
+        self.clicked = pan_zoom::show(ui, "bars", |ui| {
+            sort_input::draw_bars(ui, &self.data, 400.0, |i| {
+                theme.color(if i == self.comparing_indices.0 || i == self.comparing_indices.1 {
+                    Role::Compared
+                } else if i == self.swapping_indices.0 || i == self.swapping_indices.1 {
+                    Role::Swapped
+                } else if i < self.heap_size {
+                    Role::Frontier
+                } else {
+                    Role::Visited
+                })
+            })
+        });
     }
 
-    fn auto_play(&self) -> bool {
-        self.is_auto_traversing
+    fn is_finished(&self) -> bool {
+        self.stage == HeapSortStage::Done
     }
 
-    fn toggle_auto_traverse(&mut self) {
-        self.is_auto_traversing = !self.is_auto_traversing;
+    fn reset(&mut self) {
+        self.data = self.original.clone();
+        self.heap_size = self.data.len();
+        self.current_index = self.heap_size / 2 - 1;
+        self.comparing_indices = (0, 0);
+        self.swapping_indices = (0, 0);
+        self.stage = HeapSortStage::BuildHeap;
     }
 
-    fn start(&mut self) {
-        self.initialize();
+    /// The first swap of the next step: sifting down `current_index` while building the
+    /// heap, or moving the root behind the heap while sorting.
+    fn peek(&self) -> Option<Action> {
+        match self.stage {
+            HeapSortStage::BuildHeap => {
+                let largest = largest_child(&self.data, self.heap_size, self.current_index);
+                (largest != self.current_index).then_some(Action::Swap(self.current_index, largest))
+            }
+            HeapSortStage::Sorting if self.current_index > 0 => Some(Action::Swap(0, self.current_index)),
+            _ => None,
+        }
+    }
+
+    fn take_click(&mut self) -> Option<usize> {
+        self.clicked.take()
     }
 
-    fn last_step_time(&self) -> Option<Instant> {
-        self.last_step
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![
+            (Role::Frontier, "In the heap"),
+            (Role::Compared, "Compared"),
+            (Role::Swapped, "Swapped"),
+            (Role::Visited, "Sorted"),
+        ]
     }
 
-    fn set_last_step_time(&mut self, time: Option<Instant>) {
-        self.last_step = time;
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&self.input)
     }
-}
\ No newline at end of file
+
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        self.input = session::decode::<SortInput>(inputs)?.clamped();
+        self.initialize();
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use crate::sorting::{Recorder, SortKind, SortVisualizer};
+use serde::{Deserialize, Serialize};
+
+/// Grows a sorted prefix by sinking each new value left past every larger one.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Insertion;
+
+pub type InsertionSortVisualizer = SortVisualizer<Insertion>;
+
+impl SortKind for Insertion {
+    const NAME: &'static str = "Insertion sort";
+    const STABLE: bool = true;
+
+    fn sort(&self, rec: &mut Recorder) {
+        for i in 1..rec.len() {
+            rec.mark(0..=i, format!("a[0..{}] is sorted, insert a[{}] = {}", i, i, rec.key(i)));
+            let mut j = i;
+            // Strictly less, so equal keys never jump over each other.
+            while j > 0 && rec.less(j, j - 1) {
+                rec.swap(j, j - 1);
+                j -= 1;
+            }
+        }
+    }
+}
@@ -1,12 +1,20 @@
 use crate::algorithm::Algorithm;
+use crate::session;
 use eframe::egui;
-use egui::Color32;
+use serde::{Deserialize, Serialize};
+use crate::theme::{Role, Theme};
 
 #[derive(Clone, PartialEq)]
 enum CellState {
     Default,
     Current,
-    LCS(usize),  
+    Lcs(usize),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    str1: String,
+    str2: String,
 }
 
 pub struct LCSVisualizer {
@@ -17,8 +25,6 @@ pub struct LCSVisualizer {
     current_i: usize,
     current_j: usize,
     phase: LCSPhase,
-    auto_play: bool,
-    last_step_time: Option<std::time::Instant>,
     lcs_result: String,
 }
 
@@ -43,8 +49,6 @@ impl LCSVisualizer {
             current_i: 1,
             current_j: 1,
             phase: LCSPhase::Building,
-            auto_play: false,
-            last_step_time: None,
             lcs_result: String::new(),
         }
     }
@@ -54,15 +58,10 @@ impl LCSVisualizer {
         let n = self.str2.len();
 
         if self.current_i <= m {
-            if self.str1.as_bytes()[self.current_i - 1] == self.str2.as_bytes()[self.current_j - 1] {
-                self.dp_table[self.current_i][self.current_j] =
-                    self.dp_table[self.current_i - 1][self.current_j - 1] + 1;
+            if fill_cell(&mut self.dp_table, self.str1.as_bytes(), self.str2.as_bytes(), self.current_i, self.current_j) {
                 self.cell_states[self.current_i][self.current_j] =
-                    CellState::LCS(self.dp_table[self.current_i][self.current_j]);
+                    CellState::Lcs(self.dp_table[self.current_i][self.current_j]);
             } else {
-                self.dp_table[self.current_i][self.current_j] =
-                    usize::max(self.dp_table[self.current_i - 1][self.current_j],
-                               self.dp_table[self.current_i][self.current_j - 1]);
                 self.cell_states[self.current_i][self.current_j] = CellState::Current;
             }
 
@@ -99,12 +98,35 @@ impl LCSVisualizer {
             true
         } else {
             self.phase = LCSPhase::Done;
-            self.auto_play = false;
             false
         }
     }
 }
 
+/// Fills `table[i][j]` from its neighbours above and to the left. Returns true when
+/// `a[i - 1]` and `b[j - 1]` match and extend the diagonal.
+fn fill_cell(table: &mut [Vec<usize>], a: &[u8], b: &[u8], i: usize, j: usize) -> bool {
+    let matched = a[i - 1] == b[j - 1];
+    table[i][j] = if matched {
+        table[i - 1][j - 1] + 1
+    } else {
+        usize::max(table[i - 1][j], table[i][j - 1])
+    };
+    matched
+}
+
+/// Length of the longest common subsequence of `a` and `b`, computed in one go without
+/// the visualizer. Also returns the operations it took, one per table cell.
+pub fn lcs_length(a: &[u8], b: &[u8]) -> (usize, usize) {
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            fill_cell(&mut table, a, b, i, j);
+        }
+    }
+    (table[a.len()][b.len()], a.len() * b.len())
+}
+
 impl Algorithm for LCSVisualizer {
     fn initialize(&mut self) {
         let m = self.str1.len();
@@ -142,6 +164,7 @@ impl Algorithm for LCSVisualizer {
 
         ui.separator();
         ui.label("DP Table:");
+        let theme = Theme::current(ui.ctx());
 
         ui.horizontal(|ui| {
             ui.label("  ");
@@ -162,10 +185,10 @@ impl Algorithm for LCSVisualizer {
                     let text = self.dp_table[i][j].to_string();
                     match self.cell_states[i][j] {
                         CellState::Current => {
-                            ui.colored_label(Color32::DARK_BLUE, text);
+                            ui.colored_label(theme.color(Role::Current), text);
                         }
-                        CellState::LCS(_) => {
-                            ui.colored_label(Color32::BLUE, text);
+                        CellState::Lcs(_) => {
+                            ui.colored_label(theme.color(Role::Solution), text);
                         }
                         CellState::Default => {
                             ui.label(text);
@@ -178,13 +201,21 @@ impl Algorithm for LCSVisualizer {
         ui.separator();
         ui.horizontal(|ui| {
             ui.label("LCS:");
-            ui.colored_label(Color32::GREEN, format!("{}", self.lcs_result));
+            ui.colored_label(theme.color(Role::Solution), &self.lcs_result);
         });
     }
 
-    fn auto_play(&self) -> bool { self.auto_play }
-    fn toggle_auto_traverse(&mut self) { self.auto_play = !self.auto_play; }
-    fn start(&mut self) { self.initialize(); }
-    fn last_step_time(&self) -> Option<std::time::Instant> { self.last_step_time }
-    fn set_last_step_time(&mut self, time: Option<std::time::Instant>) { self.last_step_time = time; }
-}
\ No newline at end of file
+    fn is_finished(&self) -> bool { self.phase == LCSPhase::Done }
+    fn reset(&mut self) { self.initialize(); }
+    fn legend(&self) -> Vec<(Role, &'static str)> { vec![(Role::Current, "Current cell"), (Role::Solution, "On the LCS path")] }
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs { str1: self.str1.clone(), str2: self.str2.clone() })
+    }
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        self.str1 = inputs.str1;
+        self.str2 = inputs.str2;
+        self.initialize();
+        Ok(())
+    }
+}
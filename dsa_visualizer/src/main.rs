@@ -12,16 +12,56 @@ mod longest_common_sequence;
 mod knapsack;
 mod kmp;
 mod rabin_carp;
+mod trie;
+mod suffix_array;
 mod gradient_descent;
 mod euclidean;
+mod binary_tree;
+mod bst;
+mod avl_tree;
+mod red_black_tree;
+mod graph;
+mod topological_sort;
+mod strongly_connected_components;
+mod grid_pathfinding;
+mod prim;
+mod max_flow;
+mod hash_table;
+mod catalog;
+mod sort_input;
+mod sorting;
+mod insertion_sort;
+mod selection_sort;
+mod bubble_sort;
+mod shell_sort;
+mod counting_sort;
+mod radix_sort;
+mod tim_sort;
+mod offscreen;
+mod export;
+mod cli;
+mod quiz;
+mod theme;
+mod pan_zoom;
+mod session;
+mod benchmark;
 
-use std::time::Duration;
-use algorithm::Algorithm;
-use bfs::BFSVisualizer;
+use std::time::{Duration, Instant};
+use algorithm::{Lifecycle, LifecycleEvent, Runner};
+use benchmark::Benchmark;
+use catalog::Registry;
 use eframe::egui;
-use crate::dfs::DFSVisualizer;
+use export::ExportSettings;
+use quiz::Quiz;
+use session::{FileAction, Saved, Session};
+use theme::Theme;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     let ctx = egui::Context::default();
     let mut size = ctx.used_size();
     size.x = 1200.00;
@@ -35,122 +75,310 @@ fn main() {
     eframe::run_native(
         "DSA Visualizer",
         options,
-        Box::new(|_cc| Ok(Box::new(DSAVisualizer::default()))),
+        Box::new(|cc| Ok(Box::new(DSAVisualizer::new(cc)))),
     )
         .expect("Unexpected error in running the application");
 }
 
 struct DSAVisualizer {
-    current_scene: String,
-    current_algorithm: Option<Box<dyn Algorithm>>,
+    registry: Registry,
+    search: String,
+    current_entry: Option<usize>,
+    runner: Option<Runner>,
+    /// Most recent thing the runner reported, shown next to the playback controls.
+    last_event: Option<LifecycleEvent>,
+    export_settings: ExportSettings,
+    export_status: Option<String>,
+    /// Export being written in the background, polled once per frame.
+    export_job: Option<export::Job>,
+    quiz: Quiz,
+    theme: Theme,
+    recent: Vec<Session>,
+    session_path: String,
+    session_status: Option<String>,
+    /// Open instead of the catalog while the benchmark screen is shown.
+    benchmark: Option<Benchmark>,
 }
 
 impl Default for DSAVisualizer {
     fn default() -> Self {
         Self {
-            current_scene: String::new(),
-            current_algorithm: None,
+            registry: catalog::default_registry(),
+            search: String::new(),
+            current_entry: None,
+            runner: None,
+            last_event: None,
+            export_settings: ExportSettings::new(),
+            export_status: None,
+            export_job: None,
+            quiz: Quiz::default(),
+            theme: Theme::default(),
+            recent: Vec::new(),
+            session_path: String::from("session.json"),
+            session_status: None,
+            benchmark: None,
         }
     }
 }
 
-impl eframe::App for DSAVisualizer {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if self.current_scene.is_empty() {
-                ui.vertical_centered(|ui| {
-                    ui.heading("Algorithms");
-                    let button_size = egui::Vec2::new(320.0, 0.0);
-                    if ui.add_sized(button_size, egui::Button::new("Breadth First Search (BFS)")).clicked() {
-                        self.current_scene = "BFS".to_string();
-                        self.current_algorithm = Some(Box::new(BFSVisualizer::new()));
-                        self.current_algorithm.as_mut().unwrap().initialize();
-                    }
-                    if ui.add_sized(button_size, egui::Button::new("Depth First Search (DFS)")).clicked() {
-                        self.current_scene = "DFS".to_string();
-                        self.current_algorithm = Some(Box::new(DFSVisualizer::new()));
-                        self.current_algorithm.as_mut().unwrap().initialize();
-                    }
-                    if ui.add_sized(button_size, egui::Button::new("Dijkstra's Algorithm")).clicked() {
-                        self.current_scene = "Dijkstra".to_string();
-                        self.current_algorithm = Some(Box::new(dijkstra::DijkstraVisualizer::new()));
-                        self.current_algorithm.as_mut().unwrap().initialize();
-                    }
-                    if ui.add_sized(button_size, egui::Button::new("Merge Sort")).clicked() {
-                        self.current_scene = "Merge Sort".to_string();
-                        self.current_algorithm = Some(Box::new(merge_sort::MergeSortVisualizer::new()));
-                        self.current_algorithm.as_mut().unwrap().initialize();
-                    }
-                    if ui.add_sized(button_size, egui::Button::new("Heap Sort")).clicked() {
-                        self.current_scene = "Heap Sort".to_string();
-                        self.current_algorithm = Some(Box::new(heap_sort::HeapSortVisualizer::new()));
-                        self.current_algorithm.as_mut().unwrap().initialize();
-                    }
-                    if ui.add_sized(button_size, egui::Button::new("Gradient Descent")).clicked() {
-                        self.current_scene = "Gradient Descent".to_string();
-                        self.current_algorithm = Some(Box::new(gradient_descent::GradientDescentVisualizer::new()));
-                        self.current_algorithm.as_mut().unwrap().initialize();
-                    }
-                    if ui.add_sized(button_size, egui::Button::new("Longest Common Sequence")).clicked() {
-                        self.current_scene = "Longest Common Sequence".to_string();
-                        self.current_algorithm = Some(Box::new(longest_common_sequence::LCSVisualizer::new()));
-                        self.current_algorithm.as_mut().unwrap().initialize();
-                    }
-                    if ui.add_sized(button_size, egui::Button::new("Euclidean Algorithm")).clicked() {
-                        self.current_scene = "Euclidean Algorithm".to_string();
-                        self.current_algorithm = Some(Box::new(euclidean::EuclideanVisualizer::new()));
-                        self.current_algorithm.as_mut().unwrap().initialize();
-                    }
+impl DSAVisualizer {
+    /// Restores the theme, recent sessions and the visualizer that was open when the
+    /// app last closed.
+    fn new(cc: &eframe::CreationContext) -> Self {
+        let mut app = Self::default();
+        let saved: Saved = cc.storage.and_then(|storage| eframe::get_value(storage, eframe::APP_KEY)).unwrap_or_default();
+        app.theme = saved.theme;
+        app.recent = saved.recent;
+        if !saved.session_path.is_empty() {
+            app.session_path = saved.session_path;
+        }
+        if let Some(session) = saved.current {
+            app.open_session(&session);
+        }
+        app
+    }
+
+    fn current_session(&mut self) -> Option<Session> {
+        let entry = self.current_entry.and_then(|i| self.registry.get(i))?;
+        Some(Session::capture(entry.name, self.runner.as_mut()?))
+    }
+
+    /// Leaves the open visualizer, keeping it in the recent list.
+    fn close_session(&mut self) {
+        if let Some(session) = self.current_session() {
+            session::remember(&mut self.recent, session);
+        }
+        self.current_entry = None;
+        self.runner = None;
+        self.last_event = None;
+        self.export_status = None;
+    }
+
+    fn open_session(&mut self, session: &Session) {
+        match session.open(&self.registry) {
+            Ok((index, runner)) => {
+                self.close_session();
+                self.current_entry = Some(index);
+                self.runner = Some(runner);
+                self.session_status = None;
+            }
+            Err(e) => self.session_status = Some(format!("Could not open session: {}", e)),
+        }
+    }
+
+    fn session_file(&mut self, action: FileAction) {
+        match action {
+            FileAction::Export => {
+                let Some(session) = self.current_session() else {
+                    return;
+                };
+                self.session_status = Some(match session.export(&self.session_path) {
+                    Ok(()) => format!("Wrote session to {}", self.session_path),
+                    Err(e) => format!("Export failed: {}", e),
                 });
+            }
+            FileAction::Import => match Session::import(&self.session_path) {
+                Ok(session) => self.open_session(&session),
+                Err(e) => self.session_status = Some(format!("Import failed: {}", e)),
+            },
+        }
+    }
 
-            } else {
-                if let Some(algorithm) = &mut self.current_algorithm {
-                    ui.heading(format!("{} Algorithm Visualization", self.current_scene));
+    fn render_session_file(&mut self, ui: &mut egui::Ui) {
+        let can_export = self.runner.is_some();
+        if let Some(action) = session::file_panel(ui, &mut self.session_path, can_export) {
+            self.session_file(action);
+        }
+        if let Some(status) = &self.session_status {
+            ui.label(status);
+        }
+    }
+}
 
-                    if ui.button("Back").clicked() {
-                        self.current_scene.clear();
-                        self.current_algorithm = None;
-                        return;
-                    }
+impl DSAVisualizer {
+    fn render_catalog(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            ui.heading("Algorithms");
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search algorithms...").desired_width(320.0));
+            if ui.button("Complexity benchmark").clicked() {
+                self.benchmark = Some(Benchmark::new());
+            }
+        });
+        ui.add_space(8.0);
 
-                    ui.separator();
-                    ui.add_space(12.0);
+        if !self.recent.is_empty() {
+            let mut opened = None;
+            egui::CollapsingHeader::new("Recent sessions")
+                .default_open(true)
+                .show(ui, |ui| opened = session::recent_list(ui, &self.recent));
+            if let Some(session) = opened {
+                self.open_session(&session);
+                return;
+            }
+        }
+        self.render_session_file(ui);
 
-                    algorithm.render(ui);
+        if self.quiz.score.total.asked > 0 {
+            egui::CollapsingHeader::new("Quiz score this session").show(ui, |ui| self.quiz.score.summary(ui));
+        }
 
-                    if ui.button("Start").clicked() {
-                        algorithm.toggle_auto_traverse();
-                        algorithm.start();
-                    }
+        let mut selected = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let groups = self.registry.grouped(&self.search);
+            if groups.is_empty() {
+                ui.label(format!("No algorithm matches \"{}\"", self.search));
+            }
+            for (category, entries) in groups {
+                egui::CollapsingHeader::new(category.to_string())
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        for (index, entry) in entries {
+                            ui.horizontal(|ui| {
+                                if ui.add_sized(egui::Vec2::new(320.0, 0.0), egui::Button::new(entry.name)).clicked() {
+                                    selected = Some(index);
+                                }
+                                ui.monospace(entry.complexity);
+                                ui.weak(entry.description);
+                            });
+                        }
+                    });
+            }
+        });
 
-                    if ui.button("Pause").clicked() {
-                        algorithm.toggle_auto_traverse();
-                    }
+        if let Some(index) = selected {
+            self.runner = self.registry.get(index).map(|entry| Runner::new(entry.create()));
+            self.current_entry = Some(index);
+        }
+    }
+}
 
-                    if ui.button("Resume").clicked() {
-                        algorithm.toggle_auto_traverse();
-                    }
+impl DSAVisualizer {
+    /// In quiz mode the quiz does the stepping, so only Pause and Reset stay available.
+    fn render_controls(ui: &mut egui::Ui, runner: &mut Runner, quiz: bool, last_event: Option<LifecycleEvent>) {
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!quiz && runner.can_start(), egui::Button::new("Start")).clicked() {
+                runner.start();
+            }
+            if ui.add_enabled(runner.can_pause(), egui::Button::new("Pause")).clicked() {
+                runner.pause();
+            }
+            if ui.add_enabled(!quiz && runner.can_resume(), egui::Button::new("Resume")).clicked() {
+                runner.resume();
+            }
+            if ui.add_enabled(!quiz && runner.can_step(), egui::Button::new("Next Step")).clicked() {
+                runner.step();
+            }
+            if ui.add_enabled(runner.can_reset(), egui::Button::new("Reset")).clicked() {
+                runner.reset();
+            }
 
-                    if ui.button("Next Step").clicked() {
-                        algorithm.step();
-                    }
+            let mut seconds = runner.step_interval.as_secs_f32();
+            if ui.add(egui::Slider::new(&mut seconds, 0.05..=3.0).text("seconds per step")).changed() {
+                runner.step_interval = Duration::from_secs_f32(seconds);
+            }
+        });
 
-                }
+        let status = match runner.state() {
+            Lifecycle::Idle => "Idle".to_string(),
+            Lifecycle::Running => format!("Running, step {}", runner.steps()),
+            Lifecycle::Paused => format!("Paused at step {}", runner.steps()),
+            Lifecycle::Finished => format!("Finished after {} steps", runner.steps()),
+        };
+        ui.horizontal(|ui| {
+            ui.label(status);
+            if let Some(event) = last_event {
+                ui.separator();
+                ui.weak(event.to_string());
             }
         });
+    }
+}
+
+impl eframe::App for DSAVisualizer {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.theme.install(ctx);
+        egui::TopBottomPanel::top("theme_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                self.theme.settings(ui);
+                ui.separator();
+                ui.weak("Canvases: drag to pan, Ctrl+scroll to zoom, double-click to reset");
+            });
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(benchmark) = &mut self.benchmark {
+                ui.heading("Complexity Benchmark");
+                if ui.button("Back").clicked() {
+                    self.benchmark = None;
+                    return;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| benchmark.render(ui));
+                return;
+            }
+
+            let Some(entry) = self.current_entry.and_then(|i| self.registry.get(i)) else {
+                self.render_catalog(ui);
+                return;
+            };
 
-        if let Some(algorithm) = &mut self.current_algorithm {
-            if algorithm.auto_play() {
-                let now = std::time::Instant::now();
-                if algorithm.last_step_time().map_or(true, |t| now.duration_since(t) >= Duration::from_secs(2)) {
-                    println!("running algorithm");
-                    algorithm.step();
-                    algorithm.set_last_step_time(Some(now));
+            if let Some(runner) = &mut self.runner {
+                ui.heading(format!("{} Algorithm Visualization", entry.name));
+
+                if ui.button("Back").clicked() {
+                    self.close_session();
+                    return;
+                }
+
+                ui.separator();
+                theme::legend(ui, &runner.algorithm().legend());
+                ui.add_space(12.0);
+
+                runner.algorithm().render(ui);
+                Self::render_controls(ui, runner, self.quiz.enabled, self.last_event);
+                self.quiz.update(runner, entry.name);
+                self.quiz.panel(ui, runner);
+
+                if export::export_panel(ui, &mut self.export_settings, self.export_job.is_some()) {
+                    self.export_settings.theme = self.theme;
+                    // Exporting rewinds and steps, so it runs on a copy built from the same
+                    // inputs and the run on screen keeps its place.
+                    let inputs = runner.algorithm().save_inputs();
+                    self.export_job = Some(export::Job::start(entry.factory, inputs, self.export_settings.clone()));
+                    self.export_status = Some(format!("Exporting to {}...", self.export_settings.path));
+                }
+                if let Some(status) = &self.export_status {
+                    ui.label(status);
                 }
+                self.render_session_file(ui);
+            }
+        });
+
+        if let Some(result) = self.export_job.as_ref().and_then(export::Job::poll) {
+            let path = self.export_job.take().map(|job| job.path).unwrap_or_default();
+            self.export_status = Some(match result {
+                Ok(frames) => format!("Wrote {} frames to {}", frames, path),
+                Err(e) => format!("Export failed: {}", e),
+            });
+        }
+
+        if let Some(runner) = &mut self.runner {
+            runner.tick(Instant::now());
+            if let Some(event) = runner.drain_events().pop() {
+                self.last_event = Some(event);
             }
         }
 
         ctx.request_repaint();
     }
-}
 
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let saved = Saved {
+            theme: self.theme,
+            current: self.current_session(),
+            recent: self.recent.clone(),
+            session_path: self.session_path.clone(),
+        };
+        eframe::set_value(storage, eframe::APP_KEY, &saved);
+    }
+}
@@ -0,0 +1,376 @@
+use crate::algorithm::Algorithm;
+use crate::graph::{self, EdgeInput, Graph, GraphStyle};
+use crate::session;
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Clone, Default)]
+struct Snapshot {
+    flow: HashMap<(usize, usize), i64>,
+    reached: HashSet<usize>,
+    path: Vec<usize>,
+    total: i64,
+    cut: Vec<(usize, usize)>,
+    message: String,
+}
+
+/// What a session keeps: the edited graph, its source and sink, and the residual toggle.
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    graph: Graph,
+    source: usize,
+    sink: usize,
+    show_residual: bool,
+}
+
+/// Edmonds–Karp maximum flow: repeatedly augment along the shortest path in the
+/// residual graph found by BFS. Edge weights of the graph are capacities.
+pub struct MaxFlowVisualizer {
+    graph: Graph,
+    source: usize,
+    sink: usize,
+    show_residual: bool,
+    snapshots: Vec<Snapshot>,
+    current_step: usize,
+    edge_input: EdgeInput,
+}
+
+impl MaxFlowVisualizer {
+    pub fn new() -> Self {
+        Self {
+            graph: Graph::new(),
+            source: 0,
+            sink: 5,
+            show_residual: false,
+            snapshots: Vec::new(),
+            current_step: 0,
+            edge_input: EdgeInput::new(true, false),
+        }
+    }
+
+    fn example_graph() -> Graph {
+        let mut graph = Graph::new();
+        let edges = [(0, 1, 16), (0, 2, 13), (1, 3, 12), (2, 1, 4), (2, 4, 14), (3, 2, 9), (3, 5, 20), (4, 3, 7), (4, 5, 4)];
+        for (from, to, capacity) in edges {
+            graph::add_edge(&mut graph, from, to, capacity);
+        }
+        graph
+    }
+
+    fn capacity(&self, from: usize, to: usize) -> i64 {
+        self.graph
+            .get(&from)
+            .and_then(|edges| edges.iter().find(|&&(n, _)| n == to))
+            .map_or(0, |&(_, capacity)| capacity as i64)
+    }
+
+    /// Residual capacities as a graph of the same shape: an edge u -> v exists
+    /// wherever more flow can be pushed from u to v, including undoing flow on v -> u.
+    fn residual_graph(&self, flow: &HashMap<(usize, usize), i64>) -> Graph {
+        let mut residual = Graph::new();
+        for node in graph::nodes(&self.graph) {
+            residual.entry(node).or_default();
+        }
+        let mut pairs = HashSet::new();
+        for (&from, edges) in &self.graph {
+            for &(to, _) in edges {
+                pairs.insert((from, to));
+                pairs.insert((to, from));
+            }
+        }
+        for (from, to) in pairs {
+            let remaining = self.capacity(from, to) - flow.get(&(from, to)).copied().unwrap_or(0);
+            if remaining > 0 {
+                residual.entry(from).or_default().push((to, remaining as usize));
+            }
+        }
+        residual
+    }
+
+    /// Shortest augmenting path by BFS, plus every node the search reached.
+    fn bfs(&self, residual: &Graph) -> (Option<Vec<usize>>, HashSet<usize>) {
+        let mut previous = HashMap::new();
+        let mut reached = HashSet::from([self.source]);
+        let mut queue = VecDeque::from([self.source]);
+
+        while let Some(node) = queue.pop_front() {
+            let mut neighbors: Vec<usize> = residual[&node].iter().map(|&(n, _)| n).collect();
+            neighbors.sort_unstable();
+            for neighbor in neighbors {
+                if reached.insert(neighbor) {
+                    previous.insert(neighbor, node);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        if !reached.contains(&self.sink) {
+            return (None, reached);
+        }
+        let mut path = vec![self.sink];
+        while let Some(&node) = previous.get(path.last().unwrap()) {
+            path.push(node);
+        }
+        path.reverse();
+        (Some(path), reached)
+    }
+
+    fn format_path(path: &[usize]) -> String {
+        path.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" -> ")
+    }
+
+    fn generate_steps(&mut self) {
+        self.snapshots.clear();
+        self.current_step = 0;
+        let nodes = graph::nodes(&self.graph);
+        if !nodes.contains(&self.source) || !nodes.contains(&self.sink) || self.source == self.sink {
+            self.snapshots.push(Snapshot {
+                message: "Pick two different vertices of the graph as source and sink".to_string(),
+                ..Default::default()
+            });
+            return;
+        }
+
+        let mut state = Snapshot::default();
+        loop {
+            let residual = self.residual_graph(&state.flow);
+            let (path, reached) = self.bfs(&residual);
+            state.reached = reached;
+
+            let Some(path) = path else {
+                state.path.clear();
+                state.cut = self
+                    .graph
+                    .iter()
+                    .flat_map(|(&from, edges)| edges.iter().map(move |&(to, _)| (from, to)))
+                    .filter(|(from, to)| state.reached.contains(from) && !state.reached.contains(to))
+                    .collect();
+                state.cut.sort_unstable();
+                let cut_capacity: i64 = state.cut.iter().map(|&(from, to)| self.capacity(from, to)).sum();
+                let message = format!(
+                    "No augmenting path left. Max flow {} equals the capacity {} of the min cut {:?}",
+                    state.total, cut_capacity, state.cut
+                );
+                self.record(&state, message);
+                break;
+            };
+
+            let bottleneck = path
+                .windows(2)
+                .map(|pair| self.capacity(pair[0], pair[1]) - state.flow.get(&(pair[0], pair[1])).copied().unwrap_or(0))
+                .min()
+                .unwrap_or(0);
+            state.path = path.clone();
+            self.record(
+                &state,
+                format!("BFS found augmenting path {} with bottleneck {}", Self::format_path(&path), bottleneck),
+            );
+
+            for pair in path.windows(2) {
+                *state.flow.entry((pair[0], pair[1])).or_insert(0) += bottleneck;
+                *state.flow.entry((pair[1], pair[0])).or_insert(0) -= bottleneck;
+            }
+            state.total += bottleneck;
+            self.record(&state, format!("Augmented by {}, total flow is now {}", bottleneck, state.total));
+        }
+    }
+
+    fn record(&mut self, state: &Snapshot, message: String) {
+        let mut snapshot = state.clone();
+        snapshot.message = message;
+        self.snapshots.push(snapshot);
+    }
+
+    fn current_snapshot(&self) -> Option<&Snapshot> {
+        self.current_step.checked_sub(1).and_then(|i| self.snapshots.get(i))
+    }
+}
+
+impl Algorithm for MaxFlowVisualizer {
+    fn initialize(&mut self) {
+        self.graph = Self::example_graph();
+        self.generate_steps();
+    }
+
+    fn step(&mut self) {
+        if self.current_step < self.snapshots.len() {
+            self.current_step += 1;
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Source:");
+            changed |= ui.add(egui::DragValue::new(&mut self.source).speed(0.1)).changed();
+            ui.label("Sink:");
+            changed |= ui.add(egui::DragValue::new(&mut self.sink).speed(0.1)).changed();
+            if ui.button("Example Network").clicked() {
+                self.graph = Self::example_graph();
+                changed = true;
+            }
+            ui.checkbox(&mut self.show_residual, "Show residual graph");
+        });
+        changed |= graph::edge_editor(ui, &mut self.graph, &mut self.edge_input);
+        if changed {
+            self.generate_steps();
+        }
+
+        let snapshot = self.current_snapshot().cloned().unwrap_or_default();
+        ui.label(format!("Step {}/{}", self.current_step, self.snapshots.len()));
+        ui.label(&snapshot.message);
+        ui.label(format!("Total flow: {}", snapshot.total));
+
+        let on_path = |from: usize, to: usize| snapshot.path.windows(2).any(|pair| pair == [from, to]);
+        let (source, sink) = (self.source, self.sink);
+        let cut_done = !snapshot.cut.is_empty();
+        let theme = Theme::current(ui.ctx());
+        let node_color = Box::new(|node: usize| {
+            theme.color(if node == source {
+                Role::Visited
+            } else if node == sink {
+                Role::Swapped
+            } else if cut_done && snapshot.reached.contains(&node) {
+                Role::Frontier
+            } else if snapshot.path.contains(&node) {
+                Role::Current
+            } else {
+                Role::Unvisited
+            })
+        });
+
+        if self.show_residual {
+            let residual = self.residual_graph(&snapshot.flow);
+            graph::draw_graph(
+                ui,
+                &residual,
+                GraphStyle {
+                    node_color,
+                    edge_color: Box::new(|from, to| {
+                        if on_path(from, to) {
+                            theme.color(Role::Current)
+                        } else if self.capacity(from, to) == 0 {
+                            theme.color(Role::Rejected)
+                        } else {
+                            theme.line()
+                        }
+                    }),
+                    edge_label: Box::new(|_, _, remaining| Some(remaining.to_string())),
+                    ..Default::default()
+                },
+            );
+            ui.label("Reverse edges let a later path cancel flow.");
+        } else {
+            graph::draw_graph(
+                ui,
+                &self.graph,
+                GraphStyle {
+                    node_color,
+                    edge_color: Box::new(|from, to| {
+                        if snapshot.cut.contains(&(from, to)) {
+                            theme.color(Role::Rejected)
+                        } else if on_path(from, to) {
+                            theme.color(Role::Current)
+                        } else if snapshot.flow.get(&(from, to)).copied().unwrap_or(0) > 0 {
+                            theme.color(Role::Solution)
+                        } else {
+                            theme.line()
+                        }
+                    }),
+                    edge_label: Box::new(|from, to, capacity| {
+                        let flow = snapshot.flow.get(&(from, to)).copied().unwrap_or(0).max(0);
+                        Some(format!("{}/{}", flow, capacity))
+                    }),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.current_step >= self.snapshots.len()
+    }
+
+    fn reset(&mut self) {
+        self.current_step = 0;
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            graph: self.graph.clone(),
+            source: self.source,
+            sink: self.sink,
+            show_residual: self.show_residual,
+        })
+    }
+
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        self.graph = inputs.graph;
+        self.source = inputs.source;
+        self.sink = inputs.sink;
+        self.show_residual = inputs.show_residual;
+        self.generate_steps();
+        Ok(())
+    }
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        let mut entries = vec![
+            (Role::Visited, "Source"),
+            (Role::Swapped, "Sink"),
+            (Role::Current, "Augmenting path"),
+            (Role::Frontier, "Source side of the cut"),
+        ];
+        if self.show_residual {
+            entries.push((Role::Rejected, "Reverse edge"));
+        } else {
+            entries.push((Role::Solution, "Carries flow"));
+            entries.push((Role::Rejected, "Minimum cut"));
+        }
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last(graph: Graph, source: usize, sink: usize) -> (MaxFlowVisualizer, Snapshot) {
+        let mut visualizer = MaxFlowVisualizer { graph, source, sink, ..MaxFlowVisualizer::new() };
+        visualizer.generate_steps();
+        let snapshot = visualizer.snapshots.last().unwrap().clone();
+        (visualizer, snapshot)
+    }
+
+    #[test]
+    fn the_textbook_network_carries_23_across_its_min_cut() {
+        let (network, flow) = last(MaxFlowVisualizer::example_graph(), 0, 5);
+        assert_eq!(flow.total, 23);
+        assert_eq!(flow.cut, vec![(1, 3), (4, 3), (4, 5)]);
+        let cut: i64 = flow.cut.iter().map(|&(from, to)| network.capacity(from, to)).sum();
+        assert_eq!(cut, flow.total);
+
+        // Every edge stays within its capacity, and every inner node passes on what it gets.
+        for (&(from, to), &amount) in &flow.flow {
+            assert!(amount <= network.capacity(from, to), "{} -> {} carries {}", from, to, amount);
+        }
+        for node in 1..5 {
+            let net: i64 = flow.flow.iter().filter(|&(&(from, _), _)| from == node).map(|(_, &amount)| amount).sum();
+            assert_eq!(net, 0, "flow piles up at {}", node);
+        }
+    }
+
+    #[test]
+    fn an_unreachable_sink_gets_no_flow() {
+        let mut graph = Graph::new();
+        graph::add_edge(&mut graph, 0, 1, 5);
+        graph::add_edge(&mut graph, 2, 3, 5);
+        let (_, flow) = last(graph, 0, 3);
+        // Nothing leaves the source's side, so the cut is empty and costs nothing.
+        assert_eq!(flow.total, 0);
+        assert_eq!(flow.reached, HashSet::from([0, 1]));
+        assert!(flow.cut.is_empty());
+    }
+}
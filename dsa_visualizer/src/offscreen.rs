@@ -0,0 +1,197 @@
+use eframe::egui;
+use egui::epaint::{ClippedPrimitive, ImageData, Mesh, Primitive, Vertex};
+use egui::{Color32, Pos2, Rect, TextureId};
+use std::collections::HashMap;
+
+/// Texture pixels as egui uploads them: premultiplied sRGBA.
+struct Texture {
+    size: [usize; 2],
+    pixels: Vec<Color32>,
+}
+
+impl Texture {
+    fn texel(&self, x: isize, y: isize) -> [f32; 4] {
+        let x = x.clamp(0, self.size[0] as isize - 1) as usize;
+        let y = y.clamp(0, self.size[1] as isize - 1) as usize;
+        let c = self.pixels[y * self.size[0] + x];
+        [c.r() as f32, c.g() as f32, c.b() as f32, c.a() as f32].map(|v| v / 255.0)
+    }
+
+    /// Bilinear sample at normalized coordinates.
+    fn sample(&self, uv: Pos2) -> [f32; 4] {
+        let x = uv.x * self.size[0] as f32 - 0.5;
+        let y = uv.y * self.size[1] as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+
+        let top = lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), fx);
+        let bottom = lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), fx);
+        lerp(top, bottom, fy)
+    }
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn color(c: Color32) -> [f32; 4] {
+    [c.r(), c.g(), c.b(), c.a()].map(|v| v as f32 / 255.0)
+}
+
+/// Twice the signed area of the triangle (a, b, p).
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Premultiplied RGBA pixels that triangles are blended onto.
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, background: Color32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color(background); width * height],
+        }
+    }
+
+    fn draw_mesh(&mut self, mesh: &Mesh, clip: Rect, texture: &Texture) {
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| &mesh.vertices[triangle[i] as usize]);
+            self.draw_triangle(a, b, c, clip, texture);
+        }
+    }
+
+    fn draw_triangle(&mut self, a: &Vertex, b: &Vertex, c: &Vertex, clip: Rect, texture: &Texture) {
+        let area = edge(a.pos, b.pos, c.pos);
+        if area.abs() < f32::EPSILON {
+            return;
+        }
+
+        let bounds = Rect::from_points(&[a.pos, b.pos, c.pos])
+            .intersect(clip)
+            .intersect(Rect::from_min_size(Pos2::ZERO, egui::vec2(self.width as f32, self.height as f32)));
+        if !bounds.is_positive() {
+            return;
+        }
+
+        let [ca, cb, cc] = [a.color, b.color, c.color].map(color);
+        for y in bounds.min.y.floor() as usize..(bounds.max.y.ceil() as usize).min(self.height) {
+            for x in bounds.min.x.floor() as usize..(bounds.max.x.ceil() as usize).min(self.width) {
+                let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let wa = edge(b.pos, c.pos, p) / area;
+                let wb = edge(c.pos, a.pos, p) / area;
+                let wc = 1.0 - wa - wb;
+                if wa < 0.0 || wb < 0.0 || wc < 0.0 || !clip.contains(p) {
+                    continue;
+                }
+
+                let uv = Pos2::new(
+                    a.uv.x * wa + b.uv.x * wb + c.uv.x * wc,
+                    a.uv.y * wa + b.uv.y * wb + c.uv.y * wc,
+                );
+                let texel = texture.sample(uv);
+                let src: [f32; 4] = [0, 1, 2, 3].map(|i| (ca[i] * wa + cb[i] * wb + cc[i] * wc) * texel[i]);
+
+                // Premultiplied "over", blended in gamma space like egui's own backends.
+                let dst = &mut self.pixels[y * self.width + x];
+                *dst = [0, 1, 2, 3].map(|i| src[i] + dst[i] * (1.0 - src[3]));
+            }
+        }
+    }
+
+    /// Straight (not premultiplied) RGBA bytes, row by row.
+    fn into_rgba(self) -> Vec<u8> {
+        self.pixels
+            .into_iter()
+            .flat_map(|[r, g, b, a]| {
+                let unpremultiply = if a > 0.0 { 1.0 / a } else { 0.0 };
+                [r * unpremultiply, g * unpremultiply, b * unpremultiply, a].map(|v| (v * 255.0).round().clamp(0.0, 255.0) as u8)
+            })
+            .collect()
+    }
+}
+
+/// Runs egui without a window and rasterizes its output on the CPU, so frames can be
+/// produced with no GPU or display.
+pub struct OffscreenRenderer {
+    ctx: egui::Context,
+    width: u32,
+    height: u32,
+    textures: HashMap<TextureId, Texture>,
+}
+
+impl OffscreenRenderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            ctx: egui::Context::default(),
+            width,
+            height,
+            textures: HashMap::new(),
+        }
+    }
+
+    pub fn context(&self) -> &egui::Context {
+        &self.ctx
+    }
+
+    /// Lays out one frame with `add_contents` inside a central panel and returns its
+    /// pixels as straight RGBA bytes.
+    pub fn render(&mut self, mut add_contents: impl FnMut(&mut egui::Ui)) -> Vec<u8> {
+        let input = egui::RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, egui::vec2(self.width as f32, self.height as f32))),
+            ..Default::default()
+        };
+        let output = self.ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| add_contents(ui));
+        });
+
+        self.update_textures(&output.textures_delta);
+        let primitives = self.ctx.tessellate(output.shapes, output.pixels_per_point);
+
+        let background = self.ctx.style().visuals.panel_fill;
+        let mut canvas = Canvas::new(self.width as usize, self.height as usize, background);
+        for ClippedPrimitive { clip_rect, primitive } in &primitives {
+            if let Primitive::Mesh(mesh) = primitive {
+                if let Some(texture) = self.textures.get(&mesh.texture_id) {
+                    canvas.draw_mesh(mesh, *clip_rect, texture);
+                }
+            }
+        }
+
+        for id in &output.textures_delta.free {
+            self.textures.remove(id);
+        }
+        canvas.into_rgba()
+    }
+
+    fn update_textures(&mut self, delta: &egui::TexturesDelta) {
+        for (id, image_delta) in &delta.set {
+            let size = image_delta.image.size();
+            let pixels: Vec<Color32> = match &image_delta.image {
+                ImageData::Color(image) => image.pixels.clone(),
+                ImageData::Font(image) => image.srgba_pixels(None).collect(),
+            };
+
+            match image_delta.pos {
+                None => {
+                    self.textures.insert(*id, Texture { size, pixels });
+                }
+                Some([x, y]) => {
+                    let Some(texture) = self.textures.get_mut(id) else {
+                        continue;
+                    };
+                    for row in 0..size[1] {
+                        let start = (y + row) * texture.size[0] + x;
+                        texture.pixels[start..start + size[0]].copy_from_slice(&pixels[row * size[0]..(row + 1) * size[0]]);
+                    }
+                }
+            }
+        }
+    }
+}
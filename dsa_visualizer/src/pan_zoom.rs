@@ -0,0 +1,62 @@
+use eframe::egui;
+use egui::emath::TSTransform;
+use egui::{Pos2, Rect, Vec2};
+use std::hash::Hash;
+
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 4.0;
+
+/// Remembered per canvas between frames.
+#[derive(Clone, Copy, Default)]
+struct View {
+    transform: TSTransform,
+    /// Size the canvas took last frame, which is what gets clipped to.
+    size: Vec2,
+}
+
+/// Draws `add_contents` on a layer of its own that can be dragged to pan, zoomed with
+/// Ctrl+scroll or a pinch, and put back with a double click. The surrounding layout
+/// sees the canvas at its normal size, and clicks inside it are transformed too.
+pub fn show<R>(ui: &mut egui::Ui, id_salt: impl Hash, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> R {
+    let id = ui.make_persistent_id(id_salt);
+    let mut view: View = ui.data(|d| d.get_temp(id)).unwrap_or_default();
+
+    let available = ui.available_rect_before_wrap();
+    let visible = if view.size == Vec2::ZERO {
+        available
+    } else {
+        Rect::from_min_size(available.min, view.size)
+    };
+    let layer = egui::LayerId::new(ui.layer_id().order, id);
+    let mut canvas = ui.new_child(egui::UiBuilder::new().layer_id(layer).max_rect(available));
+    canvas.set_clip_rect(view.transform.inverse() * visible.intersect(ui.clip_rect()));
+    let inner = add_contents(&mut canvas);
+    let used = canvas.min_rect();
+
+    // Widgets on the canvas only sense hovers and clicks, so drags fall through to here.
+    let response = ui.allocate_rect(used, egui::Sense::click_and_drag());
+    if response.dragged() {
+        view.transform.translation += response.drag_delta();
+    }
+    if response.double_clicked() {
+        view.transform = TSTransform::IDENTITY;
+    }
+    let pointer = ui.input(|i| i.pointer.hover_pos()).filter(|&p| used.contains(p));
+    let zoom = ui.input(|i| i.zoom_delta());
+    if let (Some(pointer), true) = (pointer, zoom != 1.0) {
+        let scaling = (view.transform.scaling * zoom).clamp(MIN_ZOOM, MAX_ZOOM);
+        view.transform = zoom_about(view.transform, pointer, scaling / view.transform.scaling);
+    }
+
+    view.size = used.size();
+    ui.ctx().set_sublayer(ui.layer_id(), layer);
+    ui.ctx().set_transform_layer(layer, view.transform);
+    ui.data_mut(|d| d.insert_temp(id, view));
+    inner
+}
+
+/// Scales `transform` by `factor` while keeping the point under `pointer` in place.
+fn zoom_about(transform: TSTransform, pointer: Pos2, factor: f32) -> TSTransform {
+    let anchor = (transform.inverse() * pointer).to_vec2();
+    transform * TSTransform::from_translation(anchor) * TSTransform::from_scaling(factor) * TSTransform::from_translation(-anchor)
+}
@@ -0,0 +1,277 @@
+use crate::algorithm::Algorithm;
+use crate::graph::{self, EdgeInput, Graph, GraphStyle};
+use crate::session;
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+#[derive(Clone, Default)]
+struct Snapshot {
+    in_tree: HashSet<usize>,
+    tree_edges: Vec<(usize, usize, usize)>,
+    frontier: Vec<(usize, usize, usize)>,
+    considered: Option<(usize, usize)>,
+    total: usize,
+    message: String,
+}
+
+/// What a session keeps: the edited graph and the vertex the tree grows from.
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    graph: Graph,
+    start: usize,
+}
+
+/// Prim's minimum spanning tree, grown from one vertex by always taking the
+/// cheapest edge leaving the tree. The graph stores every edge in both directions.
+pub struct PrimVisualizer {
+    graph: Graph,
+    start: usize,
+    snapshots: Vec<Snapshot>,
+    current_step: usize,
+    edge_input: EdgeInput,
+}
+
+impl PrimVisualizer {
+    pub fn new() -> Self {
+        Self {
+            graph: Graph::new(),
+            start: 0,
+            snapshots: Vec::new(),
+            current_step: 0,
+            edge_input: EdgeInput::new(true, true),
+        }
+    }
+
+    fn example_graph() -> Graph {
+        let mut graph = Graph::new();
+        let edges = [
+            (0, 1, 4), (0, 7, 8), (1, 2, 8), (1, 7, 11), (2, 3, 7), (2, 8, 2), (2, 5, 4),
+            (3, 4, 9), (3, 5, 14), (4, 5, 10), (5, 6, 2), (6, 7, 1), (6, 8, 6), (7, 8, 7),
+        ];
+        for (a, b, weight) in edges {
+            graph::add_edge(&mut graph, a, b, weight);
+            graph::add_edge(&mut graph, b, a, weight);
+        }
+        graph
+    }
+
+    fn record(&mut self, state: &Snapshot, heap: &BinaryHeap<Reverse<(usize, usize, usize)>>, message: String) {
+        let mut snapshot = state.clone();
+        let mut frontier: Vec<(usize, usize, usize)> = heap.iter().map(|&Reverse(edge)| edge).collect();
+        frontier.sort_unstable();
+        snapshot.frontier = frontier;
+        snapshot.message = message;
+        self.snapshots.push(snapshot);
+    }
+
+    fn push_edges(&self, node: usize, state: &Snapshot, heap: &mut BinaryHeap<Reverse<(usize, usize, usize)>>) {
+        for &(neighbor, weight) in self.graph.get(&node).into_iter().flatten() {
+            if !state.in_tree.contains(&neighbor) {
+                heap.push(Reverse((weight, node, neighbor)));
+            }
+        }
+    }
+
+    fn generate_steps(&mut self) {
+        self.snapshots.clear();
+        self.current_step = 0;
+        if !self.graph.contains_key(&self.start) {
+            self.snapshots.push(Snapshot {
+                message: format!("Start vertex {} is not in the graph", self.start),
+                ..Default::default()
+            });
+            return;
+        }
+
+        let mut state = Snapshot::default();
+        let mut heap = BinaryHeap::new();
+        state.in_tree.insert(self.start);
+        self.push_edges(self.start, &state, &mut heap);
+        self.record(&state, &heap, format!("Start the tree at {} and push its edges onto the heap", self.start));
+
+        while let Some(Reverse((weight, from, to))) = heap.pop() {
+            state.considered = Some((from, to));
+            if state.in_tree.contains(&to) {
+                self.record(
+                    &state,
+                    &heap,
+                    format!("Pop {}-{} ({}): both ends are already in the tree, skip it", from, to, weight),
+                );
+                continue;
+            }
+
+            state.in_tree.insert(to);
+            state.tree_edges.push((from, to, weight));
+            state.total += weight;
+            self.push_edges(to, &state, &mut heap);
+            self.record(
+                &state,
+                &heap,
+                format!("Pop {}-{} ({}): cheapest edge leaving the tree, add {}", from, to, weight, to),
+            );
+        }
+
+        state.considered = None;
+        let total_nodes = graph::nodes(&self.graph).len();
+        let message = if state.in_tree.len() < total_nodes {
+            format!(
+                "Heap is empty but only {} of {} vertices are reachable, the graph is disconnected. Tree weight {}",
+                state.in_tree.len(),
+                total_nodes,
+                state.total
+            )
+        } else {
+            format!("Minimum spanning tree complete with total weight {}", state.total)
+        };
+        self.record(&state, &heap, message);
+    }
+
+    fn current_snapshot(&self) -> Option<&Snapshot> {
+        self.current_step.checked_sub(1).and_then(|i| self.snapshots.get(i))
+    }
+}
+
+impl Algorithm for PrimVisualizer {
+    fn initialize(&mut self) {
+        self.graph = Self::example_graph();
+        self.generate_steps();
+    }
+
+    fn step(&mut self) {
+        if self.current_step < self.snapshots.len() {
+            self.current_step += 1;
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Start vertex:");
+            changed |= ui.add(egui::DragValue::new(&mut self.start).speed(0.1)).changed();
+            if ui.button("Example Graph").clicked() {
+                self.graph = Self::example_graph();
+                changed = true;
+            }
+        });
+        changed |= graph::edge_editor(ui, &mut self.graph, &mut self.edge_input);
+        if changed {
+            self.generate_steps();
+        }
+
+        let snapshot = self.current_snapshot().cloned().unwrap_or_default();
+        ui.label(format!("Step {}/{}", self.current_step, self.snapshots.len()));
+        ui.label(&snapshot.message);
+        let heap: Vec<String> = snapshot
+            .frontier
+            .iter()
+            .map(|(weight, from, to)| format!("{}-{} ({})", from, to, weight))
+            .collect();
+        ui.label(format!("Frontier heap: [{}]", heap.join(", ")));
+        ui.label(format!("Tree weight: {}", snapshot.total));
+
+        let in_mst = |a: usize, b: usize| {
+            snapshot
+                .tree_edges
+                .iter()
+                .any(|&(from, to, _)| (from, to) == (a, b) || (from, to) == (b, a))
+        };
+        let theme = Theme::current(ui.ctx());
+        let same = |edge: Option<(usize, usize)>, a: usize, b: usize| edge == Some((a, b)) || edge == Some((b, a));
+        graph::draw_graph(
+            ui,
+            &self.graph,
+            GraphStyle {
+                directed: false,
+                node_color: Box::new(|node| {
+                    if snapshot.in_tree.contains(&node) {
+                        theme.color(Role::Visited)
+                    } else {
+                        theme.color(Role::Unvisited)
+                    }
+                }),
+                edge_color: Box::new(|from, to| {
+                    if same(snapshot.considered, from, to) {
+                        theme.color(Role::Current)
+                    } else if in_mst(from, to) {
+                        theme.color(Role::Solution)
+                    } else if snapshot.frontier.iter().any(|&(_, a, b)| same(Some((a, b)), from, to)) {
+                        theme.color(Role::Frontier)
+                    } else {
+                        theme.line()
+                    }
+                }),
+                edge_label: Box::new(|_, _, weight| Some(weight.to_string())),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn is_finished(&self) -> bool {
+        self.current_step >= self.snapshots.len()
+    }
+
+    fn reset(&mut self) {
+        self.current_step = 0;
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            graph: self.graph.clone(),
+            start: self.start,
+        })
+    }
+
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        self.graph = inputs.graph;
+        self.start = inputs.start;
+        self.generate_steps();
+        Ok(())
+    }
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![
+            (Role::Visited, "In the tree"),
+            (Role::Frontier, "Edge in the heap"),
+            (Role::Current, "Edge considered"),
+            (Role::Solution, "Tree edge"),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last(graph: Graph, start: usize) -> Snapshot {
+        let mut visualizer = PrimVisualizer { graph, start, ..PrimVisualizer::new() };
+        visualizer.generate_steps();
+        visualizer.snapshots.pop().unwrap()
+    }
+
+    #[test]
+    fn the_textbook_graph_has_a_tree_of_weight_37_from_any_start() {
+        for start in 0..9 {
+            let tree = last(PrimVisualizer::example_graph(), start);
+            assert_eq!(tree.total, 37);
+            assert_eq!(tree.tree_edges.len(), 8);
+            assert_eq!(tree.in_tree.len(), 9);
+            assert_eq!(tree.tree_edges.iter().map(|&(_, _, weight)| weight).sum::<usize>(), 37);
+        }
+    }
+
+    #[test]
+    fn a_disconnected_graph_spans_only_the_start_component() {
+        let mut graph = Graph::new();
+        for (a, b, weight) in [(0, 1, 3), (1, 2, 1), (0, 2, 5), (3, 4, 2)] {
+            graph::add_edge(&mut graph, a, b, weight);
+            graph::add_edge(&mut graph, b, a, weight);
+        }
+        let tree = last(graph, 0);
+        assert_eq!((tree.total, tree.in_tree.len()), (4, 3));
+        assert!(tree.message.contains("disconnected"));
+    }
+}
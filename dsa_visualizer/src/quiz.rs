@@ -0,0 +1,197 @@
+use crate::algorithm::{Action, Lifecycle, Runner};
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use std::collections::BTreeMap;
+
+/// Steps skipped in one frame while looking for something to ask, so an algorithm
+/// whose `peek` never answers can't hang the UI.
+const MAX_SKIPPED_STEPS: usize = 10_000;
+/// How many past answers the summary lists.
+const HISTORY_SHOWN: usize = 5;
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Tally {
+    pub asked: usize,
+    pub correct: usize,
+}
+
+impl Tally {
+    fn record(&mut self, correct: bool) {
+        self.asked += 1;
+        if correct {
+            self.correct += 1;
+        }
+    }
+
+    fn percent(&self) -> f32 {
+        if self.asked == 0 {
+            0.0
+        } else {
+            self.correct as f32 * 100.0 / self.asked as f32
+        }
+    }
+}
+
+pub struct Answer {
+    pub algorithm: &'static str,
+    pub expected: Action,
+    pub picks: Vec<usize>,
+    pub correct: bool,
+}
+
+/// Predictions scored since the app was opened.
+#[derive(Default)]
+pub struct Score {
+    pub total: Tally,
+    pub streak: usize,
+    pub best_streak: usize,
+    pub per_algorithm: BTreeMap<&'static str, Tally>,
+    pub history: Vec<Answer>,
+}
+
+impl Score {
+    fn record(&mut self, answer: Answer) {
+        self.total.record(answer.correct);
+        self.per_algorithm.entry(answer.algorithm).or_default().record(answer.correct);
+        if answer.correct {
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+        } else {
+            self.streak = 0;
+        }
+        self.history.push(answer);
+    }
+
+    /// Totals, per-algorithm accuracy and the last few answers.
+    pub fn summary(&self, ui: &mut egui::Ui) {
+        if self.total.asked == 0 {
+            ui.weak("No predictions yet this session.");
+            return;
+        }
+        ui.label(format!(
+            "{} of {} correct ({:.0}%), streak {}, best streak {}",
+            self.total.correct,
+            self.total.asked,
+            self.total.percent(),
+            self.streak,
+            self.best_streak
+        ));
+        egui::Grid::new("quiz_score").striped(true).show(ui, |ui| {
+            ui.strong("Algorithm");
+            ui.strong("Correct");
+            ui.strong("Accuracy");
+            ui.end_row();
+            for (name, tally) in &self.per_algorithm {
+                ui.label(*name);
+                ui.label(format!("{} / {}", tally.correct, tally.asked));
+                ui.label(format!("{:.0}%", tally.percent()));
+                ui.end_row();
+            }
+        });
+        for answer in self.history.iter().rev().take(HISTORY_SHOWN) {
+            let verdict = if answer.correct { "right" } else { "wrong" };
+            ui.weak(format!("{}: picked {:?}, it was {} ({})", answer.algorithm, answer.picks, answer.expected, verdict));
+        }
+    }
+}
+
+/// Asks the user to predict each step before it's taken. The runner is held paused and
+/// only stepped here, once the prediction has been graded.
+#[derive(Default)]
+pub struct Quiz {
+    pub enabled: bool,
+    question: Option<Action>,
+    picks: Vec<usize>,
+    feedback: Option<(bool, String)>,
+    pub score: Score,
+}
+
+impl Quiz {
+    /// Called once per frame after the algorithm has rendered, so its clicks are in.
+    pub fn update(&mut self, runner: &mut Runner, algorithm: &'static str) {
+        if !self.enabled {
+            return;
+        }
+        runner.pause();
+
+        // Steps with nothing to predict, like a sort's comparisons, run straight through.
+        let mut skipped = 0;
+        let mut question = runner.algorithm().peek();
+        while question.is_none() && runner.can_step() && skipped < MAX_SKIPPED_STEPS {
+            runner.step();
+            skipped += 1;
+            question = runner.algorithm().peek();
+        }
+
+        // A reset or new input changes the question under the user, start over.
+        if question != self.question {
+            self.question = question;
+            self.picks.clear();
+        }
+        let Some(expected) = question else {
+            return;
+        };
+
+        if let Some(pick) = runner.algorithm().take_click() {
+            // Clicking a pick again takes it back.
+            match self.picks.iter().position(|&p| p == pick) {
+                Some(index) => {
+                    self.picks.remove(index);
+                }
+                None => self.picks.push(pick),
+            }
+        }
+        if self.picks.len() < expected.picks_needed() {
+            return;
+        }
+
+        let picks = std::mem::take(&mut self.picks);
+        let correct = expected.matches(&picks);
+        self.feedback = Some((
+            correct,
+            if correct {
+                format!("Correct, the next step does {}", expected)
+            } else {
+                format!("Not quite, you picked {:?} but the next step does {}", picks, expected)
+            },
+        ));
+        self.score.record(Answer { algorithm, expected, picks, correct });
+        self.question = None;
+        runner.step();
+    }
+
+    /// Toggle, current question and score, shown under the playback controls.
+    pub fn panel(&mut self, ui: &mut egui::Ui, runner: &Runner) {
+        egui::CollapsingHeader::new("Quiz").default_open(self.enabled).show(ui, |ui| {
+            if ui.checkbox(&mut self.enabled, "Predict each step before it happens").changed() {
+                self.question = None;
+                self.picks.clear();
+                self.feedback = None;
+            }
+            if !self.enabled {
+                return;
+            }
+
+            match self.question {
+                Some(action) => {
+                    ui.strong(action.question());
+                    if !self.picks.is_empty() {
+                        ui.label(format!("Picked so far: {:?}", self.picks));
+                    }
+                }
+                None if runner.state() == Lifecycle::Finished => {
+                    ui.label("The algorithm has finished, reset it to play again.");
+                }
+                None => {
+                    ui.label("This visualizer has no steps to predict.");
+                }
+            }
+            if let Some((correct, message)) = &self.feedback {
+                let color = Theme::current(ui.ctx()).color(if *correct { Role::Solution } else { Role::Rejected });
+                ui.colored_label(color, message);
+            }
+            ui.separator();
+            self.score.summary(ui);
+        });
+    }
+}
@@ -0,0 +1,44 @@
+use crate::sorting::{Recorder, SortKind, SortVisualizer};
+use serde::{Deserialize, Serialize};
+
+/// Least significant digit first radix sort in base 10. Each pass distributes the
+/// array into ten buckets by one digit and collects them back in order; it only works
+/// because every pass is stable.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Radix;
+
+pub type RadixSortVisualizer = SortVisualizer<Radix>;
+
+impl SortKind for Radix {
+    const NAME: &'static str = "LSD radix sort";
+    const STABLE: bool = true;
+
+    fn sort(&self, rec: &mut Recorder) {
+        let n = rec.len();
+        // Keys are shifted so the smallest is 0; radix digits need non-negative values.
+        let min = (0..n).map(|i| rec.key(i)).min().unwrap_or(0);
+        let max = (0..n).map(|i| rec.key(i) - min).max().unwrap_or(0);
+
+        let mut place = 1;
+        loop {
+            let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); 10];
+            for i in 0..n {
+                let digit = (((rec.key(i) - min) / place) % 10) as usize;
+                rec.put_in_bucket(digit, i);
+                buckets[digit].push(i);
+            }
+
+            let order: Vec<_> = buckets.iter().flatten().map(|&i| rec.items[i]).collect();
+            rec.mark([], format!("Collect the buckets in order for digit place {}", place));
+            for (i, item) in order.into_iter().enumerate() {
+                rec.write(i, item);
+            }
+            rec.clear_buckets();
+
+            if max / place < 10 {
+                break;
+            }
+            place *= 10;
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use crate::binary_tree::{bst_insert, search, Frames, NodeStyle, Tree, TreeKind, TreeVisualizer};
+use crate::theme::{Role, Theme};
+
+/// Red-black tree following the recoloring and rotation cases from CLRS.
+pub struct RedBlack;
+
+pub type RedBlackVisualizer = TreeVisualizer<RedBlack>;
+
+impl RedBlack {
+    fn set_red(tree: &mut Tree, i: Option<usize>, red: bool) {
+        if let Some(i) = i {
+            tree.node_mut(i).red = red;
+        }
+    }
+
+    fn side_name(left: bool) -> &'static str {
+        if left { "left" } else { "right" }
+    }
+
+    fn insert_fixup(tree: &mut Tree, mut z: usize, frames: &mut Frames) {
+        while let Some(p) = tree.parent(z).filter(|&p| tree.node(p).red) {
+            // A red parent is never the root, so the grandparent exists.
+            let g = tree.parent(p).expect("red node has a parent");
+            let parent_is_left = tree.node(g).left == Some(p);
+            let uncle = tree.child(g, !parent_is_left);
+            let (z_key, p_key, g_key) = (tree.key(z), tree.key(p), tree.key(g));
+
+            if tree.is_red(uncle) {
+                let u_key = tree.key(uncle.unwrap());
+                frames.record(
+                    tree,
+                    &[z_key, p_key, u_key],
+                    format!("Case 1: parent {} and uncle {} are red, recolor", p_key, u_key),
+                );
+                Self::set_red(tree, Some(p), false);
+                Self::set_red(tree, uncle, false);
+                Self::set_red(tree, Some(g), true);
+                frames.record(tree, &[g_key], format!("Recolored, continue from grandparent {}", g_key));
+                z = g;
+                continue;
+            }
+
+            let mut p = p;
+            if tree.child(p, !parent_is_left) == Some(z) {
+                frames.record(
+                    tree,
+                    &[z_key, p_key],
+                    format!(
+                        "Case 2: {} is an inner child, rotate {} at {}",
+                        z_key,
+                        Self::side_name(parent_is_left),
+                        p_key
+                    ),
+                );
+                tree.rotate(p, parent_is_left);
+                z = p;
+                p = tree.parent(z).expect("rotated node has a parent");
+                frames.record(tree, &[tree.key(z), tree.key(p)], "Rotated into an outer child");
+            }
+
+            frames.record(
+                tree,
+                &[tree.key(z), tree.key(p), g_key],
+                format!(
+                    "Case 3: {} is an outer child, recolor and rotate {} at {}",
+                    tree.key(z),
+                    Self::side_name(!parent_is_left),
+                    g_key
+                ),
+            );
+            Self::set_red(tree, Some(p), false);
+            Self::set_red(tree, Some(g), true);
+            tree.rotate(g, !parent_is_left);
+            frames.record(tree, &[tree.key(p)], format!("{} is now the subtree root", tree.key(p)));
+        }
+
+        if let Some(root) = tree.root.filter(|&r| tree.node(r).red) {
+            Self::set_red(tree, Some(root), false);
+            frames.record(tree, &[tree.key(root)], "Root is recolored black");
+        }
+    }
+
+    /// `x` may be a nil leaf, so its parent is tracked separately.
+    fn delete_fixup(tree: &mut Tree, mut x: Option<usize>, mut x_parent: Option<usize>, frames: &mut Frames) {
+        while x != tree.root && !tree.is_red(x) {
+            let p = x_parent.expect("non-root node has a parent");
+            let x_is_left = tree.node(p).left == x;
+            let mut w = tree.child(p, !x_is_left).expect("double black node has a sibling");
+            let p_key = tree.key(p);
+
+            if tree.node(w).red {
+                frames.record(
+                    tree,
+                    &[p_key, tree.key(w)],
+                    format!(
+                        "Case 1: sibling {} is red, recolor and rotate {} at {}",
+                        tree.key(w),
+                        Self::side_name(x_is_left),
+                        p_key
+                    ),
+                );
+                Self::set_red(tree, Some(w), false);
+                Self::set_red(tree, Some(p), true);
+                tree.rotate(p, x_is_left);
+                w = tree.child(p, !x_is_left).expect("double black node has a sibling");
+                frames.record(tree, &[p_key, tree.key(w)], "Sibling is now black");
+            }
+
+            let near = tree.child(w, x_is_left);
+            let far = tree.child(w, !x_is_left);
+            if !tree.is_red(near) && !tree.is_red(far) {
+                frames.record(
+                    tree,
+                    &[p_key, tree.key(w)],
+                    format!("Case 2: sibling {} has two black children, recolor it red", tree.key(w)),
+                );
+                Self::set_red(tree, Some(w), true);
+                x = Some(p);
+                x_parent = tree.parent(p);
+                frames.record(tree, &[p_key], format!("Move the extra black up to {}", p_key));
+                continue;
+            }
+
+            if !tree.is_red(far) {
+                frames.record(
+                    tree,
+                    &[tree.key(w), tree.key(near.unwrap())],
+                    format!(
+                        "Case 3: sibling's far child is black, rotate {} at {}",
+                        Self::side_name(!x_is_left),
+                        tree.key(w)
+                    ),
+                );
+                Self::set_red(tree, near, false);
+                Self::set_red(tree, Some(w), true);
+                tree.rotate(w, !x_is_left);
+                w = tree.child(p, !x_is_left).expect("double black node has a sibling");
+                frames.record(tree, &[tree.key(w)], "Sibling's far child is now red");
+            }
+
+            frames.record(
+                tree,
+                &[p_key, tree.key(w)],
+                format!(
+                    "Case 4: sibling's far child is red, recolor and rotate {} at {}",
+                    Self::side_name(x_is_left),
+                    p_key
+                ),
+            );
+            let parent_red = tree.node(p).red;
+            Self::set_red(tree, Some(w), parent_red);
+            Self::set_red(tree, Some(p), false);
+            Self::set_red(tree, tree.child(w, !x_is_left), false);
+            tree.rotate(p, x_is_left);
+            frames.record(tree, &[tree.key(w)], "Extra black removed");
+            x = tree.root;
+            x_parent = None;
+        }
+
+        if let Some(x) = x.filter(|&x| tree.node(x).red) {
+            Self::set_red(tree, Some(x), false);
+            frames.record(tree, &[tree.key(x)], format!("Recolor {} black", tree.key(x)));
+        }
+    }
+}
+
+impl TreeKind for RedBlack {
+    const NAME: &'static str = "Red-Black Tree";
+
+    fn insert(tree: &mut Tree, key: i32, frames: &mut Frames) {
+        if let Some(z) = bst_insert(tree, key, frames) {
+            Self::insert_fixup(tree, z, frames);
+            frames.record(tree, &[key], format!("{} inserted, all red-black properties hold", key));
+        }
+    }
+
+    fn delete(tree: &mut Tree, key: i32, frames: &mut Frames) {
+        let Some(z) = search(tree, key, frames) else {
+            return;
+        };
+
+        let node = tree.node(z).clone();
+        let mut removed_red = node.red;
+        let x;
+        let x_parent;
+
+        match (node.left, node.right) {
+            (None, right) => {
+                x = right;
+                x_parent = node.parent;
+                tree.transplant(z, right);
+            }
+            (left, None) => {
+                x = left;
+                x_parent = node.parent;
+                tree.transplant(z, left);
+            }
+            (Some(left), Some(right)) => {
+                let y = tree.minimum(right);
+                frames.record(
+                    tree,
+                    &[key, tree.key(y)],
+                    format!("{} has two children, its successor {} takes its place", key, tree.key(y)),
+                );
+                removed_red = tree.node(y).red;
+                x = tree.node(y).right;
+                if tree.parent(y) == Some(z) {
+                    x_parent = Some(y);
+                } else {
+                    x_parent = tree.parent(y);
+                    tree.transplant(y, x);
+                    tree.node_mut(y).right = Some(right);
+                    tree.node_mut(right).parent = Some(y);
+                }
+                tree.transplant(z, Some(y));
+                tree.node_mut(y).left = Some(left);
+                tree.node_mut(left).parent = Some(y);
+                tree.node_mut(y).red = node.red;
+            }
+        }
+        tree.free(z);
+
+        if removed_red {
+            frames.record(tree, &[], format!("Removed {}, a red node was unlinked so nothing to fix", key));
+        } else {
+            frames.record(tree, &[], format!("Removed {}, a black node was unlinked so fix the black height", key));
+            Self::delete_fixup(tree, x, x_parent, frames);
+        }
+        frames.record(tree, &[], format!("{} deleted, all red-black properties hold", key));
+    }
+
+    /// Red nodes take the palette's red-ish role and black ones the neutral fill, with
+    /// the color also written under the node for palettes where they are hard to tell apart.
+    fn style(tree: &Tree, i: usize, theme: &Theme) -> NodeStyle {
+        let red = tree.node(i).red;
+        NodeStyle {
+            fill: theme.color(if red { Role::Swapped } else { Role::Unvisited }),
+            label: Some(if red { "red" } else { "black" }.to_string()),
+        }
+    }
+
+    fn legend() -> Vec<(Role, &'static str)> {
+        vec![(Role::Swapped, "Red node"), (Role::Unvisited, "Black node")]
+    }
+}
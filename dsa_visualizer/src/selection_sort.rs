@@ -0,0 +1,31 @@
+use crate::sorting::{Recorder, SortKind, SortVisualizer};
+use serde::{Deserialize, Serialize};
+
+/// Finds the smallest remaining value and swaps it to the front of the unsorted part.
+/// The long-distance swap is what makes it unstable.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Selection;
+
+pub type SelectionSortVisualizer = SortVisualizer<Selection>;
+
+impl SortKind for Selection {
+    const NAME: &'static str = "Selection sort";
+    const STABLE: bool = false;
+
+    fn sort(&self, rec: &mut Recorder) {
+        let n = rec.len();
+        for i in 0..n.saturating_sub(1) {
+            let mut min = i;
+            for j in i + 1..n {
+                if rec.less(j, min) {
+                    min = j;
+                    rec.note(format!("New minimum {} at a[{}]", rec.key(j), j));
+                }
+            }
+            if min != i {
+                rec.swap(i, min);
+                rec.note(format!("Swap the minimum {} into a[{}]", rec.key(i), i));
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::algorithm::Runner;
+use crate::catalog::Registry;
+use crate::theme::Theme;
+use eframe::egui;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Bumped when a session file changes in a way older builds can't read.
+const FORMAT_VERSION: u32 = 1;
+/// Sessions kept in the recent list, newest first.
+const MAX_RECENT: usize = 8;
+
+/// One visualizer as the user left it: which one, what they typed or edited, and how
+/// far it had run. Saved between launches and exported to share a scenario.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    /// Registry name of the visualizer.
+    pub algorithm: String,
+    /// Whatever the visualizer's `save_inputs` returned.
+    pub inputs: serde_json::Value,
+    /// Steps taken when the session was saved, replayed when it is opened.
+    pub steps: usize,
+    pub seconds_per_step: f32,
+    /// Unix time the session was saved at.
+    pub saved_at: u64,
+}
+
+impl Session {
+    pub fn capture(algorithm: &str, runner: &mut Runner) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            algorithm: algorithm.to_string(),
+            inputs: runner.algorithm().save_inputs(),
+            steps: runner.steps(),
+            seconds_per_step: runner.step_interval.as_secs_f32(),
+            saved_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        }
+    }
+
+    /// Builds the visualizer, restores its inputs and steps it back to where it was.
+    /// Returns its registry index along with the runner.
+    pub fn open(&self, registry: &Registry) -> Result<(usize, Runner), String> {
+        if self.version > FORMAT_VERSION {
+            return Err(format!("session was saved by a newer version (format {})", self.version));
+        }
+        let index = registry
+            .position(&self.algorithm)
+            .ok_or_else(|| format!("no visualizer named \"{}\"", self.algorithm))?;
+        let mut algorithm = registry.get(index).expect("position returned a valid index").create();
+        algorithm.load_inputs(self.inputs.clone())?;
+
+        let mut runner = Runner::new(algorithm);
+        runner.step_interval = Duration::from_secs_f32(self.seconds_per_step.clamp(0.05, 3.0));
+        for _ in 0..self.steps {
+            if !runner.can_step() {
+                break;
+            }
+            runner.step();
+        }
+        Ok((index, runner))
+    }
+
+    pub fn export(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn import(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        serde_json::from_str(&json).map_err(|e| format!("{} is not a session file: {}", path, e))
+    }
+
+    fn label(&self) -> String {
+        if self.steps == 0 {
+            self.algorithm.clone()
+        } else {
+            format!("{}, step {}", self.algorithm, self.steps)
+        }
+    }
+}
+
+/// Decodes inputs saved by `Algorithm::save_inputs`, for visualizers' `load_inputs`.
+pub fn decode<T: DeserializeOwned>(inputs: serde_json::Value) -> Result<T, String> {
+    serde_json::from_value(inputs).map_err(|e| format!("unreadable inputs: {}", e))
+}
+
+/// Encodes a visualizer's inputs for `Algorithm::save_inputs`.
+pub fn encode<T: Serialize>(inputs: &T) -> serde_json::Value {
+    serde_json::to_value(inputs).unwrap_or(serde_json::Value::Null)
+}
+
+/// What the app keeps in eframe's storage between launches.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Saved {
+    pub theme: Theme,
+    /// The visualizer that was open when the app closed.
+    pub current: Option<Session>,
+    pub recent: Vec<Session>,
+    pub session_path: String,
+}
+
+/// Puts `session` at the top of `recent`, replacing an older session of the same
+/// visualizer so the list shows one entry per algorithm.
+pub fn remember(recent: &mut Vec<Session>, session: Session) {
+    recent.retain(|s| s.algorithm != session.algorithm);
+    recent.insert(0, session);
+    recent.truncate(MAX_RECENT);
+}
+
+/// Recent sessions as buttons, returns the one clicked.
+pub fn recent_list(ui: &mut egui::Ui, recent: &[Session]) -> Option<Session> {
+    let mut opened = None;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    for session in recent {
+        ui.horizontal(|ui| {
+            if ui.add_sized(egui::Vec2::new(320.0, 0.0), egui::Button::new(session.label())).clicked() {
+                opened = Some(session.clone());
+            }
+            ui.weak(age(now.saturating_sub(session.saved_at)));
+        });
+    }
+    opened
+}
+
+fn age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86_399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86_400),
+    }
+}
+
+pub enum FileAction {
+    Export,
+    Import,
+}
+
+/// Path field with Export and Import buttons. Export is only offered when there is an
+/// open visualizer to write out.
+pub fn file_panel(ui: &mut egui::Ui, path: &mut String, can_export: bool) -> Option<FileAction> {
+    let mut action = None;
+    egui::CollapsingHeader::new("Session file").show(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(path);
+            if can_export && ui.button("Export session").clicked() {
+                action = Some(FileAction::Export);
+            }
+            if ui.button("Import session").clicked() {
+                action = Some(FileAction::Import);
+            }
+        });
+        ui.weak("A session file holds the algorithm, its inputs and the step it was on, to share an exact scenario.");
+    });
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog;
+
+    #[test]
+    fn a_captured_session_reopens_where_it_was() {
+        let registry = catalog::default_registry();
+        let entry = registry.find("Bubble Sort").unwrap();
+        let mut runner = Runner::new(entry.create());
+        runner.step_interval = Duration::from_millis(250);
+        for _ in 0..5 {
+            runner.step();
+        }
+
+        let session = Session::capture(entry.name, &mut runner);
+        let json = serde_json::to_string(&session).unwrap();
+        let read: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, session);
+
+        let (index, mut reopened) = read.open(&registry).unwrap();
+        assert_eq!(registry.get(index).unwrap().name, entry.name);
+        assert_eq!(reopened.steps(), 5);
+        assert_eq!(reopened.step_interval, Duration::from_millis(250));
+        assert_eq!(reopened.algorithm().save_inputs(), runner.algorithm().save_inputs());
+        assert_eq!(Session::capture(entry.name, &mut reopened).inputs, session.inputs);
+    }
+
+    #[test]
+    fn sessions_from_a_newer_build_or_unknown_visualizer_are_refused() {
+        let registry = catalog::default_registry();
+        let mut runner = Runner::new(registry.find("Trie").unwrap().create());
+        let session = Session::capture("Trie", &mut runner);
+        assert!(Session { version: FORMAT_VERSION + 1, ..session.clone() }.open(&registry).is_err());
+        assert!(Session { algorithm: "Bogo Sort".to_string(), ..session.clone() }.open(&registry).is_err());
+        assert!(Session { inputs: serde_json::json!("not inputs"), ..session }.open(&registry).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::sorting::{Recorder, SortKind, SortVisualizer};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum GapSequence {
+    /// n/2, n/4, ..., 1 from Shell's original paper.
+    Shell,
+    /// 1, 4, 13, 40, ... (3^k - 1) / 2.
+    Knuth,
+    /// 1, 3, 7, 15, ... 2^k - 1.
+    Hibbard,
+    /// Empirically found gaps, extended by a factor of 2.25 past 701.
+    Ciura,
+}
+
+impl GapSequence {
+    pub const ALL: [GapSequence; 4] = [GapSequence::Shell, GapSequence::Knuth, GapSequence::Hibbard, GapSequence::Ciura];
+
+    /// Gaps smaller than `n`, largest first and always ending in 1.
+    pub fn gaps(self, n: usize) -> Vec<usize> {
+        let mut gaps: Vec<usize> = match self {
+            GapSequence::Shell => std::iter::successors(Some(n / 2), |&g| Some(g / 2)).take_while(|&g| g > 0).collect(),
+            GapSequence::Knuth => std::iter::successors(Some(1), |&g| Some(3 * g + 1)).take_while(|&g| g < n).collect(),
+            GapSequence::Hibbard => std::iter::successors(Some(1), |&g| Some(2 * g + 1)).take_while(|&g| g < n).collect(),
+            GapSequence::Ciura => {
+                let mut gaps = vec![1, 4, 10, 23, 57, 132, 301, 701];
+                while *gaps.last().unwrap() < n {
+                    let next = (*gaps.last().unwrap() as f64 * 2.25) as usize;
+                    gaps.push(next);
+                }
+                gaps.into_iter().filter(|&g| g < n).collect()
+            }
+        };
+        gaps.sort_unstable_by(|a, b| b.cmp(a));
+        if gaps.last() != Some(&1) {
+            gaps.push(1);
+        }
+        gaps
+    }
+}
+
+impl fmt::Display for GapSequence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GapSequence::Shell => "Shell (n/2^k)",
+            GapSequence::Knuth => "Knuth (3^k-1)/2",
+            GapSequence::Hibbard => "Hibbard (2^k-1)",
+            GapSequence::Ciura => "Ciura",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Insertion sort over elements `gap` apart for a shrinking sequence of gaps, so values
+/// travel far early on and the final gap-1 pass has little left to do.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Shell {
+    gaps: GapSequence,
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Self { gaps: GapSequence::Ciura }
+    }
+}
+
+pub type ShellSortVisualizer = SortVisualizer<Shell>;
+
+impl SortKind for Shell {
+    const NAME: &'static str = "Shell sort";
+    const STABLE: bool = false;
+
+    fn sort(&self, rec: &mut Recorder) {
+        let n = rec.len();
+        for gap in self.gaps.gaps(n) {
+            rec.mark((0..n).step_by(gap), format!("Gap {}: insertion sort every {}th element", gap, gap));
+            for i in gap..n {
+                let mut j = i;
+                while j >= gap && rec.less(j, j - gap) {
+                    rec.swap(j, j - gap);
+                    j -= gap;
+                }
+            }
+        }
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.gaps;
+        egui::ComboBox::from_label("Gap sequence")
+            .selected_text(self.gaps.to_string())
+            .show_ui(ui, |ui| {
+                for gaps in GapSequence::ALL {
+                    ui.selectable_value(&mut self.gaps, gaps, gaps.to_string());
+                }
+            });
+        self.gaps != before
+    }
+}
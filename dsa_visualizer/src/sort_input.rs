@@ -0,0 +1,159 @@
+use eframe::egui;
+use egui::Color32;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution as _, Normal};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Largest value a generated input can hold. Bars are scaled to the actual maximum anyway.
+const MAX_VALUE: i32 = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Distribution {
+    Uniform,
+    NearlySorted,
+    Reversed,
+    ManyDuplicates,
+    Gaussian,
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 5] = [
+        Distribution::Uniform,
+        Distribution::NearlySorted,
+        Distribution::Reversed,
+        Distribution::ManyDuplicates,
+        Distribution::Gaussian,
+    ];
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Distribution::Uniform => "Uniform",
+            Distribution::NearlySorted => "Nearly sorted",
+            Distribution::Reversed => "Reversed",
+            Distribution::ManyDuplicates => "Many duplicates",
+            Distribution::Gaussian => "Gaussian",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Size, shape and seed of the array handed to a sorting visualizer. The same
+/// settings always generate the same array.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SortInput {
+    pub size: usize,
+    pub distribution: Distribution,
+    pub seed: u64,
+}
+
+impl SortInput {
+    pub const MIN_SIZE: usize = 5;
+    pub const MAX_SIZE: usize = 500;
+
+    pub fn new(size: usize) -> Self {
+        Self {
+            size: size.clamp(Self::MIN_SIZE, Self::MAX_SIZE),
+            distribution: Distribution::Uniform,
+            seed: 42,
+        }
+    }
+
+    /// Brings a size read from a session file back into range.
+    pub fn clamped(self) -> Self {
+        Self {
+            size: self.size.clamp(Self::MIN_SIZE, Self::MAX_SIZE),
+            ..self
+        }
+    }
+
+    pub fn generate(&self) -> Vec<i32> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let uniform = |rng: &mut StdRng| -> Vec<i32> { (0..self.size).map(|_| rng.gen_range(1..=MAX_VALUE)).collect() };
+
+        match self.distribution {
+            Distribution::Uniform => uniform(&mut rng),
+            Distribution::NearlySorted => {
+                let mut data = uniform(&mut rng);
+                data.sort_unstable();
+                // Swap about one element in twenty with a close neighbor.
+                for _ in 0..(self.size / 20).max(1) {
+                    let i = rng.gen_range(0..self.size);
+                    let j = (i + rng.gen_range(1..=3)).min(self.size - 1);
+                    data.swap(i, j);
+                }
+                data
+            }
+            Distribution::Reversed => {
+                let mut data = uniform(&mut rng);
+                data.sort_unstable_by(|a, b| b.cmp(a));
+                data
+            }
+            Distribution::ManyDuplicates => {
+                let values: Vec<i32> = (0..5).map(|_| rng.gen_range(1..=MAX_VALUE)).collect();
+                (0..self.size).map(|_| values[rng.gen_range(0..values.len())]).collect()
+            }
+            Distribution::Gaussian => {
+                let normal = Normal::new(MAX_VALUE as f64 / 2.0, MAX_VALUE as f64 / 6.0).unwrap();
+                (0..self.size)
+                    .map(|_| (normal.sample(&mut rng).round() as i32).clamp(1, MAX_VALUE))
+                    .collect()
+            }
+        }
+    }
+
+    /// Size slider, distribution picker and seed field. Returns true when any of them
+    /// changed, so the caller can regenerate its data.
+    pub fn controls(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+        ui.horizontal(|ui| {
+            ui.label("N:");
+            ui.add(egui::Slider::new(&mut self.size, Self::MIN_SIZE..=Self::MAX_SIZE).logarithmic(true));
+
+            egui::ComboBox::from_id_salt("sort_input_distribution")
+                .selected_text(self.distribution.to_string())
+                .show_ui(ui, |ui| {
+                    for distribution in Distribution::ALL {
+                        ui.selectable_value(&mut self.distribution, distribution, distribution.to_string());
+                    }
+                });
+
+            ui.label("Seed:");
+            ui.add(egui::DragValue::new(&mut self.seed));
+            if ui.button("New Seed").clicked() {
+                self.seed = rand::thread_rng().gen();
+            }
+        });
+        *self != before
+    }
+}
+
+/// Bar chart of `data`, scaled so the largest value fills `height`. Spacing between
+/// bars disappears once they get too thin to keep it. Returns the index of a clicked bar.
+pub fn draw_bars(ui: &mut egui::Ui, data: &[i32], height: f32, color: impl Fn(usize) -> Color32) -> Option<usize> {
+    let (response, painter) = ui.allocate_painter(egui::vec2(ui.available_width(), height), egui::Sense::click());
+    let rect = response.rect;
+    if data.is_empty() {
+        return None;
+    }
+
+    let max_value = data.iter().copied().max().unwrap_or(1).max(1) as f32;
+    let slot = rect.width() / data.len() as f32;
+    let spacing = if slot > 6.0 { 2.0 } else { 0.0 };
+
+    for (i, &value) in data.iter().enumerate() {
+        let bar_height = value.max(0) as f32 / max_value * rect.height();
+        let x = rect.left() + i as f32 * slot;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(x, rect.bottom() - bar_height),
+            egui::pos2(x + slot - spacing, rect.bottom()),
+        );
+        painter.rect_filled(bar, 0.0, color(i));
+    }
+
+    let click = response.interact_pointer_pos().filter(|_| response.clicked())?;
+    Some((((click.x - rect.left()) / slot) as usize).min(data.len() - 1))
+}
@@ -0,0 +1,564 @@
+use crate::algorithm::{Action, Algorithm};
+use crate::pan_zoom;
+use crate::session;
+use crate::sort_input::{self, SortInput};
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use egui::{Color32, Stroke};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// A value being sorted. `tag` counts how many equal keys came before it in the
+/// input, so a stable sort keeps the tags of equal keys in ascending order.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Item {
+    pub key: i32,
+    pub tag: usize,
+}
+
+/// Tags every value with its occurrence number among equal keys.
+pub fn tag_items(values: &[i32]) -> Vec<Item> {
+    let mut seen: std::collections::HashMap<i32, usize> = std::collections::HashMap::new();
+    values
+        .iter()
+        .map(|&key| {
+            let count = seen.entry(key).or_insert(0);
+            *count += 1;
+            Item { key, tag: *count - 1 }
+        })
+        .collect()
+}
+
+/// One primitive operation of a sort. The visualizer replays these against a copy of
+/// the input instead of storing a full array per step, so quadratic sorts over a few
+/// hundred values stay cheap.
+#[derive(Clone, Debug)]
+pub enum Op {
+    Compare(usize, usize),
+    /// Compares the item at a buffer slot with one in the array.
+    CompareBuffer(usize, usize),
+    Swap(usize, usize),
+    Write(usize, Item),
+    Buffer(usize, Item),
+    ClearBuffer,
+    Bucket(usize, usize),
+    ClearBuckets,
+    /// Only highlights, used to point out runs and passes.
+    Mark(Vec<usize>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Step {
+    op: Op,
+    note: Option<String>,
+}
+
+/// Runs a sort against a working copy and records every operation it performs.
+pub struct Recorder {
+    pub items: Vec<Item>,
+    pub buffer: Vec<Option<Item>>,
+    steps: Vec<Step>,
+    /// False when only counting, as the benchmark does for inputs far too large to replay.
+    keep_steps: bool,
+    /// Comparisons and element moves so far. Highlights and clearing don't count.
+    operations: usize,
+}
+
+impl Recorder {
+    fn new(items: Vec<Item>) -> Self {
+        Self {
+            buffer: vec![None; items.len()],
+            items,
+            steps: Vec::new(),
+            keep_steps: true,
+            operations: 0,
+        }
+    }
+
+    /// A recorder that only counts operations, so a sort can run on its own.
+    pub fn counting(items: Vec<Item>) -> Self {
+        Self {
+            keep_steps: false,
+            ..Self::new(items)
+        }
+    }
+
+    pub fn operations(&self) -> usize {
+        self.operations
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn key(&self, i: usize) -> i32 {
+        self.items[i].key
+    }
+
+    fn push(&mut self, op: Op) {
+        if !matches!(op, Op::Mark(_) | Op::ClearBuffer | Op::ClearBuckets) {
+            self.operations += 1;
+        }
+        if self.keep_steps {
+            self.steps.push(Step { op, note: None });
+        }
+    }
+
+    /// Attaches an explanation to the most recent operation.
+    pub fn note(&mut self, note: String) {
+        if let Some(step) = self.steps.last_mut() {
+            step.note = Some(note);
+        }
+    }
+
+    /// True when the item at `i` sorts strictly before the item at `j`.
+    pub fn less(&mut self, i: usize, j: usize) -> bool {
+        self.push(Op::Compare(i, j));
+        self.items[i].key < self.items[j].key
+    }
+
+    /// True when buffer slot `slot` may be taken before array item `i` without
+    /// breaking stability, that is when it is not greater.
+    pub fn buffer_first(&mut self, slot: usize, i: usize) -> bool {
+        self.push(Op::CompareBuffer(slot, i));
+        self.buffered(slot).key <= self.items[i].key
+    }
+
+    pub fn swap(&mut self, i: usize, j: usize) {
+        self.push(Op::Swap(i, j));
+        self.items.swap(i, j);
+    }
+
+    pub fn write(&mut self, i: usize, item: Item) {
+        self.push(Op::Write(i, item));
+        self.items[i] = item;
+    }
+
+    pub fn buffered(&self, slot: usize) -> Item {
+        self.buffer[slot].expect("buffer slot was filled")
+    }
+
+    pub fn put_in_buffer(&mut self, slot: usize, item: Item) {
+        self.push(Op::Buffer(slot, item));
+        self.buffer[slot] = Some(item);
+    }
+
+    pub fn clear_buffer(&mut self) {
+        self.push(Op::ClearBuffer);
+        self.buffer.iter_mut().for_each(|slot| *slot = None);
+    }
+
+    /// Drops the item at `i` into `bucket`. The array slot keeps its value until it is
+    /// overwritten by the collect pass.
+    pub fn put_in_bucket(&mut self, bucket: usize, i: usize) {
+        self.push(Op::Bucket(bucket, i));
+    }
+
+    pub fn clear_buckets(&mut self) {
+        self.push(Op::ClearBuckets);
+    }
+
+    pub fn mark(&mut self, indices: impl IntoIterator<Item = usize>, note: String) {
+        if !self.keep_steps {
+            return;
+        }
+        self.push(Op::Mark(indices.into_iter().collect()));
+        self.note(note);
+    }
+}
+
+/// What the screen shows after replaying some prefix of the recorded operations.
+#[derive(Clone, Default)]
+struct State {
+    items: Vec<Item>,
+    buffer: Vec<Option<Item>>,
+    buckets: Vec<Vec<Item>>,
+    compared: Vec<usize>,
+    written: Vec<usize>,
+    message: String,
+}
+
+impl State {
+    fn new(items: Vec<Item>) -> Self {
+        Self {
+            buffer: vec![None; items.len()],
+            items,
+            ..Default::default()
+        }
+    }
+
+    fn apply(&mut self, step: &Step) {
+        self.compared.clear();
+        self.written.clear();
+        let message = match step.op {
+            Op::Compare(i, j) => {
+                self.compared = vec![i, j];
+                format!("Compare a[{}] = {} with a[{}] = {}", i, self.items[i].key, j, self.items[j].key)
+            }
+            Op::CompareBuffer(slot, i) => {
+                self.compared = vec![i];
+                let key = self.buffer[slot].map_or(0, |item| item.key);
+                format!("Compare buffered {} with a[{}] = {}", key, i, self.items[i].key)
+            }
+            Op::Swap(i, j) => {
+                self.items.swap(i, j);
+                self.written = vec![i, j];
+                format!("Swap a[{}] and a[{}]", i, j)
+            }
+            Op::Write(i, item) => {
+                self.items[i] = item;
+                self.written = vec![i];
+                format!("Write {} to a[{}]", item.key, i)
+            }
+            Op::Buffer(slot, item) => {
+                self.buffer[slot] = Some(item);
+                format!("Put {} in buffer slot {}", item.key, slot)
+            }
+            Op::ClearBuffer => {
+                self.buffer.iter_mut().for_each(|slot| *slot = None);
+                "Clear the buffer".to_string()
+            }
+            Op::Bucket(bucket, i) => {
+                if self.buckets.len() <= bucket {
+                    self.buckets.resize(bucket + 1, Vec::new());
+                }
+                self.buckets[bucket].push(self.items[i]);
+                self.compared = vec![i];
+                format!("a[{}] = {} goes to bucket {}", i, self.items[i].key, bucket)
+            }
+            Op::ClearBuckets => {
+                self.buckets.clear();
+                "Empty the buckets".to_string()
+            }
+            Op::Mark(ref indices) => {
+                self.compared = indices.clone();
+                String::new()
+            }
+        };
+        self.message = step.note.clone().unwrap_or(message);
+    }
+
+    /// False as soon as two equal keys appear in the opposite order to the input.
+    fn equal_keys_in_order(&self) -> bool {
+        let mut last_tag: std::collections::HashMap<i32, usize> = std::collections::HashMap::new();
+        self.items.iter().all(|item| {
+            let in_order = last_tag.get(&item.key).is_none_or(|&tag| tag <= item.tag);
+            last_tag.insert(item.key, item.tag);
+            in_order
+        })
+    }
+}
+
+/// Color of an item that is not being compared or written: equal keys get one color
+/// per occurrence so their relative order is visible, unique keys stay neutral.
+fn item_color(theme: &Theme, item: &Item, duplicated: bool) -> Color32 {
+    if duplicated {
+        theme.group(item.tag)
+    } else {
+        theme.color(Role::Unvisited)
+    }
+}
+
+/// What distinguishes one sorting algorithm from another.
+pub trait SortKind: Default + Clone + Serialize + DeserializeOwned {
+    const NAME: &'static str;
+    const STABLE: bool;
+    fn sort(&self, rec: &mut Recorder);
+    /// Extra settings shown next to the input controls. Returns true when they changed.
+    fn controls(&mut self, _ui: &mut egui::Ui) -> bool {
+        false
+    }
+}
+
+/// What a session keeps of a sorting visualizer.
+#[derive(Serialize, Deserialize)]
+struct Inputs<T> {
+    input: SortInput,
+    kind: T,
+    ops_per_step: usize,
+}
+
+pub struct SortVisualizer<T: SortKind> {
+    kind: T,
+    input: SortInput,
+    original: Vec<Item>,
+    steps: Vec<Step>,
+    /// Comparisons and moves the sort took, counted the way the benchmark counts them.
+    operations: usize,
+    current_step: usize,
+    state: State,
+    ops_per_step: usize,
+    clicked: Option<usize>,
+}
+
+impl<T: SortKind> SortVisualizer<T> {
+    pub fn new() -> Self {
+        Self {
+            kind: T::default(),
+            input: SortInput::new(25),
+            original: Vec::new(),
+            steps: Vec::new(),
+            operations: 0,
+            current_step: 0,
+            state: State::default(),
+            ops_per_step: 1,
+            clicked: None,
+        }
+    }
+
+    fn record(&mut self) {
+        self.original = tag_items(&self.input.generate());
+        let mut rec = Recorder::new(self.original.clone());
+        self.kind.sort(&mut rec);
+        self.operations = rec.operations();
+        self.steps = rec.steps;
+        self.reset();
+    }
+
+    fn render_bars(&self, ui: &mut egui::Ui) -> Option<usize> {
+        let items = &self.state.items;
+        let duplicated: Vec<bool> = items
+            .iter()
+            .map(|item| self.original.iter().filter(|other| other.key == item.key).count() > 1)
+            .collect();
+        let keys: Vec<i32> = items.iter().map(|item| item.key).collect();
+        let theme = Theme::current(ui.ctx());
+        sort_input::draw_bars(ui, &keys, 240.0, |i| {
+            if self.state.written.contains(&i) {
+                theme.color(Role::Swapped)
+            } else if self.state.compared.contains(&i) {
+                theme.color(Role::Compared)
+            } else {
+                item_color(&theme, &items[i], duplicated[i])
+            }
+        })
+    }
+
+    fn render_buffer(&self, ui: &mut egui::Ui) {
+        if self.state.buffer.iter().all(Option::is_none) {
+            return;
+        }
+        ui.label("Buffer:");
+        // Empty slots become zero-height bars so filled ones stay at their index.
+        let keys: Vec<i32> = self.state.buffer.iter().map(|slot| slot.map_or(0, |item| item.key)).collect();
+        let theme = Theme::current(ui.ctx());
+        sort_input::draw_bars(ui, &keys, 80.0, |i| {
+            self.state.buffer[i].map_or(Color32::TRANSPARENT, |item| item_color(&theme, &item, true))
+        });
+    }
+
+    fn render_buckets(&self, ui: &mut egui::Ui) {
+        if self.state.buckets.iter().all(Vec::is_empty) {
+            return;
+        }
+        let theme = Theme::current(ui.ctx());
+        let height = 160.0;
+        let (response, painter) = ui.allocate_painter(egui::vec2(ui.available_width(), height + 20.0), egui::Sense::hover());
+        let rect = response.rect;
+        let columns = self.state.buckets.len().max(10);
+        let column_width = rect.width() / columns as f32;
+        let tallest = self.state.buckets.iter().map(Vec::len).max().unwrap_or(1).max(1);
+        let tile = (height / tallest as f32).min(14.0);
+
+        for (b, bucket) in self.state.buckets.iter().enumerate() {
+            let x = rect.left() + b as f32 * column_width;
+            let column = egui::Rect::from_min_size(egui::pos2(x + 2.0, rect.top()), egui::vec2(column_width - 4.0, height));
+            painter.rect_stroke(column, 0.0, Stroke::new(1.0, theme.line()));
+            for (k, item) in bucket.iter().enumerate() {
+                let y = column.bottom() - (k as f32 + 1.0) * tile;
+                let cell = egui::Rect::from_min_size(egui::pos2(column.left() + 2.0, y), egui::vec2(column.width() - 4.0, tile - 1.0));
+                let fill = item_color(&theme, item, true);
+                painter.rect_filled(cell, 0.0, fill);
+                if tile >= 12.0 {
+                    painter.text(
+                        cell.center(),
+                        egui::Align2::CENTER_CENTER,
+                        item.key.to_string(),
+                        egui::FontId::new(10.0, egui::FontFamily::Proportional),
+                        theme.text_on(fill),
+                    );
+                }
+            }
+            painter.text(
+                egui::pos2(column.center().x, rect.bottom() - 8.0),
+                egui::Align2::CENTER_CENTER,
+                b.to_string(),
+                egui::FontId::new(12.0, egui::FontFamily::Proportional),
+                theme.muted(),
+            );
+        }
+    }
+}
+
+impl<T: SortKind> Algorithm for SortVisualizer<T> {
+    fn initialize(&mut self) {
+        self.record();
+    }
+
+    fn step(&mut self) {
+        for _ in 0..self.ops_per_step {
+            let Some(step) = self.steps.get(self.current_step) else {
+                break;
+            };
+            self.state.apply(step);
+            self.current_step += 1;
+        }
+        if self.is_finished() {
+            self.state.compared.clear();
+            self.state.written.clear();
+            self.state.message = format!("Sorted with {} operations", self.operations);
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        let mut changed = self.input.controls(ui);
+        ui.horizontal(|ui| {
+            changed |= self.kind.controls(ui);
+            ui.add(egui::Slider::new(&mut self.ops_per_step, 1..=100).text("operations per step"));
+        });
+        if changed {
+            self.record();
+        }
+
+        ui.label(format!("Operation {}/{}", self.current_step, self.steps.len()));
+        ui.label(&self.state.message);
+        let theme = Theme::current(ui.ctx());
+        ui.horizontal(|ui| {
+            let (text, color) = if T::STABLE {
+                (format!("{} is stable", T::NAME), theme.color(Role::Solution))
+            } else {
+                (format!("{} is not stable", T::NAME), theme.color(Role::Rejected))
+            };
+            ui.colored_label(color, text);
+            ui.separator();
+            if self.state.equal_keys_in_order() {
+                ui.label("Equal keys are still in input order");
+            } else {
+                ui.colored_label(theme.color(Role::Rejected), "Equal keys have been reordered");
+            }
+        });
+        ui.weak("Equal keys share a color per occurrence: 1st, 2nd, 3rd...");
+
+        self.clicked = pan_zoom::show(ui, "bars", |ui| {
+            let clicked = self.render_bars(ui);
+            self.render_buffer(ui);
+            self.render_buckets(ui);
+            clicked
+        });
+    }
+
+    fn is_finished(&self) -> bool {
+        self.current_step >= self.steps.len()
+    }
+
+    fn reset(&mut self) {
+        self.current_step = 0;
+        self.state = State::new(self.original.clone());
+    }
+
+    /// The first swap among the operations the next step replays. Steps that only
+    /// compare or write have nothing to predict.
+    fn peek(&self) -> Option<Action> {
+        self.steps
+            .iter()
+            .skip(self.current_step)
+            .take(self.ops_per_step)
+            .find_map(|step| match step.op {
+                Op::Swap(i, j) => Some(Action::Swap(i, j)),
+                _ => None,
+            })
+    }
+
+    fn take_click(&mut self) -> Option<usize> {
+        self.clicked.take()
+    }
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![(Role::Compared, "Compared"), (Role::Swapped, "Written or swapped"), (Role::Unvisited, "Unique key")]
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            input: self.input.clone(),
+            kind: self.kind.clone(),
+            ops_per_step: self.ops_per_step,
+        })
+    }
+
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs<T> = session::decode(inputs)?;
+        self.input = inputs.input.clamped();
+        self.kind = inputs.kind;
+        self.ops_per_step = inputs.ops_per_step.clamp(1, 100);
+        self.record();
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bubble_sort::{Bubble, Cocktail};
+    use crate::counting_sort::Counting;
+    use crate::insertion_sort::Insertion;
+    use crate::radix_sort::Radix;
+    use crate::selection_sort::Selection;
+    use crate::shell_sort::Shell;
+    use crate::tim_sort::Tim;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    type Sort = fn(&mut Recorder);
+
+    fn kinds() -> Vec<(&'static str, bool, Sort)> {
+        vec![
+            (Bubble::NAME, Bubble::STABLE, |rec| Bubble.sort(rec)),
+            (Cocktail::NAME, Cocktail::STABLE, |rec| Cocktail.sort(rec)),
+            (Insertion::NAME, Insertion::STABLE, |rec| Insertion.sort(rec)),
+            (Selection::NAME, Selection::STABLE, |rec| Selection.sort(rec)),
+            (Shell::NAME, Shell::STABLE, |rec| Shell::default().sort(rec)),
+            (Counting::NAME, Counting::STABLE, |rec| Counting.sort(rec)),
+            (Radix::NAME, Radix::STABLE, |rec| Radix.sort(rec)),
+            (Tim::NAME, Tim::STABLE, |rec| Tim.sort(rec)),
+        ]
+    }
+
+    fn inputs() -> Vec<Vec<i32>> {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut inputs = vec![vec![], vec![7], vec![2, 2, 2, 2], vec![5, -3, 5, 0, -3, 12, 0, 5], (0..40).rev().map(|k| k / 4).collect()];
+        inputs.extend((0..6).map(|n| (0..10 + 30 * n).map(|_| rng.gen_range(-5..=15)).collect()));
+        inputs
+    }
+
+    #[test]
+    fn every_kind_sorts_and_stable_ones_keep_equal_keys_in_order() {
+        for (name, stable, sort) in kinds() {
+            for values in inputs() {
+                let mut rec = Recorder::counting(tag_items(&values));
+                sort(&mut rec);
+                let keys: Vec<i32> = rec.items.iter().map(|item| item.key).collect();
+                let mut expected = values.clone();
+                expected.sort_unstable();
+                assert_eq!(keys, expected, "{} on {:?}", name, values);
+                if stable {
+                    for pair in rec.items.windows(2).filter(|pair| pair[0].key == pair[1].key) {
+                        assert!(pair[0].tag < pair[1].tag, "{} moved equal keys out of order on {:?}", name, values);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_finished_message_counts_operations_like_the_benchmark() {
+        let mut visualizer = SortVisualizer::<Insertion>::new();
+        visualizer.initialize();
+        let mut rec = Recorder::counting(visualizer.original.clone());
+        Insertion.sort(&mut rec);
+        while !visualizer.is_finished() {
+            visualizer.step();
+        }
+        assert_eq!(visualizer.state.message, format!("Sorted with {} operations", rec.operations()));
+    }
+}
@@ -0,0 +1,438 @@
+use crate::algorithm::Algorithm;
+use crate::graph::{self, EdgeInput, Graph, GraphStyle};
+use crate::session;
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum SccMethod {
+    Tarjan,
+    Kosaraju,
+}
+
+#[derive(Clone, Default)]
+struct Snapshot {
+    component: HashMap<usize, usize>,
+    index: HashMap<usize, usize>,
+    low: HashMap<usize, usize>,
+    stack: Vec<usize>,
+    visited: HashSet<usize>,
+    finish_order: Vec<usize>,
+    current: Option<usize>,
+    message: String,
+}
+
+/// What a session keeps: the edited graph and which method runs on it.
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    graph: Graph,
+    method: SccMethod,
+}
+
+pub struct SCCVisualizer {
+    graph: Graph,
+    method: SccMethod,
+    snapshots: Vec<Snapshot>,
+    current_step: usize,
+    components: Vec<Vec<usize>>,
+    edge_input: EdgeInput,
+}
+
+impl SCCVisualizer {
+    pub fn new() -> Self {
+        Self {
+            graph: Graph::new(),
+            method: SccMethod::Tarjan,
+            snapshots: Vec::new(),
+            current_step: 0,
+            components: Vec::new(),
+            edge_input: EdgeInput::new(false, false),
+        }
+    }
+
+    fn example_graph() -> Graph {
+        let mut graph = Graph::new();
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (1, 3), (3, 4), (4, 5), (5, 3), (6, 5), (6, 7), (7, 6)] {
+            graph::add_edge(&mut graph, from, to, 1);
+        }
+        graph
+    }
+
+    fn sorted_neighbors(graph: &Graph, node: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> = graph
+            .get(&node)
+            .map(|edges| edges.iter().map(|&(n, _)| n).collect())
+            .unwrap_or_default();
+        neighbors.sort_unstable();
+        neighbors
+    }
+
+    fn record(&mut self, snapshot: &Snapshot, message: String) {
+        let mut snapshot = snapshot.clone();
+        snapshot.message = message;
+        self.snapshots.push(snapshot);
+    }
+
+    fn generate_steps(&mut self) {
+        self.snapshots.clear();
+        self.components.clear();
+        self.current_step = 0;
+        match self.method {
+            SccMethod::Tarjan => self.generate_tarjan(),
+            SccMethod::Kosaraju => self.generate_kosaraju(),
+        }
+
+        let cycles = self.components.iter().filter(|c| self.is_cyclic(c)).count();
+        let summary = format!(
+            "{} strongly connected components, {} of them contain a cycle",
+            self.components.len(),
+            cycles
+        );
+        let last = self.snapshots.last().cloned().unwrap_or_default();
+        self.record(&Snapshot { current: None, ..last }, summary);
+    }
+
+    /// A component holds a cycle when it has several nodes or a self loop.
+    fn is_cyclic(&self, component: &[usize]) -> bool {
+        component.len() > 1
+            || self
+                .graph
+                .get(&component[0])
+                .is_some_and(|edges| edges.iter().any(|&(n, _)| n == component[0]))
+    }
+
+    fn generate_tarjan(&mut self) {
+        let graph = self.graph.clone();
+        let mut state = Snapshot::default();
+        let mut on_stack = HashSet::new();
+        let mut next_index = 0;
+
+        for root in graph::nodes(&graph) {
+            if state.index.contains_key(&root) {
+                continue;
+            }
+            let mut calls: Vec<(usize, usize)> = Vec::new();
+            Self::tarjan_visit(root, &mut next_index, &mut state, &mut on_stack, &mut calls);
+            self.record(&state, format!("Visit {}: index = low = {}", root, state.index[&root]));
+
+            while let Some(&(node, next)) = calls.last() {
+                let neighbors = Self::sorted_neighbors(&graph, node);
+                if next < neighbors.len() {
+                    calls.last_mut().unwrap().1 += 1;
+                    let neighbor = neighbors[next];
+                    if !state.index.contains_key(&neighbor) {
+                        Self::tarjan_visit(neighbor, &mut next_index, &mut state, &mut on_stack, &mut calls);
+                        self.record(
+                            &state,
+                            format!("{} -> {}: visit {}, index = low = {}", node, neighbor, neighbor, state.index[&neighbor]),
+                        );
+                    } else if on_stack.contains(&neighbor) {
+                        let low = state.low[&node].min(state.index[&neighbor]);
+                        state.low.insert(node, low);
+                        state.current = Some(node);
+                        self.record(
+                            &state,
+                            format!("{} -> {} reaches the stack, low[{}] = {}", node, neighbor, node, low),
+                        );
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                state.current = Some(node);
+                if state.low[&node] == state.index[&node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = state.stack.pop() {
+                        on_stack.remove(&member);
+                        state.component.insert(member, self.components.len());
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    self.record(
+                        &state,
+                        format!("low[{}] == index[{}], pop component {:?}", node, node, component),
+                    );
+                    self.components.push(component);
+                }
+                if let Some(&(parent, _)) = calls.last() {
+                    let low = state.low[&parent].min(state.low[&node]);
+                    state.low.insert(parent, low);
+                    state.current = Some(parent);
+                    self.record(&state, format!("Return to {}, low[{}] = {}", parent, parent, low));
+                }
+            }
+        }
+    }
+
+    fn tarjan_visit(
+        node: usize,
+        next_index: &mut usize,
+        state: &mut Snapshot,
+        on_stack: &mut HashSet<usize>,
+        calls: &mut Vec<(usize, usize)>,
+    ) {
+        state.index.insert(node, *next_index);
+        state.low.insert(node, *next_index);
+        *next_index += 1;
+        state.stack.push(node);
+        on_stack.insert(node);
+        state.visited.insert(node);
+        state.current = Some(node);
+        calls.push((node, 0));
+    }
+
+    fn generate_kosaraju(&mut self) {
+        let graph = self.graph.clone();
+        let mut state = Snapshot::default();
+
+        // First pass: record finish order on the original graph.
+        for root in graph::nodes(&graph) {
+            if state.visited.contains(&root) {
+                continue;
+            }
+            let mut calls = vec![(root, 0)];
+            state.visited.insert(root);
+            state.stack = vec![root];
+            state.current = Some(root);
+            self.record(&state, format!("Pass 1: start a depth-first search at {}", root));
+
+            while let Some(&(node, next)) = calls.last() {
+                let neighbors = Self::sorted_neighbors(&graph, node);
+                if next < neighbors.len() {
+                    calls.last_mut().unwrap().1 += 1;
+                    let neighbor = neighbors[next];
+                    if state.visited.insert(neighbor) {
+                        calls.push((neighbor, 0));
+                        state.stack = calls.iter().map(|&(n, _)| n).collect();
+                        state.current = Some(neighbor);
+                        self.record(&state, format!("Pass 1: {} -> {}", node, neighbor));
+                    }
+                } else {
+                    calls.pop();
+                    state.finish_order.push(node);
+                    state.stack = calls.iter().map(|&(n, _)| n).collect();
+                    state.current = Some(node);
+                    self.record(&state, format!("Pass 1: {} finished", node));
+                }
+            }
+        }
+
+        // Second pass: each search on the transposed graph, in reverse finish order, is one component.
+        let reversed = graph::transpose(&graph);
+        let mut assigned = HashSet::new();
+        state.stack.clear();
+        for root in state.finish_order.clone().into_iter().rev() {
+            if !assigned.insert(root) {
+                continue;
+            }
+            let id = self.components.len();
+            let mut component = vec![root];
+            let mut pending = vec![root];
+            state.component.insert(root, id);
+            state.current = Some(root);
+            self.record(
+                &state,
+                format!("Pass 2: {} finished last among the rest, search the transposed graph from it", root),
+            );
+
+            while let Some(node) = pending.pop() {
+                for neighbor in Self::sorted_neighbors(&reversed, node) {
+                    if assigned.insert(neighbor) {
+                        pending.push(neighbor);
+                        component.push(neighbor);
+                        state.component.insert(neighbor, id);
+                        state.current = Some(neighbor);
+                        self.record(&state, format!("Pass 2: {} <- {} joins the component of {}", node, neighbor, root));
+                    }
+                }
+            }
+            component.sort_unstable();
+            self.record(&state, format!("Component {:?}", component));
+            self.components.push(component);
+        }
+    }
+
+    fn current_snapshot(&self) -> Option<&Snapshot> {
+        self.current_step.checked_sub(1).and_then(|i| self.snapshots.get(i))
+    }
+}
+
+impl Algorithm for SCCVisualizer {
+    fn initialize(&mut self) {
+        self.graph = Self::example_graph();
+        self.generate_steps();
+    }
+
+    fn step(&mut self) {
+        if self.current_step < self.snapshots.len() {
+            self.current_step += 1;
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui.radio_value(&mut self.method, SccMethod::Tarjan, "Tarjan").changed();
+            changed |= ui.radio_value(&mut self.method, SccMethod::Kosaraju, "Kosaraju").changed();
+            ui.separator();
+            if ui.button("Example Graph").clicked() {
+                self.graph = Self::example_graph();
+                changed = true;
+            }
+        });
+        changed |= graph::edge_editor(ui, &mut self.graph, &mut self.edge_input);
+        if changed {
+            self.generate_steps();
+        }
+
+        let snapshot = self.current_snapshot().cloned().unwrap_or_default();
+        ui.label(format!("Step {}/{}", self.current_step, self.snapshots.len()));
+        ui.label(&snapshot.message);
+        match self.method {
+            SccMethod::Tarjan => ui.label(format!("Stack: {:?}", snapshot.stack)),
+            SccMethod::Kosaraju => ui.label(format!("Finish order: {:?}", snapshot.finish_order)),
+        };
+
+        let theme = Theme::current(ui.ctx());
+        let found = snapshot.component.values().max().map_or(0, |&c| c + 1);
+        for (id, component) in self.components.iter().take(found).enumerate() {
+            let text = if self.is_cyclic(component) {
+                format!("Component {}: {:?} (cycle)", id, component)
+            } else {
+                format!("Component {}: {:?}", id, component)
+            };
+            ui.colored_label(theme.group(id), text);
+        }
+
+        let show_low = self.method == SccMethod::Tarjan;
+        graph::draw_graph(
+            ui,
+            &self.graph,
+            GraphStyle {
+                node_color: Box::new(|node| {
+                    if let Some(&c) = snapshot.component.get(&node) {
+                        theme.group(c)
+                    } else if snapshot.current == Some(node) {
+                        theme.color(Role::Current)
+                    } else if snapshot.visited.contains(&node) {
+                        theme.color(Role::Frontier)
+                    } else {
+                        theme.color(Role::Unvisited)
+                    }
+                }),
+                edge_color: Box::new(|from, to| match (snapshot.component.get(&from), snapshot.component.get(&to)) {
+                    (Some(a), Some(b)) if a == b => theme.group(*a),
+                    _ => theme.line(),
+                }),
+                node_label: Box::new(|node| {
+                    if !show_low {
+                        return None;
+                    }
+                    match (snapshot.index.get(&node), snapshot.low.get(&node)) {
+                        (Some(index), Some(low)) => Some(format!("{}/{}", index, low)),
+                        _ => None,
+                    }
+                }),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn is_finished(&self) -> bool {
+        self.current_step >= self.snapshots.len()
+    }
+
+    fn reset(&mut self) {
+        self.current_step = 0;
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            graph: self.graph.clone(),
+            method: self.method,
+        })
+    }
+
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        self.graph = inputs.graph;
+        self.method = inputs.method;
+        self.generate_steps();
+        Ok(())
+    }
+
+    /// Finished components each get a color of their own on top of these.
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![(Role::Unvisited, "Unvisited"), (Role::Current, "Current"), (Role::Frontier, "Visited, no component yet")]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn components(graph: &Graph, method: SccMethod) -> Vec<Vec<usize>> {
+        let mut visualizer = SCCVisualizer { graph: graph.clone(), method, ..SCCVisualizer::new() };
+        visualizer.generate_steps();
+        let mut components = visualizer.components;
+        components.iter_mut().for_each(|c| c.sort_unstable());
+        components.sort();
+        components
+    }
+
+    /// Groups nodes that reach each other, by a search from every node.
+    fn naive_components(graph: &Graph) -> Vec<Vec<usize>> {
+        let reach = |start: usize| {
+            let mut seen = HashSet::from([start]);
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for &(n, _) in graph.get(&node).into_iter().flatten() {
+                    if seen.insert(n) {
+                        stack.push(n);
+                    }
+                }
+            }
+            seen
+        };
+        let nodes = graph::nodes(graph);
+        let reached: HashMap<usize, HashSet<usize>> = nodes.iter().map(|&n| (n, reach(n))).collect();
+        let mut components: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|&a| nodes.iter().copied().filter(|b| reached[&a].contains(b) && reached[b].contains(&a)).collect())
+            .collect();
+        components.sort();
+        components.dedup();
+        components
+    }
+
+    #[test]
+    fn tarjan_and_kosaraju_find_the_same_components() {
+        let example = SCCVisualizer::example_graph();
+        assert_eq!(components(&example, SccMethod::Tarjan), vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7]]);
+
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut graphs = vec![example];
+        for n in 1..14 {
+            let mut graph = Graph::new();
+            for node in 0..n {
+                graph.entry(node).or_default();
+            }
+            for _ in 0..rng.gen_range(0..2 * n) {
+                graph::add_edge(&mut graph, rng.gen_range(0..n), rng.gen_range(0..n), 1);
+            }
+            graphs.push(graph);
+        }
+        for graph in graphs {
+            let expected = naive_components(&graph);
+            assert_eq!(components(&graph, SccMethod::Tarjan), expected);
+            assert_eq!(components(&graph, SccMethod::Kosaraju), expected);
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use crate::algorithm::Algorithm;
+use crate::session;
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Default)]
+enum Phase {
+    #[default]
+    Doubling,
+    Lcp,
+    Query,
+}
+
+/// Everything drawn for one step, precomputed so stepping is just moving an index.
+#[derive(Clone, Default)]
+struct Snapshot {
+    phase: Phase,
+    /// Suffix start positions in their current sorted order.
+    order: Vec<usize>,
+    /// Sort key of each row while doubling: (rank of the first k chars, rank of the next k).
+    keys: Vec<Option<(usize, Option<usize>)>>,
+    /// Rank each row ends up with after this round.
+    ranks: Vec<usize>,
+    /// LCP with the row above, filled in by Kasai's algorithm.
+    lcp: Vec<Option<usize>>,
+    /// Rows being compared.
+    highlight: Vec<usize>,
+    /// Rows the binary search still considers, as a half-open range.
+    range: Option<(usize, usize)>,
+    /// Rows whose suffix starts with the query.
+    matches: Option<(usize, usize)>,
+    message: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    text: String,
+    query: String,
+}
+
+pub struct SuffixArrayVisualizer {
+    text_input: String,
+    query_input: String,
+    text: Vec<char>,
+    snapshots: Vec<Snapshot>,
+    current_step: usize,
+}
+
+impl SuffixArrayVisualizer {
+    pub fn new() -> Self {
+        Self {
+            text_input: String::from("banana"),
+            query_input: String::from("ana"),
+            text: Vec::new(),
+            snapshots: Vec::new(),
+            current_step: 0,
+        }
+    }
+
+    fn suffix(&self, start: usize) -> String {
+        self.text[start..].iter().collect()
+    }
+
+    fn record(&mut self, snapshot: &Snapshot, message: String) {
+        let mut snapshot = snapshot.clone();
+        snapshot.message = message;
+        self.snapshots.push(snapshot);
+    }
+
+    fn generate_steps(&mut self) {
+        self.text = self.text_input.chars().collect();
+        self.snapshots.clear();
+        self.current_step = 0;
+        if self.text.is_empty() {
+            return;
+        }
+
+        let mut state = self.build_suffix_array();
+        self.build_lcp(&mut state);
+        let query: Vec<char> = self.query_input.chars().collect();
+        if !query.is_empty() {
+            self.find(&mut state, &query);
+        }
+    }
+
+    /// Prefix doubling: after the round for k, ranks order the suffixes by their first
+    /// 2k characters, so O(log n) rounds of sorting by rank pairs finish the job.
+    fn build_suffix_array(&mut self) -> Snapshot {
+        let n = self.text.len();
+        let mut alphabet: Vec<char> = self.text.clone();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        let mut rank: Vec<usize> = self.text.iter().map(|c| alphabet.binary_search(c).unwrap()).collect();
+
+        let mut state = Snapshot {
+            order: (0..n).collect(),
+            lcp: vec![None; n],
+            ..Default::default()
+        };
+        state.order.sort_by_key(|&i| rank[i]);
+        state.ranks = state.order.iter().map(|&i| rank[i]).collect();
+        state.keys = vec![None; n];
+        self.record(&state, "Round 0: rank every suffix by its first character".to_string());
+
+        let mut k = 1;
+        loop {
+            let key = |i: usize| (rank[i], (i + k < n).then(|| rank[i + k]));
+            // Missing second halves (None) sort first, so shorter suffixes come first on ties.
+            state.order.sort_by_key(|&i| key(i));
+
+            let mut new_rank = vec![0; n];
+            for w in 1..n {
+                let (prev, cur) = (state.order[w - 1], state.order[w]);
+                new_rank[cur] = new_rank[prev] + usize::from(key(prev) != key(cur));
+            }
+            state.keys = state.order.iter().map(|&i| Some(key(i))).collect();
+            state.ranks = state.order.iter().map(|&i| new_rank[i]).collect();
+            let distinct = new_rank.iter().max().map_or(0, |r| r + 1);
+            rank = new_rank;
+
+            let done = distinct == n;
+            self.record(
+                &state,
+                format!(
+                    "Round k = {}: sort by (rank of the first {} chars, rank of the {} after), {} of {} ranks distinct{}",
+                    k,
+                    k,
+                    k,
+                    distinct,
+                    n,
+                    if done { ", suffix array complete" } else { "" }
+                ),
+            );
+            if done {
+                break;
+            }
+            k *= 2;
+        }
+        state.keys = vec![None; n];
+        state
+    }
+
+    /// Kasai's algorithm: walking suffixes in text order, the LCP with the previous row
+    /// drops by at most one each time, so the total work is O(n).
+    fn build_lcp(&mut self, state: &mut Snapshot) {
+        let n = self.text.len();
+        state.phase = Phase::Lcp;
+        let mut row_of = vec![0; n];
+        for (row, &start) in state.order.iter().enumerate() {
+            row_of[start] = row;
+        }
+        state.lcp[0] = Some(0);
+        state.highlight = vec![0];
+        self.record(state, "The first row has nothing above it, its LCP is 0".to_string());
+
+        let mut h: usize = 0;
+        for (i, &row) in row_of.iter().enumerate() {
+            if row == 0 {
+                h = 0;
+                continue;
+            }
+            let j = state.order[row - 1];
+            let reused = h;
+            while i + h < n && j + h < n && self.text[i + h] == self.text[j + h] {
+                h += 1;
+            }
+            state.lcp[row] = Some(h);
+            state.highlight = vec![row - 1, row];
+            self.record(
+                state,
+                format!(
+                    "Suffix {} and the one above it ({}) share {} chars, {} known from the previous suffix",
+                    i, j, h, reused
+                ),
+            );
+            h = h.saturating_sub(1);
+        }
+        state.highlight.clear();
+    }
+
+    /// Compares the suffix at `start` with `query`, looking only at the query's length.
+    fn compare(&self, start: usize, query: &[char]) -> std::cmp::Ordering {
+        let end = (start + query.len()).min(self.text.len());
+        self.text[start..end].cmp(query)
+    }
+
+    /// Two binary searches over the sorted suffixes bound the rows starting with `query`.
+    fn find(&mut self, state: &mut Snapshot, query: &[char]) {
+        use std::cmp::Ordering;
+        let n = self.text.len();
+        let pattern: String = query.iter().collect();
+        state.phase = Phase::Query;
+
+        let mut bounds = [0; 2];
+        for (b, upper) in [false, true].into_iter().enumerate() {
+            let (mut lo, mut hi) = (0, n);
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                let ordering = self.compare(state.order[mid], query);
+                let go_right = ordering == Ordering::Less || (upper && ordering == Ordering::Equal);
+                state.range = Some((lo, hi));
+                state.highlight = vec![mid];
+                let relation = match ordering {
+                    Ordering::Less => "<",
+                    Ordering::Equal => "starts with",
+                    Ordering::Greater => ">",
+                };
+                self.record(
+                    state,
+                    format!(
+                        "{} bound: row {} \"{}\" {} \"{}\", keep rows {}",
+                        if upper { "Upper" } else { "Lower" },
+                        mid,
+                        self.suffix(state.order[mid]),
+                        relation,
+                        pattern,
+                        if go_right { format!("{}..{}", mid + 1, hi) } else { format!("{}..{}", lo, mid) }
+                    ),
+                );
+                if go_right {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            bounds[b] = lo;
+        }
+
+        state.range = None;
+        state.highlight.clear();
+        let [first, last] = bounds;
+        state.matches = Some((first, last));
+        let mut positions: Vec<usize> = state.order[first..last].to_vec();
+        positions.sort_unstable();
+        let message = if positions.is_empty() {
+            format!("\"{}\" does not occur in the text", pattern)
+        } else {
+            format!("\"{}\" occurs {} times, at positions {:?} (rows {}..{})", pattern, positions.len(), positions, first, last)
+        };
+        self.record(state, message);
+    }
+
+    fn current_snapshot(&self) -> Option<&Snapshot> {
+        self.current_step.checked_sub(1).and_then(|i| self.snapshots.get(i))
+    }
+
+    fn render_text(&self, ui: &mut egui::Ui, snapshot: &Snapshot) {
+        let query_len = self.query_input.chars().count();
+        let occurrences: Vec<usize> = snapshot
+            .matches
+            .map(|(first, last)| snapshot.order[first..last].to_vec())
+            .unwrap_or_default();
+        let theme = Theme::current(ui.ctx());
+        let highlight = theme.color(Role::Solution);
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            for (i, c) in self.text.iter().enumerate() {
+                let covered = occurrences.iter().any(|&start| (start..start + query_len).contains(&i));
+                let text = egui::RichText::new(c.to_string()).monospace().size(18.0);
+                ui.label(if covered { text.background_color(highlight).color(theme.text_on(highlight)) } else { text });
+            }
+        });
+    }
+
+    fn render_table(&self, ui: &mut egui::Ui, snapshot: &Snapshot) {
+        let theme = Theme::current(ui.ctx());
+        egui::Grid::new("suffix_array_table").striped(true).show(ui, |ui| {
+            ui.strong("Row");
+            ui.strong("SA");
+            if snapshot.phase == Phase::Doubling {
+                ui.strong("Key");
+                ui.strong("Rank");
+            }
+            ui.strong("LCP");
+            ui.strong("Suffix");
+            ui.end_row();
+
+            for (row, &start) in snapshot.order.iter().enumerate() {
+                let in_range = snapshot.range.is_some_and(|(lo, hi)| (lo..hi).contains(&row));
+                let matched = snapshot.matches.is_some_and(|(first, last)| (first..last).contains(&row));
+                let color = if snapshot.highlight.contains(&row) {
+                    theme.color(Role::Current)
+                } else if matched {
+                    theme.color(Role::Solution)
+                } else if in_range {
+                    theme.color(Role::Frontier)
+                } else {
+                    theme.muted()
+                };
+
+                ui.colored_label(color, row.to_string());
+                ui.colored_label(color, start.to_string());
+                if snapshot.phase == Phase::Doubling {
+                    let key = match snapshot.keys[row] {
+                        Some((first, Some(second))) => format!("({}, {})", first, second),
+                        Some((first, None)) => format!("({}, -)", first),
+                        None => String::new(),
+                    };
+                    ui.colored_label(color, key);
+                    ui.colored_label(color, snapshot.ranks[row].to_string());
+                }
+                ui.colored_label(color, snapshot.lcp[row].map_or(String::new(), |h| h.to_string()));
+                ui.label(egui::RichText::new(self.suffix(start)).monospace().color(color));
+                ui.end_row();
+            }
+        });
+    }
+}
+
+impl Algorithm for SuffixArrayVisualizer {
+    fn initialize(&mut self) {
+        self.generate_steps();
+    }
+
+    fn step(&mut self) {
+        if self.current_step < self.snapshots.len() {
+            self.current_step += 1;
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Text:");
+            changed |= ui.text_edit_singleline(&mut self.text_input).changed();
+            ui.label("Query:");
+            changed |= ui.text_edit_singleline(&mut self.query_input).changed();
+        });
+        if changed {
+            self.generate_steps();
+        }
+        if self.text.is_empty() {
+            ui.label("Enter some text to index");
+            return;
+        }
+
+        let snapshot = self.current_snapshot().cloned().unwrap_or_else(|| Snapshot {
+            order: (0..self.text.len()).collect(),
+            keys: vec![None; self.text.len()],
+            ranks: vec![0; self.text.len()],
+            lcp: vec![None; self.text.len()],
+            message: "Suffixes in text order, press Start to sort them".to_string(),
+            ..Default::default()
+        });
+        ui.label(format!("Step {}/{}", self.current_step, self.snapshots.len()));
+        ui.label(&snapshot.message);
+        ui.add_space(8.0);
+        self.render_text(ui, &snapshot);
+        ui.add_space(8.0);
+        self.render_table(ui, &snapshot);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.current_step >= self.snapshots.len()
+    }
+
+    fn reset(&mut self) {
+        self.current_step = 0;
+    }
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![(Role::Current, "Compared"), (Role::Frontier, "Still searched"), (Role::Solution, "Match")]
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            text: self.text_input.clone(),
+            query: self.query_input.clone(),
+        })
+    }
+
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        self.text_input = inputs.text;
+        self.query_input = inputs.query;
+        self.generate_steps();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn last(text: &str, query: &str) -> Snapshot {
+        let mut visualizer = SuffixArrayVisualizer {
+            text_input: text.to_string(),
+            query_input: query.to_string(),
+            ..SuffixArrayVisualizer::new()
+        };
+        visualizer.generate_steps();
+        visualizer.snapshots.pop().unwrap()
+    }
+
+    fn naive_order(text: &[char]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..text.len()).collect();
+        order.sort_by_key(|&i| &text[i..]);
+        order
+    }
+
+    fn common_prefix(a: &[char], b: &[char]) -> usize {
+        a.iter().zip(b).take_while(|(x, y)| x == y).count()
+    }
+
+    #[test]
+    fn doubling_and_kasai_match_a_naive_sort() {
+        let mut rng = StdRng::seed_from_u64(37);
+        let mut texts: Vec<String> = ["banana", "a", "aaaaaaaa", "abababab", "mississippi", "abcabcabc", "zyxwv"]
+            .iter()
+            .map(|text| text.to_string())
+            .collect();
+        texts.extend((0..20).map(|_| (0..rng.gen_range(1..30)).map(|_| if rng.gen_bool(0.5) { 'a' } else { 'b' }).collect()));
+
+        for text in &texts {
+            let chars: Vec<char> = text.chars().collect();
+            let order = naive_order(&chars);
+            let mut lcp = vec![Some(0)];
+            lcp.extend(order.windows(2).map(|w| Some(common_prefix(&chars[w[0]..], &chars[w[1]..]))));
+
+            let snapshot = last(text, "");
+            assert_eq!(snapshot.order, order, "suffix array of {:?}", text);
+            assert_eq!(snapshot.lcp, lcp, "LCP of {:?}", text);
+        }
+    }
+
+    #[test]
+    fn the_binary_search_bounds_exactly_the_matching_rows() {
+        for (text, query) in [
+            ("banana", "ana"),
+            ("banana", "a"),
+            ("banana", "banana"),
+            ("banana", "nab"),
+            ("banana", "bananas"),
+            ("aaaaaa", "aa"),
+            ("abababab", "bab"),
+            ("mississippi", "ssi"),
+            ("mississippi", "z"),
+        ] {
+            let chars: Vec<char> = text.chars().collect();
+            let query_chars: Vec<char> = query.chars().collect();
+            let snapshot = last(text, query);
+            let (first, last) = snapshot.matches.unwrap();
+
+            let rows: Vec<usize> = (0..chars.len()).filter(|&row| chars[snapshot.order[row]..].starts_with(&query_chars)).collect();
+            assert_eq!((first..last).collect::<Vec<_>>(), rows, "{:?} in {:?}", query, text);
+            if rows.is_empty() {
+                assert!(snapshot.message.contains("does not occur"));
+            }
+        }
+
+        let snapshot = last("banana", "ana");
+        assert!(snapshot.message.contains("at positions [1, 3]"), "{}", snapshot.message);
+    }
+}
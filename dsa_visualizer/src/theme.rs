@@ -0,0 +1,255 @@
+use eframe::egui;
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// What a color means on screen. Visualizers pick roles and the active palette turns
+/// them into colors, so every screen changes together when the palette does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// Not reached yet, or idle.
+    Unvisited,
+    /// Waiting to be processed: queued, on the stack, in the heap, in the open set.
+    Frontier,
+    /// Being processed this step.
+    Current,
+    /// Processed and done with.
+    Visited,
+    /// Being compared.
+    Compared,
+    /// Being moved, swapped or written.
+    Swapped,
+    /// Part of the answer: the shortest path, the spanning tree, the match.
+    Solution,
+    /// Ruled out or invalid: a cycle, an imbalance, a discarded edge.
+    Rejected,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Palette {
+    /// The app's original bright colors.
+    Classic,
+    /// Okabe and Ito's palette, distinguishable with every common color vision deficiency.
+    OkabeIto,
+    /// Paul Tol's "bright" scheme, also color-blind safe and a little softer.
+    TolBright,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::Classic, Palette::OkabeIto, Palette::TolBright];
+
+    fn color(self, role: Role) -> Color32 {
+        use Role::*;
+        match self {
+            Palette::Classic => match role {
+                Unvisited => NEUTRAL,
+                Frontier => Color32::LIGHT_BLUE,
+                Current => Color32::YELLOW,
+                Visited => Color32::GREEN,
+                Compared => Color32::from_rgb(255, 165, 0),
+                Swapped => Color32::RED,
+                Solution => Color32::from_rgb(200, 120, 200),
+                Rejected => Color32::from_rgb(170, 40, 40),
+            },
+            Palette::OkabeIto => match role {
+                Unvisited => NEUTRAL,
+                Frontier => Color32::from_rgb(86, 180, 233),
+                Current => Color32::from_rgb(230, 159, 0),
+                Visited => Color32::from_rgb(0, 114, 178),
+                Compared => Color32::from_rgb(240, 228, 66),
+                Swapped => Color32::from_rgb(213, 94, 0),
+                Solution => Color32::from_rgb(0, 158, 115),
+                Rejected => Color32::from_rgb(204, 121, 167),
+            },
+            Palette::TolBright => match role {
+                Unvisited => Color32::from_rgb(187, 187, 187),
+                Frontier => Color32::from_rgb(102, 204, 238),
+                Current => Color32::from_rgb(204, 187, 68),
+                Visited => Color32::from_rgb(68, 119, 170),
+                Compared => Color32::from_rgb(238, 119, 51),
+                Swapped => Color32::from_rgb(238, 102, 119),
+                Solution => Color32::from_rgb(34, 136, 51),
+                Rejected => Color32::from_rgb(170, 51, 119),
+            },
+        }
+    }
+
+    /// Colors for groups that have no meaning beyond being different from each other,
+    /// like components or equal keys.
+    fn groups(self) -> &'static [Color32] {
+        match self {
+            Palette::Classic => &CLASSIC_GROUPS,
+            Palette::OkabeIto => &OKABE_ITO_GROUPS,
+            Palette::TolBright => &TOL_BRIGHT_GROUPS,
+        }
+    }
+}
+
+const CLASSIC_GROUPS: [Color32; 7] = [
+    Color32::RED,
+    Color32::GREEN,
+    Color32::from_rgb(80, 120, 255),
+    Color32::YELLOW,
+    Color32::from_rgb(255, 0, 255),
+    Color32::from_rgb(0, 255, 255),
+    Color32::from_rgb(255, 165, 0),
+];
+const OKABE_ITO_GROUPS: [Color32; 7] = [
+    Color32::from_rgb(230, 159, 0),
+    Color32::from_rgb(86, 180, 233),
+    Color32::from_rgb(0, 158, 115),
+    Color32::from_rgb(240, 228, 66),
+    Color32::from_rgb(0, 114, 178),
+    Color32::from_rgb(213, 94, 0),
+    Color32::from_rgb(204, 121, 167),
+];
+const TOL_BRIGHT_GROUPS: [Color32; 6] = [
+    Color32::from_rgb(68, 119, 170),
+    Color32::from_rgb(102, 204, 238),
+    Color32::from_rgb(34, 136, 51),
+    Color32::from_rgb(204, 187, 68),
+    Color32::from_rgb(238, 102, 119),
+    Color32::from_rgb(170, 51, 119),
+];
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Palette::Classic => "Classic",
+            Palette::OkabeIto => "Okabe-Ito (color-blind safe)",
+            Palette::TolBright => "Tol bright (color-blind safe)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "classic" => Ok(Palette::Classic),
+            "okabe-ito" | "okabe" => Ok(Palette::OkabeIto),
+            "tol" | "tol-bright" => Ok(Palette::TolBright),
+            other => Err(format!("unknown palette \"{}\", expected classic, okabe-ito or tol", other)),
+        }
+    }
+}
+
+/// Gray for things that carry no state, the same in every palette.
+pub const NEUTRAL: Color32 = Color32::from_gray(150);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Theme {
+    pub palette: Palette,
+    pub dark: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            palette: Palette::OkabeIto,
+            dark: true,
+        }
+    }
+}
+
+impl Theme {
+    /// The theme `install` last stored in `ctx`, or the default one.
+    pub fn current(ctx: &egui::Context) -> Self {
+        ctx.data(|d| d.get_temp(Self::id())).unwrap_or_default()
+    }
+
+    /// Switches egui's own widgets to light or dark and makes this the theme
+    /// `Theme::current` returns. Called once per frame before anything is drawn.
+    pub fn install(&self, ctx: &egui::Context) {
+        ctx.set_visuals(if self.dark { egui::Visuals::dark() } else { egui::Visuals::light() });
+        ctx.data_mut(|d| d.insert_temp(Self::id(), *self));
+    }
+
+    fn id() -> egui::Id {
+        egui::Id::new("dsa_visualizer_theme")
+    }
+
+    pub fn color(&self, role: Role) -> Color32 {
+        self.palette.color(role)
+    }
+
+    pub fn group(&self, i: usize) -> Color32 {
+        let groups = self.palette.groups();
+        groups[i % groups.len()]
+    }
+
+    /// Black or white, whichever reads better on `fill`.
+    pub fn text_on(&self, fill: Color32) -> Color32 {
+        let luminance = 0.299 * fill.r() as f32 + 0.587 * fill.g() as f32 + 0.114 * fill.b() as f32;
+        if fill.a() < 128 {
+            self.text()
+        } else if luminance > 140.0 {
+            Color32::BLACK
+        } else {
+            Color32::WHITE
+        }
+    }
+
+    /// Text drawn straight on the canvas background.
+    pub fn text(&self) -> Color32 {
+        if self.dark {
+            Color32::from_gray(230)
+        } else {
+            Color32::from_gray(20)
+        }
+    }
+
+    /// Annotations and other secondary text on the canvas background.
+    pub fn muted(&self) -> Color32 {
+        if self.dark {
+            Color32::from_gray(160)
+        } else {
+            Color32::from_gray(90)
+        }
+    }
+
+    /// Lines that connect things without saying anything about them.
+    pub fn line(&self) -> Color32 {
+        if self.dark {
+            Color32::from_gray(120)
+        } else {
+            Color32::from_gray(150)
+        }
+    }
+
+    /// Mode and palette pickers, shown in the top bar.
+    pub fn settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.dark, true, "Dark");
+            ui.selectable_value(&mut self.dark, false, "Light");
+            ui.separator();
+            ui.label("Palette:");
+            egui::ComboBox::from_id_salt("theme_palette")
+                .selected_text(self.palette.to_string())
+                .show_ui(ui, |ui| {
+                    for palette in Palette::ALL {
+                        ui.selectable_value(&mut self.palette, palette, palette.to_string());
+                    }
+                });
+        });
+    }
+}
+
+/// Swatches explaining what each color on the current screen means.
+pub fn legend(ui: &mut egui::Ui, entries: &[(Role, &str)]) {
+    if entries.is_empty() {
+        return;
+    }
+    let theme = Theme::current(ui.ctx());
+    ui.horizontal_wrapped(|ui| {
+        for &(role, meaning) in entries {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+            ui.painter().rect_filled(rect, 3.0, theme.color(role));
+            ui.label(meaning);
+            ui.add_space(8.0);
+        }
+    });
+}
@@ -0,0 +1,230 @@
+use crate::sorting::{Recorder, SortKind, SortVisualizer};
+use serde::{Deserialize, Serialize};
+
+/// Runs shorter than this are extended with binary insertion sort. Real Timsort uses
+/// 32 to 64; a small value leaves several runs to merge at the sizes shown on screen.
+const MIN_MERGE: usize = 8;
+/// Consecutive wins by one side of a merge before switching to galloping.
+const MIN_GALLOP: usize = 3;
+
+/// A simplified Timsort: natural run detection, binary insertion up to `minrun`, a run
+/// stack merged under Timsort's size invariants, and merges that gallop once one run
+/// keeps winning.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Tim;
+
+pub type TimSortVisualizer = SortVisualizer<Tim>;
+
+/// Picks a run length in [MIN_MERGE / 2, MIN_MERGE] so n / minrun is close to a power of two.
+fn min_run(mut n: usize) -> usize {
+    let mut extra = 0;
+    while n >= MIN_MERGE {
+        extra |= n & 1;
+        n >>= 1;
+    }
+    n + extra
+}
+
+impl Tim {
+    /// Length of the natural run starting at `start`. Strictly descending runs are
+    /// reversed in place; strictness keeps the reversal stable.
+    fn count_run(rec: &mut Recorder, start: usize) -> usize {
+        let n = rec.len();
+        if start + 1 == n {
+            return 1;
+        }
+        let mut end = start + 2;
+        if rec.less(start + 1, start) {
+            while end < n && rec.less(end, end - 1) {
+                end += 1;
+            }
+            rec.mark(start..end, format!("Descending run a[{}..{}], reverse it", start, end));
+            let (mut lo, mut hi) = (start, end - 1);
+            while lo < hi {
+                rec.swap(lo, hi);
+                lo += 1;
+                hi -= 1;
+            }
+        } else {
+            while end < n && !rec.less(end, end - 1) {
+                end += 1;
+            }
+        }
+        end - start
+    }
+
+    /// Extends the sorted run a[start..sorted] to a[start..end] by binary insertion.
+    fn binary_insertion(rec: &mut Recorder, start: usize, sorted: usize, end: usize) {
+        for i in sorted..end {
+            let item = rec.items[i];
+            let (mut lo, mut hi) = (start, i);
+            // Find the first slot whose key is greater, so equal keys stay in front.
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                if rec.less(i, mid) {
+                    hi = mid;
+                } else {
+                    lo = mid + 1;
+                }
+            }
+            for k in (lo..i).rev() {
+                let shifted = rec.items[k];
+                rec.write(k + 1, shifted);
+            }
+            if lo != i {
+                rec.write(lo, item);
+                rec.note(format!("Binary insertion puts {} at a[{}]", item.key, lo));
+            }
+        }
+    }
+
+    /// Merges a[lo..mid] and a[mid..hi]. The left run is copied to the buffer and the
+    /// merge writes back over the array from the left.
+    fn merge(rec: &mut Recorder, lo: usize, mid: usize, hi: usize) {
+        rec.mark(lo..hi, format!("Merge runs a[{}..{}] and a[{}..{}]", lo, mid, mid, hi));
+        for i in lo..mid {
+            let item = rec.items[i];
+            rec.put_in_buffer(i - lo, item);
+        }
+
+        let (mut a, mut b, mut k) = (0, mid, lo);
+        let left_len = mid - lo;
+        let (mut left_wins, mut right_wins) = (0, 0);
+
+        while a < left_len && b < hi {
+            if rec.buffer_first(a, b) {
+                let item = rec.buffered(a);
+                rec.write(k, item);
+                a += 1;
+                left_wins += 1;
+                right_wins = 0;
+            } else {
+                let item = rec.items[b];
+                rec.write(k, item);
+                b += 1;
+                right_wins += 1;
+                left_wins = 0;
+            }
+            k += 1;
+
+            if left_wins >= MIN_GALLOP && a < left_len && b < hi {
+                // Every buffered key <= a[b] can go in one block.
+                let count = Self::gallop(left_len - a, |offset| rec.buffered(a + offset).key <= rec.key(b));
+                rec.mark(
+                    [b],
+                    format!("Left run won {} times in a row, gallop: {} more keys <= {} copy over", left_wins, count, rec.key(b)),
+                );
+                for _ in 0..count {
+                    let item = rec.buffered(a);
+                    rec.write(k, item);
+                    a += 1;
+                    k += 1;
+                }
+                left_wins = 0;
+            } else if right_wins >= MIN_GALLOP && a < left_len && b < hi {
+                // Every right key < the next buffered key can go in one block.
+                let head = rec.buffered(a).key;
+                let count = Self::gallop(hi - b, |offset| rec.key(b + offset) < head);
+                rec.mark(
+                    b..b + count,
+                    format!("Right run won {} times in a row, gallop: {} more keys < {} copy over", right_wins, count, head),
+                );
+                for _ in 0..count {
+                    let item = rec.items[b];
+                    rec.write(k, item);
+                    b += 1;
+                    k += 1;
+                }
+                right_wins = 0;
+            }
+        }
+
+        while a < left_len {
+            let item = rec.buffered(a);
+            rec.write(k, item);
+            a += 1;
+            k += 1;
+        }
+        rec.clear_buffer();
+    }
+
+    /// Number of leading elements (out of `len`) that satisfy `take`, which must be
+    /// true for a prefix and false after it. Probes 1, 3, 7, ... then binary searches
+    /// the last gap, so long blocks cost O(log n) comparisons.
+    fn gallop(len: usize, take: impl Fn(usize) -> bool) -> usize {
+        let (mut lo, mut hi) = (0, 1);
+        while hi <= len && take(hi - 1) {
+            lo = hi;
+            hi = hi * 2 + 1;
+        }
+        let mut hi = hi.min(len + 1) - 1;
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if take(mid) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+
+    /// Merges the runs at `i` and `i + 1` of the stack.
+    fn merge_at(rec: &mut Recorder, runs: &mut Vec<(usize, usize)>, i: usize) {
+        let (start, len) = runs[i];
+        let (_, next_len) = runs[i + 1];
+        Self::merge(rec, start, start + len, start + len + next_len);
+        runs[i] = (start, len + next_len);
+        runs.remove(i + 1);
+    }
+
+    /// Restores the stack invariants |A| > |B| + |C| and |B| > |C| for the top three runs.
+    fn merge_collapse(rec: &mut Recorder, runs: &mut Vec<(usize, usize)>) {
+        while runs.len() > 1 {
+            let mut n = runs.len() - 2;
+            if n > 0 && runs[n - 1].1 <= runs[n].1 + runs[n + 1].1 {
+                if runs[n - 1].1 < runs[n + 1].1 {
+                    n -= 1;
+                }
+                Self::merge_at(rec, runs, n);
+            } else if runs[n].1 <= runs[n + 1].1 {
+                Self::merge_at(rec, runs, n);
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+impl SortKind for Tim {
+    const NAME: &'static str = "Timsort";
+    const STABLE: bool = true;
+
+    fn sort(&self, rec: &mut Recorder) {
+        let n = rec.len();
+        let min_run = min_run(n);
+        let mut runs: Vec<(usize, usize)> = Vec::new();
+        let mut start = 0;
+
+        while start < n {
+            let natural = Self::count_run(rec, start);
+            let len = natural.max(min_run.min(n - start));
+            rec.mark(
+                start..start + natural,
+                format!("Natural run of {} at a[{}], minrun is {}", natural, start, min_run),
+            );
+            if len > natural {
+                Self::binary_insertion(rec, start, start + natural, start + len);
+                rec.mark(start..start + len, format!("Run extended to a[{}..{}]", start, start + len));
+            }
+            runs.push((start, len));
+            Self::merge_collapse(rec, &mut runs);
+            start += len;
+        }
+
+        while runs.len() > 1 {
+            let n = runs.len() - 2;
+            Self::merge_at(rec, &mut runs, n);
+        }
+    }
+}
@@ -0,0 +1,475 @@
+use crate::algorithm::Algorithm;
+use crate::graph::{self, EdgeInput, Graph, GraphStyle};
+use crate::session;
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mark {
+    Unvisited,
+    Queued,
+    Active,
+    Done,
+    OnCycle,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum TopoMethod {
+    Kahn,
+    Dfs,
+}
+
+/// Everything drawn for one step, precomputed so stepping is just moving an index.
+#[derive(Clone, Default)]
+struct Snapshot {
+    marks: HashMap<usize, Mark>,
+    in_degree: HashMap<usize, usize>,
+    frontier: Vec<usize>,
+    order: Vec<usize>,
+    current: Option<usize>,
+    message: String,
+}
+
+/// What a session keeps: the edited graph and which method runs on it.
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    graph: Graph,
+    method: TopoMethod,
+}
+
+pub struct TopologicalSortVisualizer {
+    graph: Graph,
+    method: TopoMethod,
+    snapshots: Vec<Snapshot>,
+    current_step: usize,
+    cycle: Option<Vec<usize>>,
+    edge_input: EdgeInput,
+}
+
+impl TopologicalSortVisualizer {
+    pub fn new() -> Self {
+        Self {
+            graph: Graph::new(),
+            method: TopoMethod::Kahn,
+            snapshots: Vec::new(),
+            current_step: 0,
+            cycle: None,
+            edge_input: EdgeInput::new(false, false),
+        }
+    }
+
+    fn example_dag() -> Graph {
+        let mut graph = Graph::new();
+        for (from, to) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (5, 2), (5, 4)] {
+            graph::add_edge(&mut graph, from, to, 1);
+        }
+        graph
+    }
+
+    fn example_cyclic() -> Graph {
+        let mut graph = Self::example_dag();
+        graph::add_edge(&mut graph, 4, 1, 1);
+        graph
+    }
+
+    fn sorted_neighbors(&self, node: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> = self
+            .graph
+            .get(&node)
+            .map(|edges| edges.iter().map(|&(n, _)| n).collect())
+            .unwrap_or_default();
+        neighbors.sort_unstable();
+        neighbors
+    }
+
+    fn record(&mut self, snapshot: &Snapshot, message: String) {
+        let mut snapshot = snapshot.clone();
+        snapshot.message = message;
+        self.snapshots.push(snapshot);
+    }
+
+    fn generate_steps(&mut self) {
+        self.snapshots.clear();
+        self.current_step = 0;
+        self.cycle = None;
+        match self.method {
+            TopoMethod::Kahn => self.generate_kahn(),
+            TopoMethod::Dfs => self.generate_dfs(),
+        }
+    }
+
+    fn generate_kahn(&mut self) {
+        let nodes = graph::nodes(&self.graph);
+        let mut state = Snapshot::default();
+        for &node in &nodes {
+            state.marks.insert(node, Mark::Unvisited);
+            state.in_degree.insert(node, 0);
+        }
+        for edges in self.graph.values() {
+            for &(to, _) in edges {
+                *state.in_degree.get_mut(&to).unwrap() += 1;
+            }
+        }
+
+        let mut queue: VecDeque<usize> = nodes.iter().copied().filter(|n| state.in_degree[n] == 0).collect();
+        for node in &queue {
+            state.marks.insert(*node, Mark::Queued);
+        }
+        state.frontier = queue.iter().copied().collect();
+        self.record(&state, format!("Nodes with in-degree 0 start in the queue: {:?}", state.frontier));
+
+        while let Some(node) = queue.pop_front() {
+            state.current = Some(node);
+            state.order.push(node);
+            state.marks.insert(node, Mark::Done);
+
+            let mut freed = Vec::new();
+            for neighbor in self.sorted_neighbors(node) {
+                let degree = state.in_degree.get_mut(&neighbor).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(neighbor);
+                    state.marks.insert(neighbor, Mark::Queued);
+                    freed.push(neighbor);
+                }
+            }
+            state.frontier = queue.iter().copied().collect();
+            self.record(
+                &state,
+                format!("Dequeued {} and removed its out-edges, newly free: {:?}", node, freed),
+            );
+        }
+
+        state.current = None;
+        if state.order.len() < nodes.len() {
+            let remaining: HashSet<usize> = nodes.iter().copied().filter(|n| state.in_degree[n] > 0).collect();
+            let cycle = self.find_cycle(&remaining);
+            for node in &cycle {
+                state.marks.insert(*node, Mark::OnCycle);
+            }
+            self.record(
+                &state,
+                format!(
+                    "{} nodes never reached in-degree 0, cycle {} makes a topological order impossible",
+                    remaining.len(),
+                    Self::format_cycle(&cycle)
+                ),
+            );
+            self.cycle = Some(cycle);
+        } else {
+            self.record(&state, format!("Topological order: {:?}", state.order));
+        }
+    }
+
+    fn generate_dfs(&mut self) {
+        let nodes = graph::nodes(&self.graph);
+        let mut state = Snapshot::default();
+        for &node in &nodes {
+            state.marks.insert(node, Mark::Unvisited);
+        }
+        let mut finished = Vec::new();
+
+        for &root in &nodes {
+            if state.marks[&root] != Mark::Unvisited {
+                continue;
+            }
+            let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+            state.marks.insert(root, Mark::Active);
+            state.current = Some(root);
+            state.frontier = vec![root];
+            self.record(&state, format!("Start a depth-first search at {}", root));
+
+            while let Some(&(node, next)) = stack.last() {
+                let neighbors = self.sorted_neighbors(node);
+                if next < neighbors.len() {
+                    stack.last_mut().unwrap().1 += 1;
+                    let neighbor = neighbors[next];
+                    match state.marks[&neighbor] {
+                        Mark::Unvisited => {
+                            stack.push((neighbor, 0));
+                            state.marks.insert(neighbor, Mark::Active);
+                            state.current = Some(neighbor);
+                            state.frontier = stack.iter().map(|&(n, _)| n).collect();
+                            self.record(&state, format!("{} -> {}: descend into {}", node, neighbor, neighbor));
+                        }
+                        Mark::Active => {
+                            // A back edge to a node still on the stack closes a cycle.
+                            let start = stack.iter().position(|&(n, _)| n == neighbor).unwrap();
+                            let cycle: Vec<usize> = stack[start..].iter().map(|&(n, _)| n).collect();
+                            for n in &cycle {
+                                state.marks.insert(*n, Mark::OnCycle);
+                            }
+                            state.current = Some(node);
+                            self.record(
+                                &state,
+                                format!(
+                                    "{} -> {} is a back edge, cycle {} makes a topological order impossible",
+                                    node,
+                                    neighbor,
+                                    Self::format_cycle(&cycle)
+                                ),
+                            );
+                            self.cycle = Some(cycle);
+                            return;
+                        }
+                        _ => {}
+                    }
+                } else {
+                    stack.pop();
+                    state.marks.insert(node, Mark::Done);
+                    finished.push(node);
+                    state.order = finished.iter().rev().copied().collect();
+                    state.frontier = stack.iter().map(|&(n, _)| n).collect();
+                    state.current = Some(node);
+                    self.record(&state, format!("{} finished, prepend it to the order", node));
+                }
+            }
+        }
+
+        state.current = None;
+        self.record(&state, format!("Topological order (reverse finish order): {:?}", state.order));
+    }
+
+    /// Every node in `remaining` has a predecessor in `remaining`, so walking
+    /// predecessors must eventually repeat a node.
+    fn find_cycle(&self, remaining: &HashSet<usize>) -> Vec<usize> {
+        let reversed = graph::transpose(&self.graph);
+        let Some(&start) = remaining.iter().min() else {
+            return Vec::new();
+        };
+        let mut path = vec![start];
+        let mut node = start;
+        loop {
+            node = reversed[&node]
+                .iter()
+                .map(|&(n, _)| n)
+                .filter(|n| remaining.contains(n))
+                .min()
+                .expect("remaining nodes keep a predecessor");
+            if let Some(position) = path.iter().position(|&n| n == node) {
+                let mut cycle = path[position..].to_vec();
+                cycle.reverse();
+                return cycle;
+            }
+            path.push(node);
+        }
+    }
+
+    fn format_cycle(cycle: &[usize]) -> String {
+        let mut parts: Vec<String> = cycle.iter().map(|n| n.to_string()).collect();
+        if let Some(first) = cycle.first() {
+            parts.push(first.to_string());
+        }
+        parts.join(" -> ")
+    }
+
+    fn current_snapshot(&self) -> Option<&Snapshot> {
+        self.current_step.checked_sub(1).and_then(|i| self.snapshots.get(i))
+    }
+}
+
+impl Algorithm for TopologicalSortVisualizer {
+    fn initialize(&mut self) {
+        self.graph = Self::example_dag();
+        self.generate_steps();
+    }
+
+    fn step(&mut self) {
+        if self.current_step < self.snapshots.len() {
+            self.current_step += 1;
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui.radio_value(&mut self.method, TopoMethod::Kahn, "Kahn (in-degree queue)").changed();
+            changed |= ui.radio_value(&mut self.method, TopoMethod::Dfs, "DFS finish order").changed();
+            ui.separator();
+            if ui.button("Example DAG").clicked() {
+                self.graph = Self::example_dag();
+                changed = true;
+            }
+            if ui.button("Example with Cycle").clicked() {
+                self.graph = Self::example_cyclic();
+                changed = true;
+            }
+        });
+        changed |= graph::edge_editor(ui, &mut self.graph, &mut self.edge_input);
+        if changed {
+            self.generate_steps();
+        }
+
+        let snapshot = self.current_snapshot().cloned().unwrap_or_default();
+        ui.label(format!("Step {}/{}", self.current_step, self.snapshots.len()));
+        ui.label(&snapshot.message);
+        let frontier = if self.method == TopoMethod::Kahn { "Queue" } else { "DFS stack" };
+        ui.label(format!("{}: {:?}", frontier, snapshot.frontier));
+        ui.label(format!("Order so far: {:?}", snapshot.order));
+        let theme = Theme::current(ui.ctx());
+        if let (Some(cycle), true) = (&self.cycle, self.current_step == self.snapshots.len()) {
+            ui.colored_label(theme.color(Role::Rejected), format!("Cycle found: {}", Self::format_cycle(cycle)));
+        }
+
+        let show_degree = self.method == TopoMethod::Kahn;
+        let on_cycle = |n: usize| snapshot.marks.get(&n) == Some(&Mark::OnCycle);
+        graph::draw_graph(
+            ui,
+            &self.graph,
+            GraphStyle {
+                node_color: Box::new(|node| {
+                    if snapshot.current == Some(node) {
+                        return theme.color(Role::Current);
+                    }
+                    theme.color(match snapshot.marks.get(&node).copied().unwrap_or(Mark::Unvisited) {
+                        Mark::Unvisited => Role::Unvisited,
+                        Mark::Queued | Mark::Active => Role::Frontier,
+                        Mark::Done => Role::Visited,
+                        Mark::OnCycle => Role::Rejected,
+                    })
+                }),
+                edge_color: Box::new(|from, to| {
+                    if on_cycle(from) && on_cycle(to) {
+                        theme.color(Role::Rejected)
+                    } else {
+                        theme.line()
+                    }
+                }),
+                node_label: Box::new(|node| {
+                    if show_degree {
+                        snapshot.in_degree.get(&node).map(|d| format!("in {}", d))
+                    } else {
+                        None
+                    }
+                }),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn is_finished(&self) -> bool {
+        self.current_step >= self.snapshots.len()
+    }
+
+    fn reset(&mut self) {
+        self.current_step = 0;
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            graph: self.graph.clone(),
+            method: self.method,
+        })
+    }
+
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        self.graph = inputs.graph;
+        self.method = inputs.method;
+        self.generate_steps();
+        Ok(())
+    }
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        let frontier = if self.method == TopoMethod::Kahn { "Queued" } else { "On the DFS stack" };
+        vec![
+            (Role::Unvisited, "Unvisited"),
+            (Role::Frontier, frontier),
+            (Role::Current, "Current"),
+            (Role::Visited, "Placed in the order"),
+            (Role::Rejected, "On a cycle"),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    /// The order the last step shows, and the cycle if one was reported.
+    fn run(graph: &Graph, method: TopoMethod) -> (Vec<usize>, Option<Vec<usize>>) {
+        let mut visualizer = TopologicalSortVisualizer { graph: graph.clone(), method, ..TopologicalSortVisualizer::new() };
+        visualizer.generate_steps();
+        (visualizer.snapshots.last().unwrap().order.clone(), visualizer.cycle)
+    }
+
+    /// Edges only go forward along a shuffled list of the nodes, so there is no cycle.
+    fn random_dag(rng: &mut StdRng, nodes: usize) -> Graph {
+        let mut rank: Vec<usize> = (0..nodes).collect();
+        rank.shuffle(rng);
+        let mut graph = Graph::new();
+        for &node in &rank {
+            graph.entry(node).or_default();
+        }
+        for i in 0..nodes {
+            for j in i + 1..nodes {
+                if rng.gen_bool(0.3) {
+                    graph::add_edge(&mut graph, rank[i], rank[j], 1);
+                }
+            }
+        }
+        graph
+    }
+
+    fn assert_valid_order(graph: &Graph, order: &[usize]) {
+        assert_eq!(order.len(), graph::nodes(graph).len());
+        let position: HashMap<usize, usize> = order.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        assert_eq!(position.len(), order.len(), "{:?} repeats a node", order);
+        for (from, edges) in graph {
+            for (to, _) in edges {
+                assert!(position[from] < position[to], "{} -> {} is backwards in {:?}", from, to, order);
+            }
+        }
+    }
+
+    fn assert_is_cycle(graph: &Graph, cycle: &[usize]) {
+        assert!(!cycle.is_empty());
+        for (i, from) in cycle.iter().enumerate() {
+            let to = cycle[(i + 1) % cycle.len()];
+            assert!(graph[from].iter().any(|&(n, _)| n == to), "{} -> {} is not an edge of {:?}", from, to, cycle);
+        }
+    }
+
+    #[test]
+    fn kahn_and_dfs_give_valid_orders_of_a_dag() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut graphs = vec![TopologicalSortVisualizer::example_dag()];
+        graphs.extend((1..12).map(|n| random_dag(&mut rng, n)));
+        for graph in graphs {
+            for method in [TopoMethod::Kahn, TopoMethod::Dfs] {
+                let (order, cycle) = run(&graph, method);
+                assert!(cycle.is_none());
+                assert_valid_order(&graph, &order);
+            }
+        }
+    }
+
+    #[test]
+    fn both_methods_report_a_real_cycle() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut graphs = vec![TopologicalSortVisualizer::example_cyclic()];
+        for n in 2..10 {
+            let mut graph = random_dag(&mut rng, n);
+            // Edges both ways between the first and last node make at least a two-node cycle.
+            let (order, _) = run(&graph, TopoMethod::Kahn);
+            graph::add_edge(&mut graph, order[n - 1], order[0], 1);
+            graph::add_edge(&mut graph, order[0], order[n - 1], 1);
+            graphs.push(graph);
+        }
+        let mut self_loop = Graph::new();
+        graph::add_edge(&mut self_loop, 3, 3, 1);
+        graphs.push(self_loop);
+
+        for graph in graphs {
+            for method in [TopoMethod::Kahn, TopoMethod::Dfs] {
+                let (_, cycle) = run(&graph, method);
+                assert_is_cycle(&graph, &cycle.expect("a cyclic graph has no topological order"));
+            }
+        }
+    }
+}
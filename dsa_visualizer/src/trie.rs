@@ -0,0 +1,478 @@
+use crate::algorithm::{rewound_message, Algorithm};
+use crate::pan_zoom;
+use crate::session;
+use crate::theme::{Role, Theme};
+use eframe::egui;
+use egui::{Pos2, Stroke};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+const SEED_WORDS: [&str; 9] = ["tea", "ten", "to", "inn", "in", "tree", "trie", "try", "tent"];
+/// Autocomplete stops after this many words.
+const MAX_SUGGESTIONS: usize = 8;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct TrieNode {
+    letter: char,
+    children: BTreeMap<char, usize>,
+    /// A word ends here.
+    terminal: bool,
+}
+
+/// Arena backed trie, node 0 is the root.
+#[derive(Clone, Serialize, Deserialize)]
+struct Trie {
+    nodes: Vec<TrieNode>,
+}
+
+impl Trie {
+    fn new() -> Self {
+        Self {
+            nodes: vec![TrieNode {
+                letter: ' ',
+                children: BTreeMap::new(),
+                terminal: false,
+            }],
+        }
+    }
+
+    fn child(&self, node: usize, letter: char) -> Option<usize> {
+        self.nodes[node].children.get(&letter).copied()
+    }
+
+    fn add_child(&mut self, node: usize, letter: char) -> usize {
+        self.nodes.push(TrieNode {
+            letter,
+            children: BTreeMap::new(),
+            terminal: false,
+        });
+        let child = self.nodes.len() - 1;
+        self.nodes[node].children.insert(letter, child);
+        child
+    }
+
+    /// False for a trie read from a session file in which some node isn't reached
+    /// from the root exactly once.
+    fn is_consistent(&self) -> bool {
+        let mut reached = vec![false; self.nodes.len()];
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            if node >= self.nodes.len() || reached[node] {
+                return false;
+            }
+            reached[node] = true;
+            stack.extend(self.nodes[node].children.values().copied());
+        }
+        reached.iter().all(|&r| r)
+    }
+
+    fn word_count(&self) -> usize {
+        self.nodes.iter().filter(|node| node.terminal).count()
+    }
+
+    /// Horizontal slot and depth of every node. Leaves take consecutive slots in
+    /// alphabetical order and each parent sits midway over its first and last child.
+    fn layout(&self) -> (Vec<(f32, usize)>, usize) {
+        let mut positions = vec![(0.0, 0); self.nodes.len()];
+        let mut leaves = 0;
+        // Children are pushed in reverse so they pop in alphabetical order; a node is
+        // placed on its second visit, after all of its children.
+        let mut stack = vec![(0, 0, false)];
+        while let Some((node, depth, children_done)) = stack.pop() {
+            let children = &self.nodes[node].children;
+            if children.is_empty() {
+                positions[node] = (leaves as f32, depth);
+                leaves += 1;
+            } else if children_done {
+                let first = positions[*children.values().next().unwrap()].0;
+                let last = positions[*children.values().next_back().unwrap()].0;
+                positions[node] = ((first + last) / 2.0, depth);
+            } else {
+                stack.push((node, depth, true));
+                for &child in children.values().rev() {
+                    stack.push((child, depth + 1, false));
+                }
+            }
+        }
+        (positions, leaves.max(1))
+    }
+}
+
+/// One snapshot shown to the user: the trie, the path walked so far and the node
+/// being looked at.
+#[derive(Clone)]
+struct Frame {
+    trie: Trie,
+    path: Vec<usize>,
+    current: Option<usize>,
+    suggestions: Vec<String>,
+    message: String,
+}
+
+/// A change the user asked for, kept so `reset` can play it again.
+#[derive(Clone, Serialize, Deserialize)]
+enum Operation {
+    Insert(String),
+    Search(String),
+    Autocomplete(String),
+}
+
+/// What a session keeps: the trie the operations started from and the operations, so
+/// a reopened session can replay them the same way `reset` does.
+#[derive(Serialize, Deserialize)]
+struct Inputs {
+    start: Trie,
+    operations: Vec<Operation>,
+}
+
+pub struct TrieVisualizer {
+    trie: Trie,
+    /// The trie before any of `operations`: the seeded one, a cleared one or a restored one.
+    start: Trie,
+    operations: Vec<Operation>,
+    frames: VecDeque<Frame>,
+    shown: Option<Frame>,
+    word_input: String,
+}
+
+impl TrieVisualizer {
+    pub fn new() -> Self {
+        Self {
+            trie: Trie::new(),
+            start: Trie::new(),
+            operations: Vec::new(),
+            frames: VecDeque::new(),
+            shown: None,
+            word_input: String::new(),
+        }
+    }
+
+    fn record(&mut self, path: &[usize], suggestions: &[String], message: String) {
+        self.frames.push_back(Frame {
+            trie: self.trie.clone(),
+            path: path.to_vec(),
+            current: path.last().copied(),
+            suggestions: suggestions.to_vec(),
+            message,
+        });
+    }
+
+    fn insert(&mut self, word: &str) {
+        let mut path = vec![0];
+        self.record(&path, &[], format!("Insert \"{}\", start at the root", word));
+        for (i, letter) in word.chars().enumerate() {
+            let node = *path.last().unwrap();
+            let message = match self.trie.child(node, letter) {
+                Some(child) => {
+                    path.push(child);
+                    format!("\"{}\" already has a '{}' edge, follow it", &word[..byte_offset(word, i)], letter)
+                }
+                None => {
+                    path.push(self.trie.add_child(node, letter));
+                    format!("No '{}' edge yet, add a node for it", letter)
+                }
+            };
+            self.record(&path, &[], message);
+        }
+
+        let node = *path.last().unwrap();
+        let message = if self.trie.nodes[node].terminal {
+            format!("\"{}\" was already in the trie", word)
+        } else {
+            self.trie.nodes[node].terminal = true;
+            format!("Mark the last node as the end of \"{}\"", word)
+        };
+        self.record(&path, &[], message);
+    }
+
+    /// Follows `prefix` from the root, recording each edge. Returns the path, or `None`
+    /// after recording where it fell off the trie.
+    fn walk(&mut self, prefix: &str) -> Option<Vec<usize>> {
+        let mut path = vec![0];
+        self.record(&path, &[], format!("Look up \"{}\", start at the root", prefix));
+        for (i, letter) in prefix.chars().enumerate() {
+            let node = *path.last().unwrap();
+            let walked = &prefix[..byte_offset(prefix, i)];
+            match self.trie.child(node, letter) {
+                Some(child) => {
+                    path.push(child);
+                    self.record(&path, &[], format!("Follow '{}'", letter));
+                }
+                None => {
+                    self.record(&path, &[], format!("\"{}\" has no '{}' edge, so nothing starts with \"{}\"", walked, letter, prefix));
+                    return None;
+                }
+            }
+        }
+        Some(path)
+    }
+
+    fn search(&mut self, word: &str) {
+        let Some(path) = self.walk(word) else {
+            return;
+        };
+        let node = *path.last().unwrap();
+        let message = if self.trie.nodes[node].terminal {
+            format!("Found \"{}\", its last node ends a word", word)
+        } else {
+            format!("\"{}\" is only a prefix, its last node doesn't end a word", word)
+        };
+        self.record(&path, &[], message);
+    }
+
+    /// Walks to the end of `prefix`, then collects words below it depth first in
+    /// alphabetical order, one frame per word found.
+    fn autocomplete(&mut self, prefix: &str) {
+        let Some(path) = self.walk(prefix) else {
+            return;
+        };
+
+        let mut suggestions = Vec::new();
+        let mut stack = vec![(*path.last().unwrap(), prefix.to_string(), path.clone())];
+        while let Some((node, word, node_path)) = stack.pop() {
+            if self.trie.nodes[node].terminal {
+                suggestions.push(word.clone());
+                self.record(&node_path, &suggestions, format!("\"{}\" ends here, suggest it", word));
+                if suggestions.len() == MAX_SUGGESTIONS {
+                    break;
+                }
+            }
+            for (&letter, &child) in self.trie.nodes[node].children.iter().rev() {
+                let mut child_path = node_path.clone();
+                child_path.push(child);
+                stack.push((child, format!("{}{}", word, letter), child_path));
+            }
+        }
+
+        let message = if suggestions.is_empty() {
+            format!("No words start with \"{}\"", prefix)
+        } else {
+            format!("{} completion{} of \"{}\"", suggestions.len(), if suggestions.len() == 1 { "" } else { "s" }, prefix)
+        };
+        self.record(&path, &suggestions, message);
+    }
+
+    fn apply(&mut self, operation: &Operation) {
+        match operation {
+            Operation::Insert(word) => self.insert(word),
+            Operation::Search(word) => self.search(word),
+            Operation::Autocomplete(prefix) => self.autocomplete(prefix),
+        }
+    }
+
+    fn queue(&mut self, operation: Operation) {
+        self.apply(&operation);
+        self.operations.push(operation);
+    }
+
+    /// Makes the current trie the starting point with no operations after it.
+    fn start_here(&mut self, message: String) {
+        self.start = self.trie.clone();
+        self.operations.clear();
+        self.frames.clear();
+        self.show_trie(message);
+    }
+
+    fn show_trie(&mut self, message: String) {
+        self.shown = Some(Frame {
+            trie: self.trie.clone(),
+            path: Vec::new(),
+            current: None,
+            suggestions: Vec::new(),
+            message,
+        });
+    }
+
+    fn render_trie(ui: &mut egui::Ui, frame: &Frame) {
+        let trie = &frame.trie;
+        let (positions, slots) = trie.layout();
+        let depth = positions.iter().map(|&(_, d)| d).max().unwrap_or(0);
+        let node_radius = 16.0;
+        let level_spacing = 60.0;
+        let theme = Theme::current(ui.ctx());
+
+        let size = egui::vec2(ui.available_width(), (depth as f32 + 1.0) * level_spacing + 20.0);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let rect = response.rect;
+        let slot_width = rect.width() / slots as f32;
+        let center = |node: usize| {
+            let (slot, depth) = positions[node];
+            Pos2::new(
+                rect.left() + slot_width * (slot + 0.5),
+                rect.top() + node_radius + 4.0 + depth as f32 * level_spacing,
+            )
+        };
+
+        for (node, trie_node) in trie.nodes.iter().enumerate() {
+            for &child in trie_node.children.values() {
+                let on_path = frame.path.contains(&node) && frame.path.contains(&child);
+                let stroke = if on_path {
+                    Stroke::new(3.0, theme.color(Role::Current))
+                } else {
+                    Stroke::new(1.5, theme.line())
+                };
+                painter.line_segment([center(node), center(child)], stroke);
+            }
+        }
+
+        let font = egui::FontId::new(15.0, egui::FontFamily::Monospace);
+        for (node, trie_node) in trie.nodes.iter().enumerate() {
+            let pos = center(node);
+            let fill = theme.color(if frame.current == Some(node) {
+                Role::Current
+            } else if trie_node.terminal {
+                Role::Solution
+            } else {
+                Role::Unvisited
+            });
+            painter.circle_filled(pos, node_radius, fill);
+            if trie_node.terminal {
+                painter.circle_stroke(pos, node_radius + 3.0, Stroke::new(2.0, theme.color(Role::Solution)));
+            }
+            if frame.path.contains(&node) && frame.current != Some(node) {
+                painter.circle_stroke(pos, node_radius + 1.0, Stroke::new(2.0, theme.color(Role::Current)));
+            }
+            let label = if node == 0 { "*".to_string() } else { trie_node.letter.to_string() };
+            painter.text(pos, egui::Align2::CENTER_CENTER, label, font.clone(), theme.text_on(fill));
+        }
+    }
+}
+
+/// Byte offset of the `chars`-th character, so prefixes of non-ASCII words slice cleanly.
+fn byte_offset(word: &str, chars: usize) -> usize {
+    word.char_indices().nth(chars).map_or(word.len(), |(i, _)| i)
+}
+
+impl Algorithm for TrieVisualizer {
+    fn initialize(&mut self) {
+        self.trie = Trie::new();
+        for word in SEED_WORDS {
+            self.insert(word);
+        }
+        self.start_here("Trie ready, enter a word to insert, search or autocomplete".to_string());
+    }
+
+    fn step(&mut self) {
+        if let Some(frame) = self.frames.pop_front() {
+            self.shown = Some(frame);
+        }
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Word: ");
+            ui.add(egui::TextEdit::singleline(&mut self.word_input).desired_width(120.0));
+
+            let word = self.word_input.trim().to_string();
+            if !word.is_empty() {
+                if ui.button("Insert").clicked() {
+                    self.queue(Operation::Insert(word.clone()));
+                }
+                if ui.button("Search").clicked() {
+                    self.queue(Operation::Search(word.clone()));
+                }
+                if ui.button("Autocomplete").clicked() {
+                    self.queue(Operation::Autocomplete(word));
+                }
+            }
+            if ui.button("Clear").clicked() {
+                self.trie = Trie::new();
+                self.start_here("Cleared the trie".to_string());
+            }
+        });
+
+        let Some(frame) = &self.shown else {
+            return;
+        };
+        ui.label(&frame.message);
+        ui.label(format!(
+            "{} words in {} nodes, pending steps: {}",
+            frame.trie.word_count(),
+            frame.trie.nodes.len(),
+            self.frames.len()
+        ));
+        if !frame.suggestions.is_empty() {
+            ui.label(format!("Suggestions: {}", frame.suggestions.join(", ")));
+        }
+        ui.add_space(8.0);
+        pan_zoom::show(ui, "trie", |ui| Self::render_trie(ui, frame));
+    }
+
+    fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Operations edit the trie as they are queued, so like the trees this goes back
+    /// to the starting trie and queues every operation again.
+    fn reset(&mut self) {
+        self.trie = self.start.clone();
+        self.frames.clear();
+        for operation in self.operations.clone() {
+            self.apply(&operation);
+        }
+        self.shown = Some(Frame {
+            trie: self.start.clone(),
+            path: Vec::new(),
+            current: None,
+            suggestions: Vec::new(),
+            message: rewound_message(self.operations.len()),
+        });
+    }
+
+    fn save_inputs(&self) -> serde_json::Value {
+        session::encode(&Inputs {
+            start: self.start.clone(),
+            operations: self.operations.clone(),
+        })
+    }
+
+    /// Replays the operations without animating them and shows where they ended.
+    fn load_inputs(&mut self, inputs: serde_json::Value) -> Result<(), String> {
+        let inputs: Inputs = session::decode(inputs)?;
+        if !inputs.start.is_consistent() {
+            return Err("the saved trie's links are broken".to_string());
+        }
+        self.start = inputs.start;
+        self.operations = inputs.operations;
+        self.reset();
+        self.frames.clear();
+        self.show_trie(format!("Restored {} words", self.trie.word_count()));
+        Ok(())
+    }
+
+    fn legend(&self) -> Vec<(Role, &'static str)> {
+        vec![(Role::Unvisited, "Prefix"), (Role::Solution, "Ends a word"), (Role::Current, "Current, or on the path")]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggest(visualizer: &mut TrieVisualizer, prefix: &str) -> Vec<String> {
+        visualizer.frames.clear();
+        visualizer.queue(Operation::Autocomplete(prefix.to_string()));
+        visualizer.frames.back().unwrap().suggestions.clone()
+    }
+
+    #[test]
+    fn autocomplete_lists_words_alphabetically_up_to_the_limit() {
+        let mut visualizer = TrieVisualizer::new();
+        visualizer.initialize();
+        let mut words: Vec<String> = SEED_WORDS.iter().map(|word| word.to_string()).collect();
+        for word in ["wj", "wa", "wi", "w", "wb", "wh", "wc", "wg", "wd", "wf", "we", "wee"] {
+            visualizer.queue(Operation::Insert(word.to_string()));
+            words.push(word.to_string());
+        }
+        words.sort();
+
+        for prefix in ["t", "te", "in", "tr", "tent", "w", "we", "x", "tx"] {
+            let expected: Vec<String> =
+                words.iter().filter(|word| word.starts_with(prefix)).take(MAX_SUGGESTIONS).cloned().collect();
+            assert_eq!(suggest(&mut visualizer, prefix), expected, "completions of {:?}", prefix);
+        }
+        assert_eq!(suggest(&mut visualizer, "te"), ["tea", "ten", "tent"]);
+        assert_eq!(suggest(&mut visualizer, "w"), ["w", "wa", "wb", "wc", "wd", "we", "wee", "wf"]);
+        assert!(suggest(&mut visualizer, "x").is_empty());
+    }
+}
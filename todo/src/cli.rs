@@ -0,0 +1,289 @@
+use chrono::NaiveDate;
+use crate::error::TodoError;
+use crate::formats::{Conflict, Format};
+use crate::storage::Backend;
+use crate::todo::enums::{Priority, TaskStatus};
+
+pub const USAGE: &str = "Usage: todo [--file <path>] [--backend json|sqlite] <command> [arguments]
+
+Commands:
+  add <title> [options]           add a pending item
+      --priority low|medium|high  defaults to medium
+      --due YYYY-MM-DD
+      --tag <tag>                 may be given more than once
+      --note <text>
+  get <title>                     show an item and its details
+  list [--status <status>] [--tag <tag>]
+                                  list items, optionally filtered
+  search <text>                   list items with the text in their title or a tag
+  start <title>                   mark an item as in progress
+  block <title>                   mark an item as blocked
+  done <title>                    mark an item as done
+  cancel <title>                  mark an item as cancelled
+  archive <title>                 put away a done or cancelled item
+  reopen <title>                  set an item back to pending
+  delete <title>                  remove an item that isn't archived
+  purge <title>                   remove an archived item for good
+  edit-title <title> --to <new>   rename an item
+  undo                            reverse the last change
+  redo                            apply the last undone change again
+  history <title>                 show who changed an item, when, and how
+  rebuild                         regenerate the store from the event log
+  export --format <format> [--output <file>]
+                                  write every item as csv, markdown or todotxt, to
+                                  standard output unless a file is given
+  import --format <format> [--on-conflict skip|overwrite|rename] <file>
+                                  add the items in a file; a title that is already
+                                  taken is skipped unless told otherwise
+  tui                             browse and edit the list full screen
+  serve [--addr <host:port>]      share the list over HTTP, on 127.0.0.1:8080 by default
+  help                            show this message
+
+Titles may be several words, quoted or not: `todo add buy oat milk`. An item can
+also be named by its id, as shown by list: `todo done #3`.
+Statuses: pending, in-progress, blocked, done, cancelled, archived. Blocked items
+have to be unblocked with start or reopen before they can be done, and only done or
+cancelled items can be archived.
+Items are kept in --file, else $TODO_FILE, else state.json (or todo.db for sqlite)
+in the user's data directory, such as ~/.local/share/todo. The backend is --backend,
+else $TODO_BACKEND, else sqlite for a .db, .sqlite or .sqlite3 file and json otherwise.
+The server speaks JSON: GET and POST /items, and GET, PATCH and DELETE
+/items/{title}. A PATCH body may set \"title\" and \"status\".
+Every change is appended to an event log next to the store (<file>.log, or a
+table in the database), and undo and redo are logged the same way. Undo reverses
+that log; what used to be `undo <title>` is now `reopen <title>`.
+Exit codes: 0 on success, 1 when the command failed, 2 for a usage error.";
+
+/// Everything `add` can set besides the title.
+#[derive(Default)]
+pub struct Details {
+    pub priority: Priority,
+    pub due: Option<NaiveDate>,
+    pub tags: Vec<String>,
+    pub notes: String,
+}
+
+/// What to do with a single item, handed to `processes::process_input`.
+pub enum Action {
+    Add(Details),
+    Get,
+    /// Checked against `TaskStatus::next` before anything is written.
+    SetStatus(TaskStatus),
+    Delete,
+    Purge,
+    EditTitle(String),
+}
+
+impl Action {
+    /// The command's name as written to the event log.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Add(_) => "add",
+            Action::Get => "get",
+            Action::SetStatus(TaskStatus::Pending) => "reopen",
+            Action::SetStatus(TaskStatus::InProgress) => "start",
+            Action::SetStatus(TaskStatus::Blocked) => "block",
+            Action::SetStatus(TaskStatus::Done) => "done",
+            Action::SetStatus(TaskStatus::Cancelled) => "cancel",
+            Action::SetStatus(TaskStatus::Archived) => "archive",
+            Action::Delete => "delete",
+            Action::Purge => "purge",
+            Action::EditTitle(_) => "edit-title",
+        }
+    }
+}
+
+pub enum Command {
+    Help,
+    Undo,
+    Redo,
+    History { title: String },
+    Rebuild,
+    Tui,
+    Export { format: Format, output: Option<String> },
+    Import { format: Format, conflict: Conflict, path: String },
+    Serve { addr: String },
+    List { status: Option<TaskStatus>, tag: Option<String>, text: Option<String> },
+    Item { title: String, action: Action },
+}
+
+/// Options given before the command, choosing where items are stored.
+#[derive(Default)]
+pub struct Globals {
+    pub file: Option<String>,
+    pub backend: Option<Backend>,
+}
+
+/// Takes the global options off the front of the arguments and returns the rest.
+pub fn parse_globals(mut args: &[String]) -> Result<(Globals, &[String]), String> {
+    let mut globals = Globals::default();
+    while let [flag, rest @ ..] = args {
+        let value = match (flag.as_str(), rest) {
+            ("--file" | "--backend", [value, ..]) => value,
+            ("--file" | "--backend", []) => return Err(format!("{} needs a value", flag)),
+            _ => break,
+        };
+        match flag.as_str() {
+            "--file" => globals.file = Some(value.clone()),
+            _ => globals.backend = Some(value.parse()?),
+        }
+        args = &rest[1..];
+    }
+    Ok((globals, args))
+}
+
+/// Parses the arguments after the program name and global options.
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err("no command given".to_string());
+    };
+    if rest.iter().any(|arg| arg == "--help" || arg == "-h") {
+        return Ok(Command::Help);
+    }
+
+    let action = match command.as_str() {
+        "help" | "--help" | "-h" => return Ok(Command::Help),
+        "list" => return parse_list(rest),
+        "search" if rest.is_empty() => return Err("search needs the text to look for".to_string()),
+        "search" => return Ok(Command::List { status: None, tag: None, text: Some(rest.join(" ")) }),
+        "serve" => return parse_serve(rest),
+        "export" => return parse_export(rest),
+        "import" => return parse_import(rest),
+        "tui" if !rest.is_empty() => return Err("tui takes no arguments".to_string()),
+        "tui" => return Ok(Command::Tui),
+        "undo" if !rest.is_empty() => {
+            return Err("undo takes no arguments, `undo <title>` is now `reopen <title>`".to_string())
+        }
+        "redo" | "rebuild" if !rest.is_empty() => return Err(format!("{} takes no arguments", command)),
+        "undo" => return Ok(Command::Undo),
+        "redo" => return Ok(Command::Redo),
+        "rebuild" => return Ok(Command::Rebuild),
+        "history" => return Ok(Command::History { title: title(command, rest)? }),
+        "add" => return parse_add(rest),
+        "get" => Action::Get,
+        "start" => Action::SetStatus(TaskStatus::InProgress),
+        "block" => Action::SetStatus(TaskStatus::Blocked),
+        "done" => Action::SetStatus(TaskStatus::Done),
+        "cancel" => Action::SetStatus(TaskStatus::Cancelled),
+        "archive" => Action::SetStatus(TaskStatus::Archived),
+        "reopen" => Action::SetStatus(TaskStatus::Pending),
+        "delete" => Action::Delete,
+        "purge" => Action::Purge,
+        "edit-title" => return parse_edit_title(rest),
+        other => return Err(format!("unknown command \"{}\"", other)),
+    };
+    Ok(Command::Item { title: title(command, rest)?, action })
+}
+
+fn parse_list(args: &[String]) -> Result<Command, String> {
+    let mut status = None;
+    let mut tag = None;
+    for (flag, value) in options("list", args)? {
+        match flag {
+            "--status" => status = Some(value.parse().map_err(|e: TodoError| e.to_string())?),
+            "--tag" => tag = Some(value.to_string()),
+            other => return Err(format!("unknown option \"{}\" for list", other)),
+        }
+    }
+    Ok(Command::List { status, tag, text: None })
+}
+
+fn parse_export(args: &[String]) -> Result<Command, String> {
+    let mut format = None;
+    let mut output = None;
+    for (flag, value) in options("export", args)? {
+        match flag {
+            "--format" => format = Some(value.parse()?),
+            "--output" => output = Some(value.to_string()),
+            other => return Err(format!("unknown option \"{}\" for export", other)),
+        }
+    }
+    let format = format.ok_or("export needs --format csv, markdown or todotxt")?;
+    Ok(Command::Export { format, output })
+}
+
+/// `import --format csv [--on-conflict rename] list.csv`, options and file in any order.
+fn parse_import(args: &[String]) -> Result<Command, String> {
+    let mut format = None;
+    let mut conflict = Conflict::default();
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--format" => format = Some(value()?.parse()?),
+            "--on-conflict" => conflict = value()?.parse()?,
+            other if other.starts_with("--") => return Err(format!("unknown option \"{}\" for import", other)),
+            other if path.is_none() => path = Some(other.to_string()),
+            other => return Err(format!("unexpected argument \"{}\" for import", other)),
+        }
+    }
+    let format = format.ok_or("import needs --format csv, markdown or todotxt")?;
+    let path = path.ok_or("import needs the file to read")?;
+    Ok(Command::Import { format, conflict, path })
+}
+
+fn parse_serve(args: &[String]) -> Result<Command, String> {
+    let mut addr = "127.0.0.1:8080".to_string();
+    for (flag, value) in options("serve", args)? {
+        match flag {
+            "--addr" => addr = value.to_string(),
+            other => return Err(format!("unknown option \"{}\" for serve", other)),
+        }
+    }
+    Ok(Command::Serve { addr })
+}
+
+/// `add buy oat milk --priority high --tag shop`: the title runs up to the first option.
+fn parse_add(args: &[String]) -> Result<Command, String> {
+    let end = args.iter().position(|arg| arg.starts_with("--")).unwrap_or(args.len());
+    let mut details = Details::default();
+    for (flag, value) in options("add", &args[end..])? {
+        match flag {
+            "--priority" => details.priority = value.parse()?,
+            "--due" => {
+                let due = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map_err(|_| format!("due date \"{}\" should look like 2024-05-31", value))?;
+                details.due = Some(due);
+            }
+            "--tag" => details.tags.push(value.to_string()),
+            "--note" => details.notes = value.to_string(),
+            other => return Err(format!("unknown option \"{}\" for add", other)),
+        }
+    }
+    Ok(Command::Item { title: title("add", &args[..end])?, action: Action::Add(details) })
+}
+
+/// Pairs up `--flag value` arguments.
+fn options<'a>(command: &str, args: &'a [String]) -> Result<Vec<(&'a str, &'a str)>, String> {
+    args.chunks(2)
+        .map(|pair| match pair {
+            [flag, value] if flag.starts_with("--") => Ok((flag.as_str(), value.as_str())),
+            [flag] if flag.starts_with("--") => Err(format!("{} needs a value", flag)),
+            [other, ..] => Err(format!("unexpected argument \"{}\" for {}", other, command)),
+            [] => unreachable!("chunks are never empty"),
+        })
+        .collect()
+}
+
+/// `edit-title old words --to new words`, or just two quoted titles.
+fn parse_edit_title(args: &[String]) -> Result<Command, String> {
+    let (old, new) = match args.iter().position(|arg| arg == "--to") {
+        Some(to) => (&args[..to], &args[to + 1..]),
+        None if args.len() == 2 => (&args[..1], &args[1..]),
+        None => return Err("edit-title needs the current title, --to, and the new title".to_string()),
+    };
+    Ok(Command::Item {
+        title: title("edit-title", old)?,
+        action: Action::EditTitle(title("edit-title", new)?),
+    })
+}
+
+/// Joins the words of a title given without quotes.
+fn title(command: &str, words: &[String]) -> Result<String, String> {
+    let title = words.join(" ");
+    if title.trim().is_empty() {
+        return Err(format!("{} needs a title", command));
+    }
+    Ok(title.trim().to_string())
+}
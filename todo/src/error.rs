@@ -0,0 +1,79 @@
+use std::fmt;
+use std::io;
+use crate::todo::enums::TaskStatus;
+
+/// Everything that can go wrong between the command line and the store.
+#[derive(Debug)]
+pub enum TodoError {
+    /// The store doesn't exist yet. `state::open` creates it instead of failing.
+    MissingFile(String),
+    /// The store exists but isn't valid JSON in a schema we know.
+    CorruptJson { path: String, message: String },
+    /// The store was written by a newer build with a schema this one can't read.
+    NewerSchema { path: String, version: u64 },
+    /// Reading, writing or backing up the store failed.
+    Io { path: String, source: io::Error },
+    /// The SQLite store couldn't be opened, read or written, or held a value we can't parse.
+    Database { path: String, message: String },
+    /// A file given to `import` that doesn't parse in the format asked for.
+    Import { path: String, message: String },
+    /// A bad `--backend`, `TODO_BACKEND`, or no place to put the store.
+    Config(String),
+    UnknownStatus(String),
+    /// A title no command could address: blank, or shaped like an id such as `#3`.
+    BadTitle { title: String, reason: &'static str },
+    UnknownItem(String),
+    /// `undo` or `redo` with nothing left in the log to apply.
+    NothingTo(&'static str),
+    ItemExists(String),
+    /// Moving an item to the status it already has.
+    AlreadyInStatus { title: String, status: TaskStatus },
+    /// A move the transition table in `TaskStatus::next` doesn't allow.
+    IllegalTransition { title: String, from: TaskStatus, to: TaskStatus },
+    /// A command the item's struct doesn't implement, such as purging a pending item.
+    NotAllowed { title: String, status: TaskStatus, reason: &'static str },
+}
+
+impl fmt::Display for TodoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TodoError::MissingFile(path) => write!(f, "{} does not exist", path),
+            TodoError::CorruptJson { path, message } => write!(f, "{} is not a valid todo store: {}", path, message),
+            TodoError::NewerSchema { path, version } => {
+                write!(f, "{} uses schema version {}, newer than this build understands", path, version)
+            }
+            TodoError::Io { path, source } => write!(f, "{}: {}", path, source),
+            TodoError::Database { path, message } => write!(f, "{}: {}", path, message),
+            TodoError::Import { path, message } => write!(f, "can't import {}: {}", path, message),
+            TodoError::Config(message) => write!(f, "{}", message),
+            TodoError::UnknownStatus(status) => write!(
+                f,
+                "status {} not supported, expected pending, in-progress, blocked, done, cancelled or archived",
+                status
+            ),
+            TodoError::BadTitle { title, reason } => write!(f, "title {:?} {}", title, reason),
+            TodoError::UnknownItem(title) => write!(f, "Item: {} not found", title),
+            TodoError::NothingTo(verb) => write!(f, "nothing to {}", verb),
+            TodoError::ItemExists(title) => write!(f, "{} already exists", title),
+            TodoError::AlreadyInStatus { title, status } => write!(f, "{} is already {}", title, status),
+            TodoError::IllegalTransition { title, from, to } => {
+                let allowed: Vec<String> = from.next().iter().map(|status| status.to_string()).collect();
+                write!(
+                    f,
+                    "{} can't go from {} to {}, a {} item can only become {}",
+                    title, from, to, from, allowed.join(", ")
+                )
+            }
+            TodoError::NotAllowed { title, status, reason } => write!(f, "{} is {}: {}", title, status, reason),
+        }
+    }
+}
+
+impl std::error::Error for TodoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TodoError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use crate::todo::enums::TaskStatus;
+use crate::todo::structs::base::Base;
+
+/// One line of the CSV. Every column but `title` may be left out when importing, and
+/// tags are separated by semicolons.
+#[derive(Serialize, Deserialize)]
+struct Row {
+    #[serde(default)]
+    id: u64,
+    title: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    priority: String,
+    #[serde(default)]
+    due: Option<NaiveDate>,
+    #[serde(default)]
+    tags: String,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    completed_at: Option<DateTime<Utc>>,
+}
+
+pub fn export(items: &[Base]) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for item in items {
+        let row = Row {
+            id: item.id,
+            title: item.title.clone(),
+            status: item.status.to_string(),
+            priority: item.priority.to_string(),
+            due: item.due,
+            tags: item.tags.join(";"),
+            notes: item.notes.clone(),
+            created_at: Some(item.created_at),
+            completed_at: item.completed_at,
+        };
+        writer.serialize(row).expect("rows serialize to CSV");
+    }
+    String::from_utf8(writer.into_inner().expect("writing to memory can't fail")).expect("CSV is UTF-8")
+}
+
+pub fn parse(text: &str) -> Result<Vec<Base>, String> {
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let mut items = Vec::new();
+    for (n, row) in reader.deserialize::<Row>().enumerate() {
+        // Line 1 is the header.
+        let line = n + 2;
+        let row = row.map_err(|e| format!("line {}: {}", line, e))?;
+        if row.title.trim().is_empty() {
+            return Err(format!("line {}: row without a title", line));
+        }
+        let mut item = Base::new(&row.title);
+        if !row.status.is_empty() {
+            item.status = row.status.parse().map_err(|e| format!("line {}: {}", line, e))?;
+        }
+        if !row.priority.is_empty() {
+            item.priority = row.priority.parse().map_err(|e| format!("line {}: {}", line, e))?;
+        }
+        item.due = row.due;
+        item.tags = row.tags.split(';').filter(|tag| !tag.is_empty()).map(str::to_string).collect();
+        item.notes = row.notes;
+        item.created_at = row.created_at.unwrap_or(item.created_at);
+        item.completed_at = match row.completed_at {
+            None if item.status == TaskStatus::Done => Some(Utc::now()),
+            completed_at => completed_at,
+        };
+        items.push(item);
+    }
+    Ok(items)
+}
@@ -0,0 +1,69 @@
+use chrono::Utc;
+use crate::todo::enums::TaskStatus;
+use crate::todo::structs::base::Base;
+
+// A GitHub style checklist: `- [x] title #tag` for done items and `- [ ] title #tag`
+// for everything else. Only the title, tags and whether it is done survive the trip.
+
+fn is_tag(word: &str) -> bool {
+    word.len() > 1 && word.starts_with('#')
+}
+
+/// Index of the first of the `#word`s that end `words`, `words.len()` if there are none.
+fn first_tag(words: &[&str]) -> usize {
+    words.iter().rposition(|word| !is_tag(word)).map_or(0, |i| i + 1)
+}
+
+pub fn export(items: &[Base]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            let mark = if item.status == TaskStatus::Done { 'x' } else { ' ' };
+            // A title ending in "#12" escapes it as "\#12", which renders the same but
+            // doesn't read back as a tag.
+            let words: Vec<&str> = item.title.split_whitespace().collect();
+            let escaped = first_tag(&words);
+            let title: Vec<String> = words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| if i >= escaped { format!("\\{}", word) } else { word.to_string() })
+                .collect();
+            let tags: String = item.tags.iter().map(|tag| format!(" #{}", tag)).collect();
+            format!("- [{}] {}{}\n", mark, title.join(" "), tags)
+        })
+        .collect()
+}
+
+/// Reads every checklist line, `-`, `*` or `+` bullets alike, and ignores the rest of
+/// the document such as headings and prose.
+pub fn parse(text: &str) -> Result<Vec<Base>, String> {
+    let mut items = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim_start();
+        let Some(rest) = ["- [", "* [", "+ ["].iter().find_map(|bullet| line.strip_prefix(bullet)) else {
+            continue;
+        };
+        let (done, rest) = match rest.split_once("] ") {
+            Some(("x" | "X", rest)) => (true, rest),
+            Some((" ", rest)) => (false, rest),
+            _ => return Err(format!("line {}: expected [ ] or [x] after the bullet", n + 1)),
+        };
+        // Tags are the #words at the end, so a title like "fix #12 report" stays whole,
+        // and "\#12" is a title word written that way so it isn't taken for a tag.
+        let mut words: Vec<&str> = rest.split_whitespace().collect();
+        let tags = words.split_off(first_tag(&words));
+        if words.is_empty() {
+            return Err(format!("line {}: checklist item without a title", n + 1));
+        }
+        let title: Vec<&str> =
+            words.iter().map(|word| word.strip_prefix('\\').filter(|word| is_tag(word)).unwrap_or(word)).collect();
+        let mut item = Base::new(&title.join(" "));
+        item.tags = tags.iter().map(|tag| tag[1..].to_string()).collect();
+        if done {
+            item.status = TaskStatus::Done;
+            item.completed_at = Some(Utc::now());
+        }
+        items.push(item);
+    }
+    Ok(items)
+}
@@ -0,0 +1,220 @@
+pub mod csv;
+pub mod markdown;
+pub mod todotxt;
+
+use std::str::FromStr;
+use crate::error::TodoError;
+use crate::history;
+use crate::state::State;
+use crate::storage::Storage;
+use crate::todo::structs::base::Base;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    Csv,
+    Markdown,
+    TodoTxt,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "markdown" | "md" => Ok(Format::Markdown),
+            "todotxt" | "todo.txt" => Ok(Format::TodoTxt),
+            _ => Err(format!("format {} not supported, expected csv, markdown or todotxt", input)),
+        }
+    }
+}
+
+/// What `import` does with an item whose title is already taken.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Conflict {
+    #[default]
+    Skip,
+    /// Replaces the existing item's details, keeping its id.
+    Overwrite,
+    /// Adds the item as "title (2)", or the first free number after that.
+    Rename,
+}
+
+impl FromStr for Conflict {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "skip" => Ok(Conflict::Skip),
+            "overwrite" => Ok(Conflict::Overwrite),
+            "rename" => Ok(Conflict::Rename),
+            _ => Err(format!("conflict handling {} not supported, expected skip, overwrite or rename", input)),
+        }
+    }
+}
+
+pub fn export(format: Format, items: &[Base]) -> String {
+    match format {
+        Format::Csv => csv::export(items),
+        Format::Markdown => markdown::export(items),
+        Format::TodoTxt => todotxt::export(items),
+    }
+}
+
+/// Reads items from `text`. Their ids are ignored; `import` hands out new ones.
+pub fn parse(format: Format, text: &str) -> Result<Vec<Base>, String> {
+    match format {
+        Format::Csv => csv::parse(text),
+        Format::Markdown => markdown::parse(text),
+        Format::TodoTxt => todotxt::parse(text),
+    }
+}
+
+#[derive(Default, Debug, PartialEq)]
+pub struct Summary {
+    pub added: usize,
+    pub overwritten: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+}
+
+/// Merges `items` into the store in one locked read-modify-write, logging an event
+/// for each item that changed.
+pub fn import(storage: &mut dyn Storage, user: &str, items: Vec<Base>, conflict: Conflict) -> Result<Summary, TodoError> {
+    let _lock = storage.lock()?;
+    let before = storage.load()?;
+    let mut state = before.clone();
+    let mut summary = Summary::default();
+    let mut touched = Vec::new();
+    for item in items {
+        let existing = state.items.iter().position(|existing| existing.title == item.title);
+        match (existing, conflict) {
+            (None, _) => {
+                touched.push(state.insert(item));
+                summary.added += 1;
+            }
+            (Some(_), Conflict::Skip) => summary.skipped += 1,
+            (Some(i), Conflict::Overwrite) => {
+                let id = state.items[i].id;
+                state.items[i] = Base { id, ..item };
+                touched.push(id);
+                summary.overwritten += 1;
+            }
+            (Some(_), Conflict::Rename) => {
+                let title = free_title(&state, &item.title);
+                touched.push(state.insert(Base { title, ..item }));
+                summary.renamed += 1;
+            }
+        }
+    }
+    storage.save(&state)?;
+    for id in touched {
+        history::record(storage, user, "import", &before, &state, id)?;
+    }
+    Ok(summary)
+}
+
+fn free_title(state: &State, title: &str) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", title, n))
+        .find(|candidate| !state.items.iter().any(|item| &item.title == candidate))
+        .expect("some numbered title is free")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use super::*;
+    use crate::storage::memory::Memory;
+    use crate::todo::enums::{Priority, TaskStatus};
+
+    fn sample() -> Vec<Base> {
+        let created = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+        vec![
+            Base { id: 1, tags: vec!["shop".to_string()], created_at: created, ..Base::new("buy milk, oat") },
+            Base {
+                id: 2,
+                status: TaskStatus::Done,
+                priority: Priority::High,
+                due: NaiveDate::from_ymd_opt(2024, 5, 31),
+                tags: vec!["work".to_string(), "q2".to_string()],
+                notes: "send to \"finance\"\non a new line".to_string(),
+                created_at: created,
+                completed_at: Some(Utc.with_ymd_and_hms(2024, 5, 20, 0, 0, 0).unwrap()),
+                ..Base::new("fix #12 report")
+            },
+            Base { id: 3, status: TaskStatus::Blocked, priority: Priority::Low, created_at: created, ..Base::new("call bob") },
+        ]
+    }
+
+    /// What parsing gives back: `import` assigns the ids.
+    fn without_ids(items: Vec<Base>) -> Vec<Base> {
+        items.into_iter().map(|item| Base { id: 0, ..item }).collect()
+    }
+
+    #[test]
+    fn csv_round_trips_everything() {
+        let items = sample();
+        assert_eq!(csv::parse(&csv::export(&items)).unwrap(), without_ids(items));
+    }
+
+    #[test]
+    fn every_format_refuses_an_item_without_a_title() {
+        assert_eq!(csv::parse("title,status\nok,\n  ,DONE\n").unwrap_err(), "line 3: row without a title");
+        assert!(csv::parse("status\nDONE\n").is_err());
+        assert!(todotxt::parse("(A) +work due:2024-05-31").unwrap_err().contains("task without a title"));
+        assert!(markdown::parse("- [ ] #home").unwrap_err().contains("checklist item without a title"));
+    }
+
+    #[test]
+    fn todotxt_round_trips_all_but_notes() {
+        let items = sample();
+        let text = todotxt::export(&items);
+        assert!(text.starts_with("2024-05-01 buy milk, oat +shop\nx 2024-05-20 2024-05-01 fix #12 report"));
+        let expected: Vec<Base> = without_ids(items).into_iter().map(|item| Base { notes: String::new(), ..item }).collect();
+        assert_eq!(todotxt::parse(&text).unwrap(), expected);
+    }
+
+    #[test]
+    fn markdown_keeps_titles_tags_and_done() {
+        let items = sample();
+        let text = markdown::export(&items);
+        assert_eq!(text, "- [ ] buy milk, oat #shop\n- [x] fix #12 report #work #q2\n- [ ] call bob\n");
+        let parsed = markdown::parse(&format!("# List\n\nSome prose.\n{}", text)).unwrap();
+        let summary: Vec<_> = parsed.iter().map(|item| (item.title.as_str(), item.status, item.tags.len())).collect();
+        assert_eq!(
+            summary,
+            vec![("buy milk, oat", TaskStatus::Pending, 1), ("fix #12 report", TaskStatus::Done, 2), ("call bob", TaskStatus::Pending, 0)]
+        );
+        assert!(markdown::parse("- [?] what").is_err());
+
+        // A title ending in what looks like a tag is escaped on the way out.
+        let ends_in_number = vec![Base { tags: vec!["bug".to_string()], ..Base::new("fix #12") }, Base::new("#1 #2")];
+        let text = markdown::export(&ends_in_number);
+        assert_eq!(text, "- [ ] fix \\#12 #bug\n- [ ] \\#1 \\#2\n");
+        let parsed = markdown::parse(&text).unwrap();
+        let summary: Vec<_> = parsed.iter().map(|item| (item.title.as_str(), item.tags.clone())).collect();
+        assert_eq!(summary, vec![("fix #12", vec!["bug".to_string()]), ("#1 #2", vec![])]);
+    }
+
+    #[test]
+    fn import_handles_duplicate_titles() {
+        let mut storage = Memory::default();
+        import(&mut storage, "ann", markdown::parse("- [ ] a\n- [ ] b").unwrap(), Conflict::Skip).unwrap();
+
+        let again = || markdown::parse("- [x] a\n- [ ] c").unwrap();
+        let skipped = import(&mut storage, "ann", again(), Conflict::Skip).unwrap();
+        assert_eq!(skipped, Summary { added: 1, skipped: 1, ..Summary::default() });
+
+        let overwritten = import(&mut storage, "ann", again(), Conflict::Overwrite).unwrap();
+        assert_eq!((overwritten.overwritten, overwritten.added), (2, 0));
+        let state = storage.state.clone().unwrap();
+        assert_eq!(state.find("a").map(|item| (item.id, item.status)), Some((1, TaskStatus::Done)));
+
+        import(&mut storage, "ann", again(), Conflict::Rename).unwrap();
+        import(&mut storage, "ann", again(), Conflict::Rename).unwrap();
+        let titles: Vec<_> = storage.state.unwrap().items.into_iter().map(|item| item.title).collect();
+        assert_eq!(titles, vec!["a", "b", "c", "a (2)", "c (2)", "a (3)", "c (3)"]);
+        assert_eq!(storage.events.len(), 9);
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::error::TodoError;
+use crate::todo::enums::{Priority, TaskStatus};
+use crate::todo::structs::base::Base;
+
+// The todo.txt format (github.com/todotxt/todo.txt): `x` and the completion date for
+// done items, `(A)` for high and `(C)` for low priority, the creation date, then the
+// title with `+tag`s. `@context`s are kept as tags with their `@`. The `due:` and
+// `pri:` extensions are understood, and `status:` carries the states todo.txt has
+// no word for.
+
+fn date(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d").to_string()
+}
+
+fn priority_letter(priority: Priority) -> Option<char> {
+    match priority {
+        Priority::High => Some('A'),
+        Priority::Medium => None,
+        Priority::Low => Some('C'),
+    }
+}
+
+pub fn export(items: &[Base]) -> String {
+    let mut text = String::new();
+    for item in items {
+        let mut words = Vec::new();
+        let done = item.status == TaskStatus::Done;
+        if done {
+            words.push("x".to_string());
+            words.push(date(item.completed_at.unwrap_or(item.created_at)));
+        } else if let Some(letter) = priority_letter(item.priority) {
+            words.push(format!("({})", letter));
+        }
+        words.push(date(item.created_at));
+        words.push(item.title.clone());
+        for tag in &item.tags {
+            words.push(if tag.starts_with('@') { tag.clone() } else { format!("+{}", tag) });
+        }
+        if let Some(due) = item.due {
+            words.push(format!("due:{}", due));
+        }
+        // A completed line has no room for the priority up front.
+        if let (true, Some(letter)) = (done, priority_letter(item.priority)) {
+            words.push(format!("pri:{}", letter));
+        }
+        if !matches!(item.status, TaskStatus::Pending | TaskStatus::Done) {
+            words.push(format!("status:{}", item.status.to_string().to_lowercase().replace('_', "-")));
+        }
+        text.push_str(&words.join(" "));
+        text.push('\n');
+    }
+    text
+}
+
+fn parse_date(word: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(word, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+fn parse_priority(letter: &str) -> Priority {
+    match letter {
+        "A" => Priority::High,
+        "B" => Priority::Medium,
+        _ => Priority::Low,
+    }
+}
+
+pub fn parse(text: &str) -> Result<Vec<Base>, String> {
+    let mut items = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let error = |message: String| format!("line {}: {}", n + 1, message);
+        let mut words = line.split_whitespace().peekable();
+        if words.peek().is_none() {
+            continue;
+        }
+        let done = words.next_if_eq(&"x").is_some();
+        let completed_at = if done { words.next_if(|word| parse_date(word).is_some()).and_then(parse_date) } else { None };
+        let priority = words
+            .next_if(|word| word.len() == 3 && word.starts_with('(') && word.ends_with(')'))
+            .map(|word| parse_priority(&word[1..2]));
+        let created_at = words.next_if(|word| parse_date(word).is_some()).and_then(parse_date);
+
+        let mut item = Base::new("");
+        let mut title = Vec::new();
+        for word in words {
+            match word.split_once(':') {
+                Some(("due", value)) => {
+                    item.due = Some(NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|e| error(e.to_string()))?)
+                }
+                Some(("pri", value)) => item.priority = parse_priority(value),
+                Some(("status", value)) => {
+                    item.status = value.parse().map_err(|e: TodoError| error(e.to_string()))?
+                }
+                _ if word.len() > 1 && word.starts_with('+') => item.tags.push(word[1..].to_string()),
+                _ if word.len() > 1 && word.starts_with('@') => item.tags.push(word.to_string()),
+                _ => title.push(word),
+            }
+        }
+        if title.is_empty() {
+            return Err(error("task without a title".to_string()));
+        }
+        item.title = title.join(" ");
+        item.priority = priority.unwrap_or(item.priority);
+        item.created_at = created_at.unwrap_or(item.created_at);
+        if done && item.status == TaskStatus::Pending {
+            item.status = TaskStatus::Done;
+        }
+        if done {
+            item.completed_at = Some(completed_at.unwrap_or_else(Utc::now));
+        }
+        items.push(item);
+    }
+    Ok(items)
+}
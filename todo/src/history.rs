@@ -0,0 +1,227 @@
+use std::env;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::error::TodoError;
+use crate::state::State;
+use crate::storage::Storage;
+use crate::todo::structs::base::Base;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    /// A command changed the item.
+    #[default]
+    Change,
+    /// The item was already in the store when the log was started.
+    Import,
+    /// Reverses the event at this position in the log.
+    Undo(usize),
+    /// Applies the event at this position again after it was undone.
+    Redo(usize),
+}
+
+/// One entry in the append-only log: the item as it was before and after, `None`
+/// meaning it didn't exist. Replaying every `after` in order rebuilds the store.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Event {
+    pub at: DateTime<Utc>,
+    pub user: String,
+    /// The command that made the change, such as `add` or `done`.
+    pub action: String,
+    pub item: u64,
+    pub before: Option<Base>,
+    pub after: Option<Base>,
+    #[serde(default)]
+    pub kind: Kind,
+}
+
+impl Event {
+    fn new(user: &str, action: &str, item: u64, before: Option<Base>, after: Option<Base>, kind: Kind) -> Self {
+        Event { at: Utc::now(), user: user.to_string(), action: action.to_string(), item, before, after, kind }
+    }
+}
+
+/// Who to record for changes made from this shell.
+pub fn current_user() -> String {
+    env::var("USER").or_else(|_| env::var("USERNAME")).unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Logs the change a command made to item `id`. The first time anything is logged,
+/// the items already in `before` are imported so the log covers the whole store.
+pub fn record(storage: &mut dyn Storage, user: &str, action: &str, before: &State, after: &State, id: u64) -> Result<(), TodoError> {
+    let old = before.items.iter().find(|item| item.id == id).cloned();
+    let new = after.items.iter().find(|item| item.id == id).cloned();
+    if old == new {
+        return Ok(());
+    }
+    if !storage.has_events()? {
+        for item in &before.items {
+            storage.append_event(&Event::new(user, "import", item.id, None, Some(item.clone()), Kind::Import))?;
+        }
+    }
+    storage.append_event(&Event::new(user, action, id, old, new, Kind::Change))
+}
+
+/// Positions of the changes that can be undone, most recent last, and of those that
+/// can be redone. A new change after an undo drops what could have been redone.
+fn stacks(events: &[Event]) -> (Vec<usize>, Vec<usize>) {
+    let mut done = Vec::new();
+    let mut undone = Vec::new();
+    for (i, event) in events.iter().enumerate() {
+        match event.kind {
+            Kind::Import => {}
+            Kind::Change => {
+                done.push(i);
+                undone.clear();
+            }
+            Kind::Undo(_) => undone.extend(done.pop()),
+            Kind::Redo(_) => done.extend(undone.pop()),
+        }
+    }
+    (done, undone)
+}
+
+/// Sets item `id` to `item`, removing it for `None`, and keeps the list in id order.
+fn put(state: &mut State, id: u64, item: Option<Base>) {
+    state.items.retain(|existing| existing.id != id);
+    if let Some(item) = item {
+        let at = state.items.partition_point(|existing| existing.id < id);
+        state.items.insert(at, item);
+    }
+    state.next_id = state.next_id.max(id + 1);
+}
+
+/// Reverses the most recent change, or with `redo` re-applies the most recently
+/// undone one, and logs that as an event of its own. Returns the description printed.
+pub fn step(storage: &mut dyn Storage, user: &str, redo: bool) -> Result<String, TodoError> {
+    let _lock = storage.lock()?;
+    let mut state = storage.load()?;
+    let events = storage.events()?;
+    let (done, undone) = stacks(&events);
+    let (stack, verb) = if redo { (undone, "redo") } else { (done, "undo") };
+    let i = *stack.last().ok_or(TodoError::NothingTo(verb))?;
+    let event = &events[i];
+    let (from, to, kind) = if redo {
+        (event.before.clone(), event.after.clone(), Kind::Redo(i))
+    } else {
+        (event.after.clone(), event.before.clone(), Kind::Undo(i))
+    };
+    put(&mut state, event.item, to.clone());
+    storage.save(&state)?;
+    storage.append_event(&Event::new(user, &event.action, event.item, from.clone(), to.clone(), kind))?;
+    Ok(format!("{} {}: {}", verb, event.action, describe(from.as_ref(), to.as_ref())))
+}
+
+/// The store as the log says it should be.
+pub fn replay(events: &[Event]) -> State {
+    let mut state = State::new();
+    for event in events {
+        put(&mut state, event.item, event.after.clone());
+    }
+    state
+}
+
+/// What changed between two versions of an item, in a few words.
+pub fn describe(before: Option<&Base>, after: Option<&Base>) -> String {
+    let (before, after) = match (before, after) {
+        (None, Some(after)) => return format!("created \"{}\" as {}", after.title, after.status),
+        (Some(before), None) => return format!("removed \"{}\"", before.title),
+        (Some(before), Some(after)) => (before, after),
+        (None, None) => return "nothing".to_string(),
+    };
+    let mut changes = Vec::new();
+    let mut field = |name: &str, old: String, new: String| {
+        if old != new {
+            changes.push(format!("{} {} -> {}", name, old, new));
+        }
+    };
+    let date = |due: Option<chrono::NaiveDate>| due.map_or("none".to_string(), |due| due.to_string());
+    field("title", before.title.clone(), after.title.clone());
+    field("status", before.status.to_string(), after.status.to_string());
+    field("priority", before.priority.to_string(), after.priority.to_string());
+    field("due", date(before.due), date(after.due));
+    field("tags", before.tags.join(","), after.tags.join(","));
+    field("notes", format!("{:?}", before.notes), format!("{:?}", after.notes));
+    if changes.is_empty() {
+        return "no visible change".to_string();
+    }
+    changes.join(", ")
+}
+
+/// Prints every event for the item called `key`, which may have been deleted since.
+pub fn show(key: &str, state: &State, events: &[Event]) -> Result<(), TodoError> {
+    let named = |item: &Option<Base>| item.as_ref().is_some_and(|item| item.title == key);
+    let id = match state.find(key) {
+        Some(item) => item.id,
+        None => events
+            .iter()
+            .rev()
+            .find(|event| named(&event.before) || named(&event.after))
+            .map(|event| event.item)
+            .ok_or_else(|| TodoError::UnknownItem(key.to_string()))?,
+    };
+    println!("History of #{} {}", id, key);
+    for event in events.iter().filter(|event| event.item == id) {
+        let action = match event.kind {
+            Kind::Change | Kind::Import => event.action.clone(),
+            Kind::Undo(_) => format!("undo {}", event.action),
+            Kind::Redo(_) => format!("redo {}", event.action),
+        };
+        println!(
+            "{}  {:<10} {:<16} {}",
+            event.at.format("%Y-%m-%d %H:%M:%S"),
+            event.user,
+            action,
+            describe(event.before.as_ref(), event.after.as_ref())
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Action, Details};
+    use crate::processes::run_item;
+    use crate::storage::memory::Memory;
+    use crate::todo::enums::TaskStatus;
+
+    fn titles(storage: &Memory) -> Vec<String> {
+        storage.state.as_ref().unwrap().items.iter().map(|item| item.title.clone()).collect()
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_log() {
+        let mut storage = Memory::default();
+        run_item("a", &Action::Add(Details::default()), &mut storage, "ann").unwrap();
+        run_item("b", &Action::Add(Details::default()), &mut storage, "ann").unwrap();
+        run_item("a", &Action::Delete, &mut storage, "bob").unwrap();
+
+        step(&mut storage, "bob", false).unwrap();
+        step(&mut storage, "bob", false).unwrap();
+        assert_eq!(titles(&storage), vec!["a"]);
+        step(&mut storage, "bob", true).unwrap();
+        assert_eq!(titles(&storage), vec!["a", "b"]);
+
+        // A new change drops what was left to redo.
+        run_item("b", &Action::SetStatus(TaskStatus::Done), &mut storage, "ann").unwrap();
+        assert!(matches!(step(&mut storage, "ann", true), Err(TodoError::NothingTo("redo"))));
+        assert_eq!(storage.events.len(), 7);
+        assert_eq!(storage.events[3].kind, Kind::Undo(2));
+        assert_eq!(storage.events[3].user, "bob");
+    }
+
+    #[test]
+    fn replaying_the_log_gives_the_store() {
+        let mut storage = Memory::default();
+        run_item("a", &Action::Add(Details::default()), &mut storage, "ann").unwrap();
+        run_item("b", &Action::Add(Details::default()), &mut storage, "ann").unwrap();
+        run_item("a", &Action::EditTitle("c".to_string()), &mut storage, "ann").unwrap();
+        run_item("b", &Action::SetStatus(TaskStatus::Cancelled), &mut storage, "ann").unwrap();
+        step(&mut storage, "ann", false).unwrap();
+        run_item("b", &Action::Delete, &mut storage, "ann").unwrap();
+
+        let replayed = replay(&storage.events);
+        assert_eq!(Some(replayed), storage.state);
+    }
+}
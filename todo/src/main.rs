@@ -1,30 +1,118 @@
+mod cli;
+mod error;
 mod state;
 mod todo;
 mod processes;
+mod storage;
+mod server;
+mod history;
+mod formats;
+mod tui;
 use std::env;
-use serde_json::value::Value;
-use serde_json::Map;
-use state::read_file;
-use todo::to_do_factory;
-use todo::enums::TaskStatus;
-use processes::process_input;
+use std::fs;
+use std::process::ExitCode;
+use cli::{Command, Globals};
+use error::TodoError;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let command: &String = &args[1];
-    let title: &String = &args[2];
-    let state: Map<String, Value> = read_file("./state.json");
-
-    let status: String;
-    match &state.get(*&title) {
-        Some(res) => {
-            status = res.to_string().replace('\"', "");
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let parsed = cli::parse_globals(&args).and_then(|(globals, rest)| Ok((globals, cli::parse(rest)?)));
+    let (globals, command) = match parsed {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, cli::USAGE);
+            return ExitCode::from(2);
         }
-        None => {
-            status = "pending".to_owned();
+    };
+
+    match run(globals, command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
         }
     }
+}
 
-    let item = to_do_factory(title, TaskStatus::from_string(status.to_uppercase()));
-    process_input(item, command.to_string(), &state);
-}
\ No newline at end of file
+fn run(globals: Globals, command: Command) -> Result<(), TodoError> {
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        Command::Serve { addr } => {
+            let storage = storage::open(globals.file, globals.backend)?;
+            actix_web::rt::System::new()
+                .block_on(server::serve(&addr, storage))
+                .map_err(|e| TodoError::Io { path: addr, source: e })
+        }
+        Command::List { status, tag, text } => {
+            let mut storage = storage::open(globals.file, globals.backend)?;
+            // Loading may create or migrate the store, so even listing takes the lock.
+            let _lock = storage.lock()?;
+            let state = storage.load()?;
+            processes::list(status, tag.as_deref(), text.as_deref(), &state);
+            Ok(())
+        }
+        Command::Item { title, action } => {
+            let mut storage = storage::open(globals.file, globals.backend)?;
+            println!("{}", processes::run_item(&title, &action, storage.as_mut(), &history::current_user())?);
+            Ok(())
+        }
+        Command::Undo | Command::Redo => {
+            let mut storage = storage::open(globals.file, globals.backend)?;
+            let redo = matches!(command, Command::Redo);
+            println!("{}", history::step(storage.as_mut(), &history::current_user(), redo)?);
+            Ok(())
+        }
+        Command::Tui => {
+            let mut storage = storage::open(globals.file, globals.backend)?;
+            tui::run(storage.as_mut(), &history::current_user())
+        }
+        Command::Export { format, output } => {
+            let mut storage = storage::open(globals.file, globals.backend)?;
+            let _lock = storage.lock()?;
+            let text = formats::export(format, &storage.load()?.items);
+            match output {
+                Some(path) => fs::write(&path, text).map_err(|e| TodoError::Io { path, source: e }),
+                None => {
+                    print!("{}", text);
+                    Ok(())
+                }
+            }
+        }
+        Command::Import { format, conflict, path } => {
+            let text = fs::read_to_string(&path).map_err(|e| TodoError::Io { path: path.clone(), source: e })?;
+            let items = formats::parse(format, &text).map_err(|message| TodoError::Import { path, message })?;
+            let mut storage = storage::open(globals.file, globals.backend)?;
+            let summary = formats::import(storage.as_mut(), &history::current_user(), items, conflict)?;
+            println!(
+                "Imported {} items: {} added, {} overwritten, {} renamed, {} skipped",
+                summary.added + summary.overwritten + summary.renamed,
+                summary.added,
+                summary.overwritten,
+                summary.renamed,
+                summary.skipped
+            );
+            Ok(())
+        }
+        Command::History { title } => {
+            let mut storage = storage::open(globals.file, globals.backend)?;
+            let _lock = storage.lock()?;
+            let state = storage.load()?;
+            history::show(&title, &state, &storage.events()?)
+        }
+        Command::Rebuild => {
+            let mut storage = storage::open(globals.file, globals.backend)?;
+            let _lock = storage.lock()?;
+            let events = storage.events()?;
+            if events.is_empty() {
+                return Err(TodoError::NothingTo("rebuild from, the event log is empty"));
+            }
+            let state = history::replay(&events);
+            storage.save(&state)?;
+            println!("Rebuilt {} items from {} events", state.items.len(), events.len());
+            Ok(())
+        }
+    }
+}
@@ -1,31 +1,108 @@
-use serde_json::Map;
-use serde_json::value::Value;
-use super::todo::ItemTypes;
+use super::cli::Action;
+use super::error::TodoError;
+use super::history;
+use super::state::State;
+use super::storage::Storage;
+use super::todo::{to_do_factory, ItemTypes};
+use super::todo::enums::TaskStatus;
+use super::todo::structs::archived::Archived;
+use super::todo::structs::base::Base;
+use super::todo::structs::blocked::Blocked;
+use super::todo::structs::cancelled::Cancelled;
 use super::todo::structs::done::Done;
+use super::todo::structs::in_progress::InProgress;
 use super::todo::structs::pending::Pending;
 use super::todo::traits::get::Get;
 use super::todo::traits::create::Create;
 use super::todo::traits::delete::Delete;
 use super::todo::traits::edit::Edit;
+use super::todo::traits::purge::Purge;
+use super::todo::traits::transition::Transition;
+
+// A command the item's struct has no trait for.
+fn not_allowed(item: &Base, reason: &'static str) -> Result<String, TodoError> {
+    Err(TodoError::NotAllowed { title: item.title.clone(), status: item.status, reason })
+}
+
+const PURGE_FIRST: &str = "only archived items can be purged, archive it first";
 
 // Accessing our traits in a standardized way for a more scalable approach.
-fn process_pending(item: Pending, command: String, state: &Map<String, Value>) {
+fn process_pending(item: Pending, action: &Action, state: &State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+    let mut state = state.clone();
+    let title = &item.super_struct.title;
+    match action {
+        Action::Get => item.get(title, &state),
+        Action::Add(_) => item.create(&item.super_struct, &mut state, storage),
+        Action::SetStatus(to) => item.set_status(title, *to, &mut state, storage),
+        Action::Delete => item.delete(title, &mut state, storage),
+        Action::EditTitle(new_title) => item.edit_title(title, new_title, &mut state, storage),
+        Action::Purge => not_allowed(&item.super_struct, PURGE_FIRST),
+    }
+}
+
+fn process_in_progress(item: InProgress, action: &Action, state: &State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+    let mut state = state.clone();
+    let title = &item.super_struct.title;
+    match action {
+        Action::Get => item.get(title, &state),
+        Action::SetStatus(to) => item.set_status(title, *to, &mut state, storage),
+        Action::Delete => item.delete(title, &mut state, storage),
+        Action::EditTitle(new_title) => item.edit_title(title, new_title, &mut state, storage),
+        Action::Purge => not_allowed(&item.super_struct, PURGE_FIRST),
+        Action::Add(_) => Err(TodoError::ItemExists(title.clone())),
+    }
+}
+
+fn process_blocked(item: Blocked, action: &Action, state: &State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+    let mut state = state.clone();
+    let title = &item.super_struct.title;
+    match action {
+        Action::Get => item.get(title, &state),
+        Action::SetStatus(to) => item.set_status(title, *to, &mut state, storage),
+        Action::Delete => item.delete(title, &mut state, storage),
+        Action::EditTitle(new_title) => item.edit_title(title, new_title, &mut state, storage),
+        Action::Purge => not_allowed(&item.super_struct, PURGE_FIRST),
+        Action::Add(_) => Err(TodoError::ItemExists(title.clone())),
+    }
+}
+
+fn process_done(item: Done, action: &Action, state: &State, storage: &mut dyn Storage) -> Result<String, TodoError> {
     let mut state = state.clone();
-    match command.as_str() {
-        "get" => item.get(&item.super_struct.title, state),
-        "create" => item.create(&item.super_struct.title, &item.super_struct.status.to_string(), &mut state),
-        "edit" => item.set_to_done(&item.super_struct.title, &mut state),
-        _ => println!("command: {} not supported", command)
+    let title = &item.super_struct.title;
+    match action {
+        Action::Get => item.get(title, &state),
+        Action::Delete => item.delete(title, &mut state, storage),
+        Action::SetStatus(to) => item.set_status(title, *to, &mut state, storage),
+        Action::EditTitle(new_title) => item.edit_title(title, new_title, &mut state, storage),
+        Action::Purge => not_allowed(&item.super_struct, PURGE_FIRST),
+        Action::Add(_) => Err(TodoError::ItemExists(title.clone())),
     }
 }
 
-fn process_done(item:Done, command: String, state: &Map<String, Value>) {
+fn process_cancelled(item: Cancelled, action: &Action, state: &State, storage: &mut dyn Storage) -> Result<String, TodoError> {
     let mut state = state.clone();
-    match command.as_str() {
-        "get" => item.get(&item.super_struct.title, state),
-        "delete" => item.delete(&item.super_struct.title, state),
-        "edit" => item.set_to_pending(&item.super_struct.title, &mut state),
-        _ => println!("Command: {} not supported", command)
+    let title = &item.super_struct.title;
+    match action {
+        Action::Get => item.get(title, &state),
+        Action::Delete => item.delete(title, &mut state, storage),
+        Action::SetStatus(to) => item.set_status(title, *to, &mut state, storage),
+        Action::EditTitle(_) => not_allowed(&item.super_struct, "reopen it before renaming it"),
+        Action::Purge => not_allowed(&item.super_struct, PURGE_FIRST),
+        Action::Add(_) => Err(TodoError::ItemExists(title.clone())),
+    }
+}
+
+// Archived items are kept as a record: they can be looked at, restored or purged.
+fn process_archived(item: Archived, action: &Action, state: &State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+    let mut state = state.clone();
+    let title = &item.super_struct.title;
+    match action {
+        Action::Get => item.get(title, &state),
+        Action::SetStatus(to) => item.set_status(title, *to, &mut state, storage),
+        Action::Purge => item.purge(title, &mut state, storage),
+        Action::Delete => not_allowed(&item.super_struct, "archived items are removed with purge"),
+        Action::EditTitle(_) => not_allowed(&item.super_struct, "reopen it before renaming it"),
+        Action::Add(_) => Err(TodoError::ItemExists(title.clone())),
     }
 }
 
@@ -37,9 +114,208 @@ fn process_done(item:Done, command: String, state: &Map<String, Value>) {
 // we can scale the access to structs by
 // increasing the routes by the entry point. To appreciate this more, we
 // should define our entry point, which this time is a public function
-pub fn process_input(item: ItemTypes, command: String, state: &Map<String, Value>) {
+pub fn process_input(item: ItemTypes, action: &Action, state: &State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+    let title = &item.base().title;
+    match action {
+        Action::Add(_) if state.find(title).is_some() => return Err(TodoError::ItemExists(title.clone())),
+        Action::Add(_) => {}
+        Action::EditTitle(new_title) if state.find(new_title).is_some() => {
+            return Err(TodoError::ItemExists(new_title.clone()))
+        }
+        _ if state.find(title).is_none() => return Err(TodoError::UnknownItem(title.clone())),
+        _ => {}
+    }
+
     match item {
-        ItemTypes::Pending(item) => process_pending(item, command, state),
-        ItemTypes::Done(item) => process_done(item, command, state)
+        ItemTypes::Pending(item) => process_pending(item, action, state, storage),
+        ItemTypes::InProgress(item) => process_in_progress(item, action, state, storage),
+        ItemTypes::Blocked(item) => process_blocked(item, action, state, storage),
+        ItemTypes::Done(item) => process_done(item, action, state, storage),
+        ItemTypes::Cancelled(item) => process_cancelled(item, action, state, storage),
+        ItemTypes::Archived(item) => process_archived(item, action, state, storage)
+    }
+}
+
+// One action against the store as it is now, without the lock or the event log.
+// Returns the message, the state it started from and the id of the item it touched.
+fn apply(title: &str, action: &Action, storage: &mut dyn Storage) -> Result<(String, State, u64), TodoError> {
+    let state = storage.load()?;
+    let base = match (state.find(title), action) {
+        (Some(existing), _) => existing.clone(),
+        (None, Action::Add(details)) => Base {
+            priority: details.priority,
+            due: details.due,
+            tags: details.tags.clone(),
+            notes: details.notes.clone(),
+            ..Base::new(title)
+        },
+        (None, _) => Base::new(title),
+    };
+    // A new item gets the next id when it is added.
+    let id = if base.id == 0 { state.next_id } else { base.id };
+    let message = process_input(to_do_factory(base), action, &state, storage)?;
+    Ok((message, state, id))
+}
+
+// The whole read-modify-write for one item: the lock is held from loading the store
+// until process_input has saved its change and it is in the event log, so a concurrent
+// todo can't slip in between. Returns the message for the user.
+pub fn run_item(title: &str, action: &Action, storage: &mut dyn Storage, user: &str) -> Result<String, TodoError> {
+    run_items(&[(title, action)], storage, user).map(|mut messages| messages.remove(0))
+}
+
+// Several changes as one: they run in order under a single lock, and if one is refused
+// the store is put back as it was before the first, so it never holds only some of
+// them. Nothing is logged until all of them have gone through.
+pub fn run_items(changes: &[(&str, &Action)], storage: &mut dyn Storage, user: &str) -> Result<Vec<String>, TodoError> {
+    let _lock = storage.lock()?;
+    let mut applied = Vec::new();
+    for &(title, action) in changes {
+        match apply(title, action, storage) {
+            Ok(result) => applied.push((action, result)),
+            Err(error) => {
+                if let Some((_, (_, first, _))) = applied.first() {
+                    storage.save(first)?;
+                }
+                return Err(error);
+            }
+        }
+    }
+
+    let mut messages = Vec::new();
+    for (i, (action, (message, before, id))) in applied.iter().enumerate() {
+        if !matches!(action, Action::Get) {
+            let after = match applied.get(i + 1) {
+                Some((_, (_, next, _))) => next.clone(),
+                None => storage.load()?,
+            };
+            history::record(storage, user, action.name(), before, &after, *id)?;
+        }
+        messages.push(message.clone());
     }
-}
\ No newline at end of file
+    Ok(messages)
+}
+
+// Listing isn't about one item, so it reads the state directly. `text` matches part of
+// the title or of a tag, ignoring case; search and the TUI's filter both use it.
+pub fn matching<'a>(status: Option<TaskStatus>, tag: Option<&str>, text: Option<&str>, state: &'a State) -> Vec<&'a Base> {
+    let text = text.map(str::to_lowercase);
+    state
+        .items
+        .iter()
+        .filter(|item| status.is_none_or(|status| item.status == status))
+        .filter(|item| tag.is_none_or(|tag| item.tags.iter().any(|t| t == tag)))
+        .filter(|item| {
+            text.as_deref().is_none_or(|text| {
+                item.title.to_lowercase().contains(text) || item.tags.iter().any(|tag| tag.to_lowercase().contains(text))
+            })
+        })
+        .collect()
+}
+
+pub fn list(status: Option<TaskStatus>, tag: Option<&str>, text: Option<&str>, state: &State) {
+    let items = matching(status, tag, text, state);
+    if items.is_empty() {
+        println!("No items");
+    }
+    for item in items {
+        println!("{}", line(item));
+    }
+}
+
+// One item as a row of the list, shared with the TUI.
+pub fn line(item: &Base) -> String {
+    let due = item.due.map(|due| due.to_string()).unwrap_or_default();
+    let tags: String = item.tags.iter().map(|tag| format!(" +{}", tag)).collect();
+    format!("#{:<4} {:<11} {:<7} {:<10} {}{}", item.id, item.status.to_string(), item.priority.to_string(), due, item.title, tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Details;
+    use crate::storage::memory::Memory;
+
+    fn stored(storage: &Memory, title: &str) -> Option<Base> {
+        storage.state.as_ref().and_then(|state| state.find(title).cloned())
+    }
+
+    #[test]
+    fn add_assigns_ids_and_keeps_details() {
+        let mut storage = Memory::default();
+        run_item("one", &Action::Add(Details::default()), &mut storage, "test").unwrap();
+        let details = Details { tags: vec!["home".to_string()], ..Details::default() };
+        run_item("two", &Action::Add(details), &mut storage, "test").unwrap();
+
+        let two = stored(&storage, "#2").unwrap();
+        assert_eq!(two.title, "two");
+        assert_eq!(two.tags, vec!["home"]);
+        assert!(matches!(
+            run_item("one", &Action::Add(Details::default()), &mut storage, "test"),
+            Err(TodoError::ItemExists(_))
+        ));
+    }
+
+    #[test]
+    fn transitions_follow_the_table() {
+        let mut storage = Memory::default();
+        run_item("job", &Action::Add(Details::default()), &mut storage, "test").unwrap();
+        run_item("job", &Action::SetStatus(TaskStatus::Blocked), &mut storage, "test").unwrap();
+
+        let result = run_item("job", &Action::SetStatus(TaskStatus::Done), &mut storage, "test");
+        assert!(matches!(result, Err(TodoError::IllegalTransition { .. })));
+        assert_eq!(stored(&storage, "job").unwrap().status, TaskStatus::Blocked);
+
+        run_item("job", &Action::SetStatus(TaskStatus::InProgress), &mut storage, "test").unwrap();
+        run_item("job", &Action::SetStatus(TaskStatus::Done), &mut storage, "test").unwrap();
+        assert!(stored(&storage, "job").unwrap().completed_at.is_some());
+    }
+
+    #[test]
+    fn only_archived_items_are_purged() {
+        let mut storage = Memory::default();
+        run_item("old", &Action::Add(Details::default()), &mut storage, "test").unwrap();
+        assert!(matches!(run_item("old", &Action::Purge, &mut storage, "test"), Err(TodoError::NotAllowed { .. })));
+
+        run_item("old", &Action::SetStatus(TaskStatus::Cancelled), &mut storage, "test").unwrap();
+        run_item("old", &Action::SetStatus(TaskStatus::Archived), &mut storage, "test").unwrap();
+        assert!(matches!(run_item("old", &Action::Delete, &mut storage, "test"), Err(TodoError::NotAllowed { .. })));
+        run_item("old", &Action::Purge, &mut storage, "test").unwrap();
+        assert!(stored(&storage, "old").is_none());
+    }
+
+    #[test]
+    fn matching_text_looks_at_titles_and_tags() {
+        let mut storage = Memory::default();
+        run_item("Buy milk", &Action::Add(Details::default()), &mut storage, "test").unwrap();
+        let details = Details { tags: vec!["Shopping".to_string()], ..Details::default() };
+        run_item("bread", &Action::Add(details), &mut storage, "test").unwrap();
+        run_item("call mum", &Action::Add(Details::default()), &mut storage, "test").unwrap();
+
+        let state = storage.state.as_ref().unwrap();
+        let titles = |text| matching(None, None, Some(text), state).iter().map(|item| item.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles("MILK"), vec!["Buy milk"]);
+        assert_eq!(titles("shop"), vec!["bread"]);
+        assert_eq!(titles(""), vec!["Buy milk", "bread", "call mum"]);
+    }
+
+    #[test]
+    fn a_refused_change_undoes_the_ones_before_it() {
+        let mut storage = Memory::default();
+        run_item("a", &Action::Add(Details::default()), &mut storage, "test").unwrap();
+        run_item("b", &Action::Add(Details::default()), &mut storage, "test").unwrap();
+        let logged = storage.events.len();
+
+        let done = Action::SetStatus(TaskStatus::Done);
+        let rename = Action::EditTitle("b".to_string());
+        let result = run_items(&[("a", &done), ("a", &rename)], &mut storage, "test");
+        assert!(matches!(result, Err(TodoError::ItemExists(_))));
+        assert_eq!(stored(&storage, "a").unwrap().status, TaskStatus::Pending);
+        assert_eq!(storage.events.len(), logged);
+
+        let rename = Action::EditTitle("c".to_string());
+        run_items(&[("a", &done), ("a", &rename)], &mut storage, "test").unwrap();
+        assert_eq!(stored(&storage, "c").unwrap().status, TaskStatus::Done);
+        assert_eq!(storage.events.len(), logged + 2);
+    }
+}
@@ -0,0 +1,202 @@
+use std::sync::{Mutex, PoisonError};
+use actix_web::http::StatusCode;
+use actix_web::{error, web, App, HttpRequest, HttpResponse, HttpServer};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::cli::{Action, Details};
+use crate::error::TodoError;
+use crate::processes;
+use crate::state;
+use crate::storage::Storage;
+use crate::todo::enums::{Priority, TaskStatus};
+
+// One store for every worker. The lock inside `run_item` still keeps out other todo
+// processes; this mutex keeps the server's own requests from interleaving.
+type SharedStorage = web::Data<Mutex<Box<dyn Storage>>>;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewItem {
+    title: String,
+    #[serde(default)]
+    priority: Priority,
+    #[serde(default)]
+    due: Option<NaiveDate>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    notes: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemPatch {
+    title: Option<String>,
+    status: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    status: Option<String>,
+    tag: Option<String>,
+}
+
+/// Recorded in the event log: the `X-Todo-User` header if the client sent one, else
+/// the address the request came from.
+fn user(request: &HttpRequest) -> String {
+    let header = request.headers().get("X-Todo-User").and_then(|value| value.to_str().ok());
+    match header {
+        Some(name) => name.to_string(),
+        None => format!("http:{}", request.connection_info().peer_addr().unwrap_or("unknown")),
+    }
+}
+
+/// What a handler answers with. It is worked out on the blocking pool, where an
+/// `HttpResponse` can't be sent from, and turned into one back on the worker.
+enum Reply {
+    Json(StatusCode, Value),
+    NoContent,
+}
+
+fn error_response(error: TodoError) -> HttpResponse {
+    let mut response = match error {
+        TodoError::UnknownItem(_) => HttpResponse::NotFound(),
+        TodoError::ItemExists(_)
+        | TodoError::AlreadyInStatus { .. }
+        | TodoError::IllegalTransition { .. }
+        | TodoError::NotAllowed { .. } => HttpResponse::Conflict(),
+        TodoError::UnknownStatus(_) | TodoError::BadTitle { .. } => HttpResponse::BadRequest(),
+        _ => HttpResponse::InternalServerError(),
+    };
+    response.json(json!({ "error": error.to_string() }))
+}
+
+/// Runs `work` on actix's blocking pool with the store to itself. File and SQLite I/O,
+/// and waiting for a todo command to let go of the lock, would otherwise stall a worker.
+async fn with_storage<F>(storage: SharedStorage, work: F) -> HttpResponse
+where
+    F: FnOnce(&mut dyn Storage) -> Result<Reply, TodoError> + Send + 'static,
+{
+    let result = web::block(move || {
+        let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+        work(storage.as_mut())
+    })
+    .await;
+    match result {
+        Ok(Ok(Reply::Json(status, body))) => HttpResponse::build(status).json(body),
+        Ok(Ok(Reply::NoContent)) => HttpResponse::NoContent().finish(),
+        Ok(Err(error)) => error_response(error),
+        Err(error) => HttpResponse::InternalServerError().json(json!({ "error": error.to_string() })),
+    }
+}
+
+/// Looks the item up again after a change, so the response shows what was stored.
+/// Loading may create or migrate the store, so even reading takes the lock.
+fn stored_item(storage: &mut dyn Storage, key: &str, status: StatusCode) -> Result<Reply, TodoError> {
+    let _lock = storage.lock()?;
+    let state = storage.load()?;
+    let item = state.find(key).ok_or_else(|| TodoError::UnknownItem(key.to_string()))?;
+    Ok(Reply::Json(status, json!(item)))
+}
+
+fn list(storage: &mut dyn Storage, query: &ListQuery) -> Result<Reply, TodoError> {
+    let status: Option<TaskStatus> = query.status.as_deref().map(str::parse).transpose()?;
+    let _lock = storage.lock()?;
+    let state = storage.load()?;
+    Ok(Reply::Json(StatusCode::OK, json!(processes::matching(status, query.tag.as_deref(), None, &state))))
+}
+
+/// Trims a title sent for a new or renamed item, the way the command line does, and
+/// refuses one it couldn't address afterwards.
+fn new_title(title: &str) -> Result<String, TodoError> {
+    let trimmed = title.trim();
+    if trimmed.is_empty() {
+        return Err(TodoError::BadTitle { title: title.to_string(), reason: "is empty" });
+    }
+    if state::parse_id(trimmed).is_some() {
+        return Err(TodoError::BadTitle { title: title.to_string(), reason: "would be read as an item id" });
+    }
+    Ok(trimmed.to_string())
+}
+
+fn create(storage: &mut dyn Storage, user: &str, item: NewItem) -> Result<Reply, TodoError> {
+    let title = new_title(&item.title)?;
+    let details = Details { priority: item.priority, due: item.due, tags: item.tags, notes: item.notes };
+    processes::run_item(&title, &Action::Add(details), storage, user)?;
+    stored_item(storage, &title, StatusCode::CREATED)
+}
+
+/// Applies the status first, then the new title, as one change: if either is refused
+/// neither is kept.
+fn edit(storage: &mut dyn Storage, user: &str, title: &str, patch: &ItemPatch) -> Result<Reply, TodoError> {
+    let status: Option<TaskStatus> = patch.status.as_deref().map(str::parse).transpose()?;
+    let new_title = patch.title.as_deref().map(new_title).transpose()?;
+    let set_status = status.map(Action::SetStatus);
+    let rename = new_title.clone().map(Action::EditTitle);
+    let changes: Vec<(&str, &Action)> = set_status.iter().chain(rename.iter()).map(|action| (title, action)).collect();
+    processes::run_items(&changes, storage, user)?;
+    stored_item(storage, new_title.as_deref().unwrap_or(title), StatusCode::OK)
+}
+
+fn delete(storage: &mut dyn Storage, user: &str, title: &str) -> Result<Reply, TodoError> {
+    processes::run_item(title, &Action::Delete, storage, user)?;
+    Ok(Reply::NoContent)
+}
+
+async fn list_items(storage: SharedStorage, query: web::Query<ListQuery>) -> HttpResponse {
+    with_storage(storage, move |storage| list(storage, &query)).await
+}
+
+async fn create_item(request: HttpRequest, storage: SharedStorage, item: web::Json<NewItem>) -> HttpResponse {
+    let user = user(&request);
+    with_storage(storage, move |storage| create(storage, &user, item.into_inner())).await
+}
+
+async fn get_item(storage: SharedStorage, title: web::Path<String>) -> HttpResponse {
+    with_storage(storage, move |storage| stored_item(storage, &title, StatusCode::OK)).await
+}
+
+async fn edit_item(
+    request: HttpRequest,
+    storage: SharedStorage,
+    title: web::Path<String>,
+    patch: web::Json<ItemPatch>,
+) -> HttpResponse {
+    let user = user(&request);
+    with_storage(storage, move |storage| edit(storage, &user, &title, &patch)).await
+}
+
+async fn delete_item(request: HttpRequest, storage: SharedStorage, title: web::Path<String>) -> HttpResponse {
+    let user = user(&request);
+    with_storage(storage, move |storage| delete(storage, &user, &title)).await
+}
+
+/// Bad JSON bodies get the same `{"error": ...}` shape as every other failure.
+fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|err, _| {
+        let response = HttpResponse::BadRequest().json(json!({ "error": err.to_string() }));
+        error::InternalError::from_response(err, response).into()
+    })
+}
+
+/// Serves the list over HTTP until the process is stopped. Prints the address it
+/// bound, which tells the caller the port when it asked for port 0.
+pub async fn serve(addr: &str, storage: Box<dyn Storage>) -> std::io::Result<()> {
+    let storage = web::Data::new(Mutex::new(storage));
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(storage.clone())
+            .app_data(json_config())
+            .route("/items", web::get().to(list_items))
+            .route("/items", web::post().to(create_item))
+            .route("/items/{title}", web::get().to(get_item))
+            .route("/items/{title}", web::patch().to(edit_item))
+            .route("/items/{title}", web::delete().to(delete_item))
+    })
+    .bind(addr)?;
+    for addr in server.addrs() {
+        println!("todo server listening on http://{}", addr);
+    }
+    server.run().await
+}
@@ -1,22 +1,211 @@
-use std::fs::File;
-use std::fs;
-use std::io::Read;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use serde_json::Map;
 use serde_json::value::Value;
-use serde_json::json;
+use crate::error::TodoError;
+use crate::todo::enums::TaskStatus;
+use crate::todo::structs::base::Base;
 
-pub fn read_file(file_name: &str) -> Map<String, Value> {
-    let mut file = File::open(file_name.to_string()).unwrap();
-    let mut data = String::new();
-    file.read_to_string(&mut data).unwrap();
-    let def = json!({}); // if no state {} exists in file, creates blank state.
-    let json: Value = serde_json::from_str(&data).unwrap_or(def);
-    let state: Map<String, Value> = json.as_object().unwrap().clone();
+/// Format of state.json. Version 1 was a flat object of titles to "PENDING" or "DONE"
+/// with no version field; version 2 keeps a list of items with ids and details.
+pub const SCHEMA_VERSION: u32 = 2;
 
-    return state
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct State {
+    /// Schema the state was read with. Always written as `SCHEMA_VERSION`.
+    pub version: u32,
+    /// Id the next added item gets.
+    pub next_id: u64,
+    pub items: Vec<Base>,
 }
 
-pub fn write_to_file(file_name: &str, state: &mut Map<String, Value>){
-    let new_data = json!(state);
-    fs::write(file_name.to_string(), new_data.to_string()).expect("Unable to write file")
-}
\ No newline at end of file
+impl State {
+    pub fn new() -> Self {
+        State {
+            version: SCHEMA_VERSION,
+            next_id: 1,
+            items: Vec::new(),
+        }
+    }
+
+    /// Finds an item by title, or by id written as `#3`.
+    pub fn find(&self, key: &str) -> Option<&Base> {
+        self.position(key).map(|i| &self.items[i])
+    }
+
+    pub fn find_mut(&mut self, key: &str) -> Option<&mut Base> {
+        self.position(key).map(|i| &mut self.items[i])
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        let id = parse_id(key);
+        self.items.iter().position(|item| item.title == key || Some(item.id) == id)
+    }
+
+    /// Adds `base` under the next id and returns that id.
+    pub fn insert(&mut self, base: Base) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.items.push(Base { id, ..base });
+        id
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Base> {
+        self.position(key).map(|i| self.items.remove(i))
+    }
+}
+
+/// The id in a key written as `#3`, which `find` looks up before titles.
+pub fn parse_id(key: &str) -> Option<u64> {
+    key.strip_prefix('#').and_then(|id| id.parse().ok())
+}
+
+/// Reads state.json, converting a version 1 file in memory. The result keeps the
+/// version it was read with so `open` knows to write the migration back.
+pub fn read_file(file_name: &str) -> Result<State, TodoError> {
+    let data = fs::read_to_string(file_name).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => TodoError::MissingFile(file_name.to_string()),
+        _ => TodoError::Io { path: file_name.to_string(), source: e },
+    })?;
+    // An empty file is a blank state, as if it had just been created.
+    if data.trim().is_empty() {
+        return Ok(State::new());
+    }
+    let corrupt = |message: String| TodoError::CorruptJson { path: file_name.to_string(), message };
+    let json: Value = serde_json::from_str(&data).map_err(|e| corrupt(e.to_string()))?;
+    // A version 1 file is a flat map of titles, so it can hold an item titled "version"
+    // too; only a numbered version alongside a list of items marks a newer file.
+    let version = json.get("version").and_then(Value::as_u64).filter(|_| json.get("items").is_some_and(Value::is_array));
+    match version {
+        None => match json {
+            Value::Object(map) => migrate_v1(map),
+            other => Err(corrupt(format!("expected an object of items, found {}", other))),
+        },
+        Some(version) if version > SCHEMA_VERSION as u64 => {
+            Err(TodoError::NewerSchema { path: file_name.to_string(), version })
+        }
+        Some(_) => serde_json::from_value(json).map_err(|e| corrupt(e.to_string())),
+    }
+}
+
+/// Turns `{"title": "PENDING", ...}` into items numbered in title order. When they were
+/// created or completed wasn't recorded, so both are taken as now.
+fn migrate_v1(map: Map<String, Value>) -> Result<State, TodoError> {
+    let mut state = State::new();
+    let now = Utc::now();
+    for (title, status) in map {
+        let status: TaskStatus = status.as_str().unwrap_or_default().parse()?;
+        state.insert(Base {
+            status,
+            completed_at: (status == TaskStatus::Done).then_some(now),
+            ..Base::new(&title)
+        });
+    }
+    state.version = 1;
+    Ok(state)
+}
+
+/// Writes the whole state to a temporary file next to `file_name` and renames it over
+/// the store, so a crash part way through leaves the old store rather than half a new one.
+pub fn write_to_file(file_name: &str, state: &State) -> Result<(), TodoError> {
+    let state = State { version: SCHEMA_VERSION, ..state.clone() };
+    let new_data = serde_json::to_string_pretty(&state).expect("state serializes to JSON");
+    let temp = format!("{}.{}.tmp", file_name, std::process::id());
+    let io_error = |path: &str, source| TodoError::Io { path: path.to_string(), source };
+    let mut file = File::create(&temp).map_err(|e| io_error(&temp, e))?;
+    file.write_all(new_data.as_bytes())
+        .and_then(|()| file.sync_all())
+        .map_err(|e| io_error(&temp, e))?;
+    fs::rename(&temp, file_name).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        io_error(file_name, e)
+    })
+}
+
+/// Takes an exclusive advisory lock on `<file>.lock`, blocking until any other todo
+/// process lets go. Hold the returned file for the whole read-modify-write; dropping it
+/// releases the lock. The store itself can't carry the lock because every write
+/// replaces it with a new file.
+pub fn lock(file_name: &str) -> Result<File, TodoError> {
+    let path = format!("{}.lock", file_name);
+    let io_error = |source| TodoError::Io { path: path.clone(), source };
+    let file = OpenOptions::new().create(true).truncate(false).write(true).open(&path).map_err(io_error)?;
+    file.lock().map_err(io_error)?;
+    Ok(file)
+}
+
+/// Reads the store, creating an empty one the first time. A corrupt store is moved
+/// aside to `<file>.<unix time>.bak`, numbered if that is taken, so nothing is lost,
+/// and replaced by an empty one. An older schema is migrated and written back, with
+/// the original kept the same way.
+pub fn open(file_name: &str) -> Result<State, TodoError> {
+    match read_file(file_name) {
+        Err(TodoError::MissingFile(_)) => {
+            let state = State::new();
+            write_to_file(file_name, &state)?;
+            Ok(state)
+        }
+        Err(error @ TodoError::CorruptJson { .. }) => {
+            let backup = back_up(file_name)?;
+            eprintln!("warning: {}\nwarning: moved it to {} and started an empty list", error, backup);
+            let state = State::new();
+            write_to_file(file_name, &state)?;
+            Ok(state)
+        }
+        Ok(state) if state.version < SCHEMA_VERSION => {
+            let backup = back_up(file_name)?;
+            write_to_file(file_name, &state)?;
+            eprintln!(
+                "note: migrated {} from schema version {} to {}, the original is in {}",
+                file_name, state.version, SCHEMA_VERSION, backup
+            );
+            Ok(State { version: SCHEMA_VERSION, ..state })
+        }
+        result => result,
+    }
+}
+
+/// Moves `file_name` to the first free backup name and returns it.
+fn back_up(file_name: &str) -> Result<String, TodoError> {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let backup = (0..)
+        .map(|n| match n {
+            0 => format!("{}.{}.bak", file_name, seconds),
+            n => format!("{}.{}-{}.bak", file_name, seconds, n),
+        })
+        .find(|path| !Path::new(path).exists())
+        .expect("some backup name is free");
+    fs::rename(file_name, &backup).map_err(|e| TodoError::Io { path: backup.clone(), source: e })?;
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(name: &str, json: &str) -> Result<State, TodoError> {
+        let path = std::env::temp_dir().join(format!("todo-state-{}-{}.json", name, std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        fs::write(&path, json).unwrap();
+        let state = read_file(&path);
+        fs::remove_file(&path).unwrap();
+        state
+    }
+
+    #[test]
+    fn a_flat_store_with_an_item_called_version_is_still_version_1() {
+        let state = read("flat", r#"{"version": "DONE", "items": "PENDING", "buy milk": "PENDING"}"#).unwrap();
+        assert_eq!(state.version, 1);
+        let titles: Vec<(&str, TaskStatus)> = state.items.iter().map(|item| (item.title.as_str(), item.status)).collect();
+        assert_eq!(titles, vec![("buy milk", TaskStatus::Pending), ("items", TaskStatus::Pending), ("version", TaskStatus::Done)]);
+
+        let current = read("current", &serde_json::to_string(&State::new()).unwrap()).unwrap();
+        assert_eq!(current, State::new());
+        let newer = format!(r#"{{"version": {}, "next_id": 1, "items": []}}"#, SCHEMA_VERSION + 1);
+        assert!(matches!(read("newer", &newer), Err(TodoError::NewerSchema { .. })));
+    }
+}
@@ -0,0 +1,69 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use crate::error::TodoError;
+use crate::history::Event;
+use crate::state::{self, State};
+use super::Storage;
+
+/// The state.json format, with the recovery and migration `state::open` does. Events
+/// go to `<file>.log`, one JSON object per line.
+pub struct JsonFile {
+    path: String,
+    log: String,
+}
+
+impl JsonFile {
+    pub fn new(path: &str) -> Self {
+        JsonFile { path: path.to_string(), log: format!("{}.log", path) }
+    }
+
+    fn io_error(&self, source: std::io::Error) -> TodoError {
+        TodoError::Io { path: self.log.clone(), source }
+    }
+}
+
+impl Storage for JsonFile {
+    fn load(&mut self) -> Result<State, TodoError> {
+        state::open(&self.path)
+    }
+
+    fn save(&mut self, state: &State) -> Result<(), TodoError> {
+        state::write_to_file(&self.path, state)
+    }
+
+    fn append_event(&mut self, event: &Event) -> Result<(), TodoError> {
+        let line = serde_json::to_string(event).expect("events serialize to JSON");
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.log).map_err(|e| self.io_error(e))?;
+        writeln!(file, "{}", line).and_then(|()| file.sync_data()).map_err(|e| self.io_error(e))
+    }
+
+    fn events(&mut self) -> Result<Vec<Event>, TodoError> {
+        let data = match fs::read_to_string(&self.log) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(self.io_error(e)),
+        };
+        data.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line).map_err(|e| TodoError::CorruptJson {
+                    path: self.log.clone(),
+                    message: format!("line {}: {}", n + 1, e),
+                })
+            })
+            .collect()
+    }
+
+    fn has_events(&mut self) -> Result<bool, TodoError> {
+        match fs::metadata(&self.log) {
+            Ok(metadata) => Ok(metadata.len() > 0),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(self.io_error(e)),
+        }
+    }
+
+    fn lock(&self) -> Result<Option<File>, TodoError> {
+        state::lock(&self.path).map(Some)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::error::TodoError;
+use crate::history::Event;
+use crate::state::State;
+use super::Storage;
+
+/// Keeps the state in memory, so tests can run commands without touching the disk.
+#[derive(Default)]
+pub struct Memory {
+    pub state: Option<State>,
+    pub events: Vec<Event>,
+}
+
+impl Storage for Memory {
+    fn load(&mut self) -> Result<State, TodoError> {
+        Ok(self.state.clone().unwrap_or_else(State::new))
+    }
+
+    fn save(&mut self, state: &State) -> Result<(), TodoError> {
+        self.state = Some(state.clone());
+        Ok(())
+    }
+
+    fn append_event(&mut self, event: &Event) -> Result<(), TodoError> {
+        self.events.push(event.clone());
+        Ok(())
+    }
+
+    fn events(&mut self) -> Result<Vec<Event>, TodoError> {
+        Ok(self.events.clone())
+    }
+
+    fn has_events(&mut self) -> Result<bool, TodoError> {
+        Ok(!self.events.is_empty())
+    }
+}
@@ -0,0 +1,122 @@
+pub mod json;
+pub mod sqlite;
+#[cfg(test)]
+pub mod memory;
+
+use std::env;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::str::FromStr;
+use crate::error::TodoError;
+use crate::history::Event;
+use crate::state::State;
+use json::JsonFile;
+use sqlite::Sqlite;
+
+/// Where the items live. The traits and `process_input` only see this, so they work
+/// the same whichever backend `open` picked. `Send` so the server can share one.
+pub trait Storage: Send {
+    fn load(&mut self) -> Result<State, TodoError>;
+    fn save(&mut self, state: &State) -> Result<(), TodoError>;
+    /// Adds to the end of the event log. Earlier events are never changed.
+    fn append_event(&mut self, event: &Event) -> Result<(), TodoError>;
+    /// The whole event log, oldest first.
+    fn events(&mut self) -> Result<Vec<Event>, TodoError>;
+    fn has_events(&mut self) -> Result<bool, TodoError>;
+    /// Keeps other todo processes out of the store until the returned file is dropped.
+    /// A store nothing else can see has no need to, so by default this does nothing.
+    fn lock(&self) -> Result<Option<File>, TodoError> {
+        Ok(None)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Backend {
+    Json,
+    Sqlite,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "json" => Ok(Backend::Json),
+            "sqlite" => Ok(Backend::Sqlite),
+            _ => Err(format!("backend {} not supported, expected json or sqlite", input)),
+        }
+    }
+}
+
+/// Picks the backend from `--backend`, then `TODO_BACKEND`, then the file extension,
+/// falling back to JSON; and the path from `--file`, then `TODO_FILE`, then the user's
+/// data directory, e.g. `~/.local/share/todo/state.json`.
+pub fn open(file: Option<String>, backend: Option<Backend>) -> Result<Box<dyn Storage>, TodoError> {
+    let (path, backend) = locate(file, backend)?;
+    let path = path.to_string_lossy().into_owned();
+    Ok(match backend {
+        Backend::Json => Box::new(JsonFile::new(&path)),
+        Backend::Sqlite => Box::new(Sqlite::open(&path)?),
+    })
+}
+
+fn locate(file: Option<String>, backend: Option<Backend>) -> Result<(PathBuf, Backend), TodoError> {
+    let file = file.or_else(|| env::var("TODO_FILE").ok()).map(PathBuf::from);
+    let backend = match backend {
+        Some(backend) => backend,
+        None => match env::var("TODO_BACKEND") {
+            Ok(name) => name.parse().map_err(TodoError::Config)?,
+            Err(_) => match file.as_ref().and_then(|file| file.extension()).and_then(|ext| ext.to_str()) {
+                Some("db" | "sqlite" | "sqlite3") => Backend::Sqlite,
+                _ => Backend::Json,
+            },
+        },
+    };
+    let path = match file {
+        Some(file) => file,
+        None => default_path(backend)?,
+    };
+    Ok((path, backend))
+}
+
+fn default_path(backend: Backend) -> Result<PathBuf, TodoError> {
+    let dir = dirs::data_dir()
+        .ok_or_else(|| TodoError::Config("no data directory found, pass --file or set TODO_FILE".to_string()))?
+        .join("todo");
+    fs::create_dir_all(&dir).map_err(|e| TodoError::Io { path: dir.to_string_lossy().into_owned(), source: e })?;
+    Ok(dir.join(match backend {
+        Backend::Json => "state.json",
+        Backend::Sqlite => "todo.db",
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One test, as the environment is shared by every test thread.
+    #[test]
+    fn flags_beat_the_environment_which_beats_the_extension() {
+        env::remove_var("TODO_FILE");
+        env::remove_var("TODO_BACKEND");
+        let located = |file: Option<&str>, backend| locate(file.map(String::from), backend).unwrap();
+        assert_eq!(located(Some("todo.db"), None), (PathBuf::from("todo.db"), Backend::Sqlite));
+        assert_eq!(located(Some("list.sqlite3"), None).1, Backend::Sqlite);
+        assert_eq!(located(Some("state.json"), None).1, Backend::Json);
+        assert_eq!(located(Some("notes"), None).1, Backend::Json);
+        assert_eq!(located(Some("todo.db"), Some(Backend::Json)).1, Backend::Json);
+
+        env::set_var("TODO_FILE", "shared.db");
+        assert_eq!(located(None, None), (PathBuf::from("shared.db"), Backend::Sqlite));
+        assert_eq!(located(Some("mine.json"), None), (PathBuf::from("mine.json"), Backend::Json));
+
+        env::set_var("TODO_BACKEND", "JSON");
+        assert_eq!(located(None, None).1, Backend::Json);
+        assert_eq!(located(None, Some(Backend::Sqlite)).1, Backend::Sqlite);
+        env::set_var("TODO_BACKEND", "xml");
+        assert!(matches!(locate(None, None), Err(TodoError::Config(_))));
+
+        env::remove_var("TODO_FILE");
+        env::remove_var("TODO_BACKEND");
+    }
+}
@@ -0,0 +1,249 @@
+use std::fs::File;
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{params, Connection};
+use crate::error::TodoError;
+use crate::history::Event;
+use crate::state::{self, State, SCHEMA_VERSION};
+use crate::todo::structs::base::Base;
+use super::Storage;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS items (
+        id INTEGER PRIMARY KEY,
+        title TEXT NOT NULL,
+        status TEXT NOT NULL,
+        priority TEXT NOT NULL,
+        due TEXT,
+        tags TEXT NOT NULL,
+        notes TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        completed_at TEXT
+    );
+    CREATE TABLE IF NOT EXISTS events (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        body TEXT NOT NULL
+    );";
+
+type Row = (u64, String, String, String, Option<NaiveDate>, String, String, DateTime<Utc>, Option<DateTime<Utc>>);
+
+/// One row per item in an embedded SQLite database, with the next id and schema
+/// version in `meta`. Tags are kept as a JSON array, and each event as a JSON body.
+pub struct Sqlite {
+    path: String,
+    connection: Connection,
+}
+
+impl Sqlite {
+    pub fn open(path: &str) -> Result<Self, TodoError> {
+        let error = database_error(path);
+        let connection = Connection::open(path).map_err(&error)?;
+        connection.execute_batch(SCHEMA).map_err(&error)?;
+        Ok(Sqlite { path: path.to_string(), connection })
+    }
+
+    fn meta(&self, key: &str) -> Result<Option<u64>, rusqlite::Error> {
+        let mut statement = self.connection.prepare("SELECT value FROM meta WHERE key = ?1")?;
+        let mut rows = statement.query_map([key], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+
+    fn rows(&self) -> Result<Vec<Row>, rusqlite::Error> {
+        let mut statement = self.connection.prepare(
+            "SELECT id, title, status, priority, due, tags, notes, created_at, completed_at FROM items ORDER BY id",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+                row.get(7)?,
+                row.get(8)?,
+            ))
+        })?;
+        rows.collect()
+    }
+}
+
+fn database_error(path: &str) -> impl Fn(rusqlite::Error) -> TodoError + '_ {
+    move |e| TodoError::Database { path: path.to_string(), message: e.to_string() }
+}
+
+impl Storage for Sqlite {
+    fn load(&mut self) -> Result<State, TodoError> {
+        let error = database_error(&self.path);
+        let version = self.meta("version").map_err(&error)?.unwrap_or(SCHEMA_VERSION as u64);
+        if version > SCHEMA_VERSION as u64 {
+            return Err(TodoError::NewerSchema { path: self.path.clone(), version });
+        }
+        let bad = |message: String| TodoError::Database { path: self.path.clone(), message };
+        let mut state = State::new();
+        for (id, title, status, priority, due, tags, notes, created_at, completed_at) in self.rows().map_err(&error)? {
+            state.items.push(Base {
+                id,
+                status: status.parse()?,
+                priority: priority.parse().map_err(bad)?,
+                due,
+                tags: serde_json::from_str(&tags).map_err(|e| bad(format!("tags of #{}: {}", id, e)))?,
+                notes,
+                created_at,
+                completed_at,
+                title,
+            });
+        }
+        let highest = state.items.iter().map(|item| item.id).max().unwrap_or(0);
+        state.next_id = self.meta("next_id").map_err(&error)?.unwrap_or(1).max(highest + 1);
+        Ok(state)
+    }
+
+    /// Replaces every row in one transaction, so readers see the old list or the new one.
+    fn save(&mut self, state: &State) -> Result<(), TodoError> {
+        let error = database_error(&self.path);
+        let transaction = self.connection.transaction().map_err(&error)?;
+        transaction.execute("DELETE FROM items", []).map_err(&error)?;
+        for item in &state.items {
+            let tags = serde_json::to_string(&item.tags).expect("tags serialize to JSON");
+            transaction
+                .execute(
+                    "INSERT INTO items (id, title, status, priority, due, tags, notes, created_at, completed_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        item.id,
+                        item.title,
+                        item.status.to_string(),
+                        item.priority.to_string(),
+                        item.due,
+                        tags,
+                        item.notes,
+                        item.created_at,
+                        item.completed_at,
+                    ],
+                )
+                .map_err(&error)?;
+        }
+        for (key, value) in [("version", SCHEMA_VERSION as u64), ("next_id", state.next_id)] {
+            transaction
+                .execute("INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)", params![key, value])
+                .map_err(&error)?;
+        }
+        transaction.commit().map_err(&error)
+    }
+
+    fn append_event(&mut self, event: &Event) -> Result<(), TodoError> {
+        let body = serde_json::to_string(event).expect("events serialize to JSON");
+        self.connection
+            .execute("INSERT INTO events (body) VALUES (?1)", [body])
+            .map(|_| ())
+            .map_err(database_error(&self.path))
+    }
+
+    fn events(&mut self) -> Result<Vec<Event>, TodoError> {
+        let error = database_error(&self.path);
+        let mut statement = self.connection.prepare("SELECT seq, body FROM events ORDER BY seq").map_err(&error)?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(&error)?;
+        rows.into_iter()
+            .map(|(seq, body)| {
+                serde_json::from_str(&body).map_err(|e| TodoError::Database {
+                    path: self.path.clone(),
+                    message: format!("event {}: {}", seq, e),
+                })
+            })
+            .collect()
+    }
+
+    fn has_events(&mut self) -> Result<bool, TodoError> {
+        self.connection
+            .query_row("SELECT EXISTS (SELECT 1 FROM events)", [], |row| row.get(0))
+            .map_err(database_error(&self.path))
+    }
+
+    fn lock(&self) -> Result<Option<File>, TodoError> {
+        state::lock(&self.path).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use crate::cli::{Action, Details};
+    use crate::processes::run_item;
+    use crate::todo::enums::TaskStatus;
+
+    /// A fresh database file in its own directory, so the lock file has somewhere to go.
+    fn scratch(name: &str) -> (PathBuf, Sqlite) {
+        let dir = env::temp_dir().join(format!("todo-sqlite-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let storage = Sqlite::open(&dir.join("todo.db").to_string_lossy()).unwrap();
+        (dir, storage)
+    }
+
+    #[test]
+    fn items_and_next_id_survive_reopening() {
+        let (dir, mut storage) = scratch("items");
+        let details = Details { tags: vec!["home".to_string(), "weekly".to_string()], ..Details::default() };
+        run_item("water plants", &Action::Add(details), &mut storage, "test").unwrap();
+        run_item("pay rent", &Action::Add(Details::default()), &mut storage, "test").unwrap();
+        run_item("old", &Action::Add(Details::default()), &mut storage, "test").unwrap();
+        run_item("pay rent", &Action::SetStatus(TaskStatus::Done), &mut storage, "test").unwrap();
+        run_item("old", &Action::Delete, &mut storage, "test").unwrap();
+        let saved = storage.load().unwrap();
+        drop(storage);
+
+        let mut reopened = Sqlite::open(&dir.join("todo.db").to_string_lossy()).unwrap();
+        let state = reopened.load().unwrap();
+        assert_eq!(state, saved);
+        assert_eq!(state.items.len(), 2);
+        assert_eq!(state.find("water plants").unwrap().tags, vec!["home", "weekly"]);
+        assert!(state.find("pay rent").unwrap().completed_at.is_some());
+        // The deleted item's id is not handed out again.
+        assert_eq!(state.next_id, 4);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn events_come_back_oldest_first() {
+        let (dir, mut storage) = scratch("events");
+        assert!(!storage.has_events().unwrap());
+        run_item("a", &Action::Add(Details::default()), &mut storage, "ann").unwrap();
+        run_item("a", &Action::EditTitle("b".to_string()), &mut storage, "bob").unwrap();
+        run_item("b", &Action::Delete, &mut storage, "ann").unwrap();
+
+        let events = storage.events().unwrap();
+        assert!(storage.has_events().unwrap());
+        let actions: Vec<(&str, &str)> = events.iter().map(|e| (e.action.as_str(), e.user.as_str())).collect();
+        assert_eq!(actions, vec![("add", "ann"), ("edit-title", "bob"), ("delete", "ann")]);
+        assert_eq!(events[1].before.as_ref().unwrap().title, "a");
+        assert_eq!(events[1].after.as_ref().unwrap().title, "b");
+        assert!(events[2].after.is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_newer_schema_is_refused() {
+        let (dir, mut storage) = scratch("schema");
+        storage.save(&State::new()).unwrap();
+        storage
+            .connection
+            .execute("UPDATE meta SET value = ?1 WHERE key = 'version'", [SCHEMA_VERSION as u64 + 1])
+            .unwrap();
+        assert!(matches!(
+            storage.load(),
+            Err(TodoError::NewerSchema { version, .. }) if version == SCHEMA_VERSION as u64 + 1
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
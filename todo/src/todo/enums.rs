@@ -1,27 +1,107 @@
 use std::fmt;
-use crate::todo::enums::TaskStatus::DONE;
-use crate::todo::enums::TaskStatus::PENDING;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::error::TodoError;
 
+// Stored upper case, as in the original flat state.json.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TaskStatus {
-    DONE,
-    PENDING,
+    Pending,
+    InProgress,
+    Blocked,
+    Done,
+    Cancelled,
+    Archived,
 }
+
+impl TaskStatus {
+    /// The transition table: the statuses an item in this one may be moved to. Blocked
+    /// work has to be unblocked before it can be finished, and only finished or
+    /// cancelled items are archived.
+    pub fn next(self) -> &'static [TaskStatus] {
+        use TaskStatus::*;
+        match self {
+            Pending => &[InProgress, Blocked, Done, Cancelled],
+            InProgress => &[Pending, Blocked, Done, Cancelled],
+            Blocked => &[Pending, InProgress, Cancelled],
+            Done => &[Pending, Archived],
+            Cancelled => &[Pending, Archived],
+            Archived => &[Pending],
+        }
+    }
+
+    pub fn check_move(self, title: &str, to: TaskStatus) -> Result<(), TodoError> {
+        if self == to {
+            return Err(TodoError::AlreadyInStatus { title: title.to_string(), status: to });
+        }
+        if !self.next().contains(&to) {
+            return Err(TodoError::IllegalTransition { title: title.to_string(), from: self, to });
+        }
+        Ok(())
+    }
+}
+
  impl fmt::Display for TaskStatus {
     
     fn fmt(&self, f: &mut fmt::Formatter) ->  fmt::Result {
-        match &self {
-            &self::DONE => {write!(f, "DONE")},
-            &self::PENDING => {write!(f, "PENDING")}
+        match self {
+            TaskStatus::Pending => {write!(f, "PENDING")},
+            TaskStatus::InProgress => {write!(f, "IN_PROGRESS")},
+            TaskStatus::Blocked => {write!(f, "BLOCKED")},
+            TaskStatus::Done => {write!(f, "DONE")},
+            TaskStatus::Cancelled => {write!(f, "CANCELLED")},
+            TaskStatus::Archived => {write!(f, "ARCHIVED")}
+        }
+    }
+ }
+
+ // Case insensitive, so `--status done` on the command line works as well as the
+ // upper case form stored in state.json. `in-progress` is accepted for IN_PROGRESS.
+ impl FromStr for TaskStatus {
+    type Err = TodoError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_uppercase().replace('-', "_").as_str() {
+            "PENDING" => Ok(TaskStatus::Pending),
+            "IN_PROGRESS" => Ok(TaskStatus::InProgress),
+            "BLOCKED" => Ok(TaskStatus::Blocked),
+            "DONE" => Ok(TaskStatus::Done),
+            "CANCELLED" => Ok(TaskStatus::Cancelled),
+            "ARCHIVED" => Ok(TaskStatus::Archived),
+            _ => Err(TodoError::UnknownStatus(input.to_string()))
         }
     }
  }
 
- impl TaskStatus {
-    pub fn from_string(input_string: String) -> Self {
-        match input_string.as_str() {
-            "DONE" => TaskStatus::DONE,
-            "PENDING" => TaskStatus::PENDING,
-            _ => panic!("input {} not suppoerted", input_string)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Medium => write!(f, "medium"),
+            Priority::High => write!(f, "high"),
         }
     }
- }
\ No newline at end of file
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "medium" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            _ => Err(format!("priority {} not supported, expected low, medium or high", input)),
+        }
+    }
+}
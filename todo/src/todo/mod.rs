@@ -2,20 +2,54 @@ pub mod traits;
 pub mod structs;
 pub mod enums;
 use enums::TaskStatus;
+use structs::base::Base;
+use structs::archived::Archived;
+use structs::blocked::Blocked;
+use structs::cancelled::Cancelled;
 use structs::done::Done;
+use structs::in_progress::InProgress;
 use structs::pending::Pending;
 pub enum ItemTypes {
     Pending(Pending),
+    InProgress(InProgress),
+    Blocked(Blocked),
     Done(Done),
+    Cancelled(Cancelled),
+    Archived(Archived),
 }
 
-pub fn to_do_factory(title: &str, status: TaskStatus) -> ItemTypes {
-    match status {
-        TaskStatus::DONE => {
-            ItemTypes::Done(Done::new(title))
+pub fn to_do_factory(base: Base) -> ItemTypes {
+    match base.status {
+        TaskStatus::Done => {
+            ItemTypes::Done(Done::new(base))
         },
-        TaskStatus::PENDING => {
-            ItemTypes::Pending(Pending::new(title))
+        TaskStatus::Pending => {
+            ItemTypes::Pending(Pending::new(base))
+        },
+        TaskStatus::InProgress => {
+            ItemTypes::InProgress(InProgress::new(base))
+        },
+        TaskStatus::Blocked => {
+            ItemTypes::Blocked(Blocked::new(base))
+        },
+        TaskStatus::Cancelled => {
+            ItemTypes::Cancelled(Cancelled::new(base))
+        },
+        TaskStatus::Archived => {
+            ItemTypes::Archived(Archived::new(base))
+        }
+    }
+}
+
+impl ItemTypes {
+    pub fn base(&self) -> &Base {
+        match self {
+            ItemTypes::Pending(item) => &item.super_struct,
+            ItemTypes::InProgress(item) => &item.super_struct,
+            ItemTypes::Blocked(item) => &item.super_struct,
+            ItemTypes::Done(item) => &item.super_struct,
+            ItemTypes::Cancelled(item) => &item.super_struct,
+            ItemTypes::Archived(item) => &item.super_struct,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+use super::base::Base;
+use super::super::enums::TaskStatus;
+use super::super::traits::get::Get;
+use super::super::traits::transition::Transition;
+use super::super::traits::purge::Purge;
+
+pub struct Archived {
+    pub super_struct: Base
+}
+
+impl Archived {
+    pub fn new(base: Base) -> Self {
+        let base = Base {
+            status: TaskStatus::Archived,
+            ..base
+        };
+        Archived{ super_struct: base }
+    }
+}
+impl Get for Archived {}
+impl Transition for Archived {}
+impl Purge for Archived {}
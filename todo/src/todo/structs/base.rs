@@ -1,6 +1,40 @@
-use super::super::enums::TaskStatus;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use super::super::enums::{Priority, TaskStatus};
 
+/// One item as stored in state.json.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Base {
+    /// Assigned when the item is added and never reused, so it survives renames.
+    pub id: u64,
     pub title: String,
-    pub status: TaskStatus
-}
\ No newline at end of file
+    pub status: TaskStatus,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl Base {
+    /// A pending item created now. The id is filled in when it is added to the state.
+    pub fn new(title: &str) -> Self {
+        Base {
+            id: 0,
+            title: title.to_string(),
+            status: TaskStatus::Pending,
+            priority: Priority::default(),
+            due: None,
+            tags: Vec::new(),
+            notes: String::new(),
+            created_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+}
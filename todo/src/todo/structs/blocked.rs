@@ -0,0 +1,24 @@
+use super::base::Base;
+use super::super::enums::TaskStatus;
+use super::super::traits::get::Get;
+use super::super::traits::edit::Edit;
+use super::super::traits::delete::Delete;
+use super::super::traits::transition::Transition;
+
+pub struct Blocked {
+    pub super_struct: Base
+}
+
+impl Blocked {
+    pub fn new(base: Base) -> Self {
+        let base = Base {
+            status: TaskStatus::Blocked,
+            ..base
+        };
+        Blocked{ super_struct: base }
+    }
+}
+impl Get for Blocked {}
+impl Edit for Blocked {}
+impl Delete for Blocked {}
+impl Transition for Blocked {}
@@ -0,0 +1,22 @@
+use super::base::Base;
+use super::super::enums::TaskStatus;
+use super::super::traits::get::Get;
+use super::super::traits::delete::Delete;
+use super::super::traits::transition::Transition;
+
+pub struct Cancelled {
+    pub super_struct: Base
+}
+
+impl Cancelled {
+    pub fn new(base: Base) -> Self {
+        let base = Base {
+            status: TaskStatus::Cancelled,
+            ..base
+        };
+        Cancelled{ super_struct: base }
+    }
+}
+impl Get for Cancelled {}
+impl Delete for Cancelled {}
+impl Transition for Cancelled {}
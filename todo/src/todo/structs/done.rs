@@ -3,19 +3,21 @@ use super::super::enums::TaskStatus;
 use super::super::traits::get::Get;
 use super::super::traits::delete::Delete;
 use super::super::traits::edit::Edit;
+use super::super::traits::transition::Transition;
 
 pub struct Done {
     pub super_struct: Base
 } 
 impl Done {
-    pub fn new(input_title: &str) -> Self {
+    pub fn new(base: Base) -> Self {
         let base = Base {
-            title: input_title.to_string(),
-            status: TaskStatus::DONE
+            status: TaskStatus::Done,
+            ..base
         };
-        return Done{super_struct: base}
+        Done{super_struct: base}
     }
 }
 impl Get for Done {}
 impl Delete for Done {}
-impl Edit for Done {}
\ No newline at end of file
+impl Edit for Done {}
+impl Transition for Done {}
\ No newline at end of file
@@ -0,0 +1,24 @@
+use super::base::Base;
+use super::super::enums::TaskStatus;
+use super::super::traits::get::Get;
+use super::super::traits::edit::Edit;
+use super::super::traits::delete::Delete;
+use super::super::traits::transition::Transition;
+
+pub struct InProgress {
+    pub super_struct: Base
+}
+
+impl InProgress {
+    pub fn new(base: Base) -> Self {
+        let base = Base {
+            status: TaskStatus::InProgress,
+            ..base
+        };
+        InProgress{ super_struct: base }
+    }
+}
+impl Get for InProgress {}
+impl Edit for InProgress {}
+impl Delete for InProgress {}
+impl Transition for InProgress {}
@@ -1,3 +1,7 @@
-mod base;
+pub mod base;
 pub mod done;
-pub mod pending;
\ No newline at end of file
+pub mod pending;
+pub mod in_progress;
+pub mod blocked;
+pub mod cancelled;
+pub mod archived;
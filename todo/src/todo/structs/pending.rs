@@ -2,21 +2,25 @@ use super::base::Base;
 use super::super::enums::TaskStatus;
 use super::super::traits::get::Get;
 use super::super::traits::edit::Edit;
+use super::super::traits::transition::Transition;
 use super::super::traits::create::Create;
+use super::super::traits::delete::Delete;
 
 pub struct Pending {
     pub super_struct: Base
 }
 
 impl Pending {
-    pub fn new (input_title: &str) -> Self {
+    pub fn new (base: Base) -> Self {
         let base = Base {
-            title: input_title.to_string(),
-            status: TaskStatus::PENDING
+            status: TaskStatus::Pending,
+            ..base
         };
-        return Pending{ super_struct: base }
+        Pending{ super_struct: base }
     }
 }
 impl Get for Pending {}
 impl Edit for Pending {}
-impl Create for Pending {}
\ No newline at end of file
+impl Create for Pending {}
+impl Delete for Pending {}
+impl Transition for Pending {}
\ No newline at end of file
@@ -1,12 +1,12 @@
-use serde_json::Map;
-use serde_json::value::Value;
-use serde_json::json;
-use crate::state::write_to_file;
+use crate::error::TodoError;
+use crate::state::State;
+use crate::storage::Storage;
+use super::super::structs::base::Base;
 
 pub trait Create {
-    fn create(&self, title: &String, status: &String, state: &mut Map<String, Value>) {
-        state.insert(title.to_string(), json!(status));
-        write_to_file("./state.json", state);
-        println!("\n\n{} is being added", title);
+    fn create(&self, base: &Base, state: &mut State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+        let id = state.insert(base.clone());
+        storage.save(state)?;
+        Ok(format!("\n\n{} is being added as #{}", base.title, id))
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,11 @@
-use serde_json::Map;
-use serde_json::value::Value;
-use crate::state::write_to_file;
+use crate::error::TodoError;
+use crate::state::State;
+use crate::storage::Storage;
 
 pub trait Delete {
-    fn delete(&self, title: &str, mut state: Map<String, Value>) {
-        state.remove(title);
-        write_to_file("./state.json", &mut state);
-        println!("Deleted: {}", title);
+    fn delete(&self, title: &str, state: &mut State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+        state.remove(title).ok_or_else(|| TodoError::UnknownItem(title.to_string()))?;
+        storage.save(state)?;
+        Ok(format!("Deleted: {}", title))
     }
-}
\ No newline at end of file
+}
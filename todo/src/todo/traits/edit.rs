@@ -1,20 +1,12 @@
-use serde_json::Map;
-use serde_json::value::Value;
-use serde_json::json;
-use crate::state::write_to_file;
-use super::super::enums::TaskStatus;
+use crate::error::TodoError;
+use crate::state::State;
+use crate::storage::Storage;
 
 pub trait Edit {
-    fn set_to_done(&self, title: &String, state: &mut Map<String, Value>) {
-        state.insert(title.to_string(),
-        json!(TaskStatus::DONE.to_string()));
-        write_to_file("./state.json", state);
-        println!("\n\n{} is being set to done\n\n", title);
+    fn edit_title(&self, title: &str, new_title: &str, state: &mut State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+        let item = state.find_mut(title).ok_or_else(|| TodoError::UnknownItem(title.to_string()))?;
+        item.title = new_title.to_string();
+        storage.save(state)?;
+        Ok(format!("\n\n{} is being renamed to {}\n\n", title, new_title))
     }
-    fn set_to_pending(&self, title: &String, state: &mut Map<String, Value>) {
-        state.insert(title.to_string(),
-        json!(TaskStatus::PENDING.to_string()));
-        write_to_file("./state.json", state);
-        println!("\n\n{} is being set to pending\n\n", title);
-    }
-}
\ No newline at end of file
+}
@@ -1,15 +1,29 @@
-use serde_json::Map;
-use serde_json::value::Value;
+use std::fmt::Write;
+use crate::error::TodoError;
+use crate::state::State;
 
 pub trait Get {
-    fn get(&self, title: &str, state: Map<String, Value>) {
-        let item: Option<&Value> = state.get(title);
-        match item {
-            Some(result) => {
-                println!("\n\nItem: {}", title);
-                println!("Status: {}\n\n", result);
-            },
-            None => println!("Item: {} not found", title)
+    fn get(&self, title: &str, state: &State) -> Result<String, TodoError> {
+        let item = state.find(title).ok_or_else(|| TodoError::UnknownItem(title.to_string()))?;
+        let mut text = String::new();
+        // Writing to a String can't fail.
+        let _ = writeln!(text, "\n\nItem: {} (#{})", item.title, item.id);
+        let _ = writeln!(text, "Status: {}", item.status);
+        let _ = writeln!(text, "Priority: {}", item.priority);
+        if let Some(due) = item.due {
+            let _ = writeln!(text, "Due: {}", due);
         }
+        if !item.tags.is_empty() {
+            let _ = writeln!(text, "Tags: {}", item.tags.join(", "));
+        }
+        if !item.notes.is_empty() {
+            let _ = writeln!(text, "Notes: {}", item.notes);
+        }
+        let _ = writeln!(text, "Created: {}", item.created_at.format("%Y-%m-%d %H:%M UTC"));
+        if let Some(completed_at) = item.completed_at {
+            let _ = writeln!(text, "Completed: {}", completed_at.format("%Y-%m-%d %H:%M UTC"));
+        }
+        text.push('\n');
+        Ok(text)
     }
-}
\ No newline at end of file
+}
@@ -1,4 +1,6 @@
 pub mod create;
 pub mod delete;
 pub mod edit;
-pub mod get;
\ No newline at end of file
+pub mod get;
+pub mod purge;
+pub mod transition;
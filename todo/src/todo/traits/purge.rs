@@ -0,0 +1,11 @@
+use crate::error::TodoError;
+use crate::state::State;
+use crate::storage::Storage;
+
+pub trait Purge {
+    fn purge(&self, title: &str, state: &mut State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+        state.remove(title).ok_or_else(|| TodoError::UnknownItem(title.to_string()))?;
+        storage.save(state)?;
+        Ok(format!("Purged: {}", title))
+    }
+}
@@ -0,0 +1,22 @@
+use chrono::Utc;
+use crate::error::TodoError;
+use crate::state::State;
+use crate::storage::Storage;
+use super::super::enums::TaskStatus;
+
+pub trait Transition {
+    /// Moves the item to `to` if the transition table allows it. Finishing records
+    /// when, reopening clears it, and archiving keeps whatever was there.
+    fn set_status(&self, title: &str, to: TaskStatus, state: &mut State, storage: &mut dyn Storage) -> Result<String, TodoError> {
+        let item = state.find_mut(title).ok_or_else(|| TodoError::UnknownItem(title.to_string()))?;
+        item.status.check_move(title, to)?;
+        item.status = to;
+        match to {
+            TaskStatus::Done => item.completed_at = Some(Utc::now()),
+            TaskStatus::Archived => {}
+            _ => item.completed_at = None,
+        }
+        storage.save(state)?;
+        Ok(format!("\n\n{} is being set to {}\n\n", title, to))
+    }
+}
@@ -0,0 +1,396 @@
+use std::io;
+use ratatui::backend::Backend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::widgets::{Block, List, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+use crate::cli::{Action, Details};
+use crate::error::TodoError;
+use crate::history;
+use crate::processes;
+use crate::state::State;
+use crate::storage::Storage;
+use crate::todo::enums::TaskStatus;
+use crate::todo::structs::base::Base;
+
+const HELP: &str =
+    "j/k move  space done/pending  a add  r rename  d delete  / filter  tab status  u undo  q quit";
+
+/// Where key presses come from: the real terminal, or a script in tests.
+pub trait Events {
+    /// The next event, or `None` once a script has run out.
+    fn next(&mut self) -> io::Result<Option<Event>>;
+}
+
+pub struct TerminalEvents;
+
+impl Events for TerminalEvents {
+    fn next(&mut self) -> io::Result<Option<Event>> {
+        event::read().map(Some)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Prompt {
+    Add,
+    Rename,
+    Filter,
+}
+
+#[derive(PartialEq, Debug)]
+enum Mode {
+    Browse,
+    Input(Prompt, String),
+    ConfirmDelete,
+}
+
+/// The TUI's state. Every change goes through `processes::run_item`, so the same
+/// structs, traits, transition checks, lock and event log apply as on the command line.
+pub struct App<'a> {
+    storage: &'a mut dyn Storage,
+    user: String,
+    state: State,
+    selected: usize,
+    filter: String,
+    status: Option<TaskStatus>,
+    mode: Mode,
+    message: String,
+    quit: bool,
+}
+
+fn load(storage: &mut dyn Storage) -> Result<State, TodoError> {
+    let _lock = storage.lock()?;
+    storage.load()
+}
+
+impl<'a> App<'a> {
+    pub fn new(storage: &'a mut dyn Storage, user: &str) -> Self {
+        let mut app = App {
+            storage,
+            user: user.to_string(),
+            state: State::new(),
+            selected: 0,
+            filter: String::new(),
+            status: None,
+            mode: Mode::Browse,
+            message: String::new(),
+            quit: false,
+        };
+        app.reload();
+        app
+    }
+
+    /// Reads the store again, picking up changes from other processes too.
+    fn reload(&mut self) {
+        match load(self.storage) {
+            Ok(state) => self.state = state,
+            Err(e) => self.message = format!("error: {}", e),
+        }
+        self.selected = self.selected.min(self.visible().len().saturating_sub(1));
+    }
+
+    /// The items that pass the status filter and contain the filter text in their
+    /// title or a tag.
+    fn visible(&self) -> Vec<&Base> {
+        processes::matching(self.status, None, Some(&self.filter), &self.state)
+    }
+
+    fn current(&self) -> Option<Base> {
+        self.visible().get(self.selected).map(|item| (*item).clone())
+    }
+
+    fn select_title(&mut self, title: &str) {
+        if let Some(i) = self.visible().iter().position(|item| item.title == title) {
+            self.selected = i;
+        }
+    }
+
+    /// Items are named by id so titles that look like options or ids can't be mistaken.
+    fn run(&mut self, item: &Base, action: Action) {
+        let key = format!("#{}", item.id);
+        self.message = match processes::run_item(&key, &action, self.storage, &self.user) {
+            Ok(message) => message.trim().to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        self.reload();
+    }
+
+    pub fn handle(&mut self, event: Event) {
+        let Event::Key(key) = event else {
+            return;
+        };
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.quit = true;
+            return;
+        }
+        match self.mode {
+            Mode::Browse => self.browse(key),
+            Mode::Input(..) => self.input(key),
+            Mode::ConfirmDelete => {
+                self.mode = Mode::Browse;
+                match (key.code, self.current()) {
+                    (KeyCode::Char('y'), Some(item)) => self.run(&item, Action::Delete),
+                    _ => self.message = "Kept it".to_string(),
+                }
+            }
+        }
+    }
+
+    fn browse(&mut self, key: KeyEvent) {
+        let count = self.visible().len();
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc if !self.filter.is_empty() => self.filter.clear(),
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(count.saturating_sub(1)),
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Home | KeyCode::Char('g') => self.selected = 0,
+            KeyCode::End | KeyCode::Char('G') => self.selected = count.saturating_sub(1),
+            KeyCode::Char(' ') | KeyCode::Char('x') | KeyCode::Enter => {
+                if let Some(item) = self.current() {
+                    let to = if item.status == TaskStatus::Done { TaskStatus::Pending } else { TaskStatus::Done };
+                    self.run(&item, Action::SetStatus(to));
+                }
+            }
+            KeyCode::Char('a') => self.mode = Mode::Input(Prompt::Add, String::new()),
+            KeyCode::Char('r') => {
+                if let Some(item) = self.current() {
+                    self.mode = Mode::Input(Prompt::Rename, item.title);
+                }
+            }
+            KeyCode::Char('d') if count > 0 => self.mode = Mode::ConfirmDelete,
+            KeyCode::Char('/') => self.mode = Mode::Input(Prompt::Filter, self.filter.clone()),
+            KeyCode::Tab => {
+                // None, then each status in the order they are declared, then None again.
+                let all = [
+                    TaskStatus::Pending,
+                    TaskStatus::InProgress,
+                    TaskStatus::Blocked,
+                    TaskStatus::Done,
+                    TaskStatus::Cancelled,
+                    TaskStatus::Archived,
+                ];
+                let next = match self.status {
+                    None => 0,
+                    Some(status) => all.iter().position(|s| *s == status).map_or(0, |i| i + 1),
+                };
+                self.status = all.get(next).copied();
+                self.selected = 0;
+            }
+            KeyCode::Char('u') => {
+                self.message = history::step(self.storage, &self.user, false).unwrap_or_else(|e| format!("error: {}", e));
+                self.reload();
+            }
+            _ => {}
+        }
+    }
+
+    fn input(&mut self, key: KeyEvent) {
+        let Mode::Input(prompt, text) = &mut self.mode else {
+            return;
+        };
+        let prompt = *prompt;
+        match key.code {
+            KeyCode::Char(c) => text.push(c),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Esc => {
+                self.mode = Mode::Browse;
+                return;
+            }
+            KeyCode::Enter => {
+                let text = text.trim().to_string();
+                self.mode = Mode::Browse;
+                self.submit(prompt, text);
+                return;
+            }
+            _ => {}
+        }
+        // The filter applies as it is typed.
+        if let (Prompt::Filter, Mode::Input(_, text)) = (prompt, &self.mode) {
+            self.filter = text.clone();
+            self.selected = 0;
+        }
+    }
+
+    fn submit(&mut self, prompt: Prompt, text: String) {
+        match prompt {
+            Prompt::Filter => {}
+            _ if text.is_empty() => self.message = "A title can't be empty".to_string(),
+            Prompt::Add => {
+                self.message = match processes::run_item(&text, &Action::Add(Details::default()), self.storage, &self.user) {
+                    Ok(message) => message.trim().to_string(),
+                    Err(e) => format!("error: {}", e),
+                };
+                self.reload();
+                self.select_title(&text);
+            }
+            Prompt::Rename => {
+                if let Some(item) = self.current() {
+                    self.run(&item, Action::EditTitle(text));
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let [help, list, footer] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(3), Constraint::Length(3)]).areas(frame.area());
+        frame.render_widget(Paragraph::new(HELP).dim(), help);
+
+        let visible = self.visible();
+        let mut title = format!(" Items ({} of {}) ", visible.len(), self.state.items.len());
+        if let Some(status) = self.status {
+            title.push_str(&format!("status {} ", status));
+        }
+        if !self.filter.is_empty() {
+            title.push_str(&format!("filter \"{}\" ", self.filter));
+        }
+        let rows: Vec<String> = visible.iter().map(|item| processes::line(item)).collect();
+        let mut state = ListState::default().with_selected((!rows.is_empty()).then_some(self.selected));
+        let widget = List::new(rows)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().reversed())
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(widget, list, &mut state);
+
+        let (label, text) = match &self.mode {
+            Mode::Input(Prompt::Add, text) => (" New item ", format!("{}_", text)),
+            Mode::Input(Prompt::Rename, text) => (" Rename to ", format!("{}_", text)),
+            Mode::Input(Prompt::Filter, text) => (" Filter ", format!("{}_", text)),
+            Mode::ConfirmDelete => {
+                let title = self.current().map(|item| item.title).unwrap_or_default();
+                (" Delete ", format!("Delete \"{}\"? y/n", title))
+            }
+            Mode::Browse => ("", self.message.clone()),
+        };
+        frame.render_widget(Paragraph::new(text).block(Block::bordered().title(label)), footer);
+    }
+}
+
+/// Draws and handles events until the user quits or the events run out.
+pub fn run_with<B: Backend>(terminal: &mut Terminal<B>, app: &mut App, events: &mut dyn Events) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.render(frame))?;
+        if app.quit {
+            return Ok(());
+        }
+        match events.next()? {
+            Some(event) => app.handle(event),
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Full-screen mode on the real terminal, restored on the way out even after an error.
+pub fn run(storage: &mut dyn Storage, user: &str) -> Result<(), TodoError> {
+    let mut app = App::new(storage, user);
+    let mut terminal = ratatui::init();
+    let result = run_with(&mut terminal, &mut app, &mut TerminalEvents);
+    ratatui::restore();
+    result.map_err(|e| TodoError::Io { path: "terminal".to_string(), source: e })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use ratatui::backend::TestBackend;
+    use super::*;
+    use crate::storage::memory::Memory;
+
+    /// Plays back a fixed list of events, as if they had been typed.
+    struct Scripted(VecDeque<Event>);
+
+    impl Events for Scripted {
+        fn next(&mut self) -> io::Result<Option<Event>> {
+            Ok(self.0.pop_front())
+        }
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn typed(text: &str) -> Vec<Event> {
+        text.chars().map(|c| key(KeyCode::Char(c))).collect()
+    }
+
+    /// Runs the script against `storage` and returns the last screen drawn.
+    fn play(storage: &mut Memory, script: Vec<Vec<Event>>) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        let mut app = App::new(storage, "tester");
+        let mut events = Scripted(script.into_iter().flatten().collect());
+        run_with(&mut terminal, &mut app, &mut events).unwrap();
+        let buffer = terminal.backend().buffer();
+        let width = buffer.area.width as usize;
+        let symbols: Vec<&str> = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        symbols.chunks(width).map(|line| line.concat()).collect::<Vec<_>>().join("\n")
+    }
+
+    fn status_of(storage: &Memory, title: &str) -> Option<TaskStatus> {
+        storage.state.as_ref()?.find(title).map(|item| item.status)
+    }
+
+    #[test]
+    fn add_toggle_and_navigate() {
+        let mut storage = Memory::default();
+        let screen = play(
+            &mut storage,
+            vec![
+                typed("a"),
+                typed("buy milk"),
+                vec![key(KeyCode::Enter)],
+                typed("a"),
+                typed("walk dog"),
+                vec![key(KeyCode::Enter), key(KeyCode::Up), key(KeyCode::Char(' '))],
+            ],
+        );
+        assert_eq!(status_of(&storage, "buy milk"), Some(TaskStatus::Done));
+        assert_eq!(status_of(&storage, "walk dog"), Some(TaskStatus::Pending));
+        assert!(screen.contains("> #1    DONE"), "{}", screen);
+        assert!(screen.contains("buy milk is being set to DONE"), "{}", screen);
+        assert_eq!(storage.events.len(), 3);
+        assert_eq!(storage.events[2].user, "tester");
+    }
+
+    #[test]
+    fn rename_filter_and_delete() {
+        let mut storage = Memory::default();
+        for title in ["alpha", "beta", "gamma"] {
+            processes::run_item(title, &Action::Add(Details::default()), &mut storage, "setup").unwrap();
+        }
+        let mut rename = vec![key(KeyCode::Down), key(KeyCode::Char('r'))];
+        rename.extend(std::iter::repeat_n(key(KeyCode::Backspace), 3));
+        rename.extend(typed("eta-2"));
+        rename.push(key(KeyCode::Enter));
+        let screen = play(
+            &mut storage,
+            vec![rename, typed("/am"), vec![key(KeyCode::Enter)], typed("dy")],
+        );
+        let titles: Vec<_> = storage.state.as_ref().unwrap().items.iter().map(|item| item.title.as_str()).collect();
+        assert_eq!(titles, vec!["alpha", "beta-2"]);
+        assert!(screen.contains("Deleted: gamma"), "{}", screen);
+        assert!(screen.contains("Items (0 of 2) filter \"am\""), "{}", screen);
+    }
+
+    #[test]
+    fn refused_moves_are_shown_not_applied() {
+        let mut storage = Memory::default();
+        processes::run_item("report", &Action::Add(Details::default()), &mut storage, "setup").unwrap();
+        processes::run_item("report", &Action::SetStatus(TaskStatus::Blocked), &mut storage, "setup").unwrap();
+        let screen = play(&mut storage, vec![typed(" "), vec![key(KeyCode::Tab)]]);
+        assert_eq!(status_of(&storage, "report"), Some(TaskStatus::Blocked));
+        assert!(screen.contains("error: report can't go from BLOCKED to DONE"), "{}", screen);
+        assert!(screen.contains("Items (0 of 1) status PENDING"), "{}", screen);
+
+        // Escape with no filter quits, leaving later keys unread.
+        let screen = play(&mut storage, vec![vec![key(KeyCode::Esc)], typed("dy")]);
+        assert!(screen.contains("#1    BLOCKED"), "{}", screen);
+        assert_eq!(storage.state.as_ref().unwrap().items.len(), 1);
+    }
+}
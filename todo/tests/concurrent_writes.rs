@@ -0,0 +1,53 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const PROCESSES: usize = 24;
+
+fn scratch_dir() -> PathBuf {
+    let dir = env::temp_dir().join(format!("todo-concurrent-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn parallel_adds_are_not_lost() {
+    let dir = scratch_dir();
+    let children: Vec<_> = (0..PROCESSES)
+        .map(|i| {
+            Command::new(env!("CARGO_BIN_EXE_todo"))
+                .args(["--file", "state.json", "add", &format!("item {}", i)])
+                .current_dir(&dir)
+                .stdout(Stdio::null())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let data = fs::read_to_string(dir.join("state.json")).unwrap();
+    let state: serde_json::Value = serde_json::from_str(&data).unwrap();
+    let items = state["items"].as_array().unwrap();
+    let mut titles: Vec<&str> = items.iter().map(|item| item["title"].as_str().unwrap()).collect();
+    titles.sort();
+    let mut expected: Vec<String> = (0..PROCESSES).map(|i| format!("item {}", i)).collect();
+    expected.sort();
+    assert_eq!(titles, expected);
+
+    let mut ids: Vec<u64> = items.iter().map(|item| item["id"].as_u64().unwrap()).collect();
+    ids.sort();
+    assert_eq!(ids, (1..=PROCESSES as u64).collect::<Vec<_>>());
+    assert_eq!(state["next_id"], PROCESSES as u64 + 1);
+
+    let leftovers: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "temporary files left behind: {:?}", leftovers);
+    fs::remove_dir_all(&dir).unwrap();
+}
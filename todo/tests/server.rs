@@ -0,0 +1,131 @@
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use serde_json::{json, Value};
+
+/// A `todo serve` on a free port with its own store, stopped when dropped.
+struct Server {
+    child: Child,
+    url: String,
+    dir: PathBuf,
+}
+
+impl Server {
+    fn start(name: &str) -> Server {
+        let dir = env::temp_dir().join(format!("todo-server-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut child = Command::new(env!("CARGO_BIN_EXE_todo"))
+            .args(["--file", "state.json", "serve", "--addr", "127.0.0.1:0"])
+            .current_dir(&dir)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        let url = line.trim().rsplit(' ').next().unwrap().to_string();
+        assert!(url.starts_with("http://"), "unexpected first line: {:?}", line);
+        // Keep reading so the server never blocks on a full pipe.
+        std::thread::spawn(move || std::io::copy(&mut stdout, &mut std::io::sink()));
+        Server { child, url, dir }
+    }
+
+    fn send(&self, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+        let request = ureq::request(method, &format!("{}{}", self.url, path));
+        let result = match body {
+            Some(body) => request.send_json(body),
+            None => request.call(),
+        };
+        let response = match result {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => panic!("{} {} failed: {}", method, path, e),
+        };
+        let status = response.status();
+        let mut text = String::new();
+        response.into_reader().read_to_string(&mut text).unwrap();
+        let body = if text.is_empty() { Value::Null } else { serde_json::from_str(&text).unwrap() };
+        (status, body)
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn create_list_get_and_delete() {
+    let server = Server::start("crud");
+    let (status, item) = server.send("POST", "/items", Some(json!({ "title": "buy milk", "tags": ["shop"] })));
+    assert_eq!(status, 201);
+    assert_eq!(item["id"], 1);
+    assert_eq!(item["status"], "PENDING");
+    assert_eq!(item["tags"], json!(["shop"]));
+
+    let (status, _) = server.send("POST", "/items", Some(json!({ "title": "buy milk" })));
+    assert_eq!(status, 409);
+    for title in ["", "   ", "#3"] {
+        let (status, body) = server.send("POST", "/items", Some(json!({ "title": title })));
+        assert_eq!(status, 400, "{:?} gave {}", title, body);
+    }
+    server.send("POST", "/items", Some(json!({ "title": "walk dog", "priority": "high" })));
+
+    let (status, items) = server.send("GET", "/items", None);
+    assert_eq!(status, 200);
+    assert_eq!(items.as_array().unwrap().len(), 2);
+    let (_, shop) = server.send("GET", "/items?tag=shop", None);
+    assert_eq!(shop[0]["title"], "buy milk");
+    assert_eq!(shop.as_array().unwrap().len(), 1);
+
+    let (status, item) = server.send("GET", "/items/walk%20dog", None);
+    assert_eq!(status, 200);
+    assert_eq!(item["priority"], "high");
+    let (status, item) = server.send("GET", "/items/%232", None);
+    assert_eq!((status, &item["title"]), (200, &json!("walk dog")));
+
+    let (status, _) = server.send("DELETE", "/items/buy%20milk", None);
+    assert_eq!(status, 204);
+    let (status, body) = server.send("GET", "/items/buy%20milk", None);
+    assert_eq!(status, 404);
+    assert!(body["error"].as_str().unwrap().contains("not found"));
+
+    let (status, item) = server.send("POST", "/items", Some(json!({ "title": "  call mum " })));
+    assert_eq!((status, &item["title"]), (201, &json!("call mum")));
+}
+
+#[test]
+fn patch_enforces_transitions_and_renames() {
+    let server = Server::start("patch");
+    server.send("POST", "/items", Some(json!({ "title": "report" })));
+
+    let (status, body) = server.send("PATCH", "/items/report", Some(json!({ "status": "archived" })));
+    assert_eq!(status, 409);
+    assert!(body["error"].as_str().unwrap().contains("PENDING to ARCHIVED"));
+
+    let (status, item) = server.send("PATCH", "/items/report", Some(json!({ "status": "done", "title": "q3 report" })));
+    assert_eq!(status, 200);
+    assert_eq!(item["title"], "q3 report");
+    assert_eq!(item["status"], "DONE");
+    assert!(item["completed_at"].is_string());
+
+    // A refused rename keeps the status change that came with it out of the store too.
+    server.send("POST", "/items", Some(json!({ "title": "memo" })));
+    let (status, _) = server.send("PATCH", "/items/memo", Some(json!({ "status": "in-progress", "title": "q3 report" })));
+    assert_eq!(status, 409);
+    let (_, item) = server.send("GET", "/items/memo", None);
+    assert_eq!(item["status"], "PENDING");
+
+    let (status, _) = server.send("PATCH", "/items/q3%20report", Some(json!({ "title": "#1" })));
+    assert_eq!(status, 400);
+    let (status, _) = server.send("PATCH", "/items/q3%20report", Some(json!({ "status": "sideways" })));
+    assert_eq!(status, 400);
+    let (status, _) = server.send("PATCH", "/items/q3%20report", Some(json!({ "colour": "red" })));
+    assert_eq!(status, 400);
+}